@@ -0,0 +1,53 @@
+use alx::alias::Alias;
+use alx::alias::store::AliasStore;
+use alx::shell::ShellType;
+use alx::sync::render_shell_aliases;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+const SIZE: usize = 20_000;
+
+// A store shaped like a large real-world one: most aliases grouped, some
+// with descriptions and tags
+fn large_store() -> AliasStore {
+    let aliases = (0..SIZE)
+        .map(|i| {
+            let mut alias = Alias::new(format!("a{}", i), format!("git log --oneline -n {}", i))
+                .with_group(format!("group{}", i % 50));
+            if i % 3 == 0 {
+                alias = alias.with_description(format!("Show the last {} commits", i));
+            }
+            if i % 5 == 0 {
+                alias = alias.with_tags(vec!["git".to_string(), "log".to_string()]);
+            }
+            alias
+        })
+        .collect();
+    AliasStore::from_aliases(aliases)
+}
+
+fn bench_store(c: &mut Criterion) {
+    let store = large_store();
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("aliases.toml");
+    store.save(&path).unwrap();
+
+    c.bench_function("load", |b| b.iter(|| AliasStore::load(black_box(&path))));
+    c.bench_function("save", |b| b.iter(|| store.save(black_box(&path))));
+    c.bench_function("groups", |b| b.iter(|| black_box(&store).groups().len()));
+    c.bench_function("search", |b| {
+        b.iter(|| black_box(&store).search("commits").len())
+    });
+}
+
+fn bench_render(c: &mut Criterion) {
+    let store = large_store();
+    for shell_type in [ShellType::Bash, ShellType::Zsh, ShellType::Fish] {
+        c.bench_function(&format!("render_{}", shell_type.as_str()), |b| {
+            b.iter(|| render_shell_aliases(black_box(&store), shell_type))
+        });
+    }
+}
+
+criterion_group!(benches, bench_store, bench_render);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+use crate::alias::Alias;
+use crate::alias::store::AliasStore;
+
+#[derive(Debug, PartialEq)]
+pub enum Change<'a> {
+    Added(&'a Alias),
+    Removed(&'a Alias),
+    Changed { from: &'a Alias, to: &'a Alias },
+}
+
+// Changes needed to turn `from` into `to`. Timestamps are ignored so that
+// re-adding an identical alias doesn't show up as a change.
+pub fn diff_stores<'a>(from: &'a AliasStore, to: &'a AliasStore) -> Vec<Change<'a>> {
+    let mut changes = Vec::new();
+
+    for old in &from.aliases {
+        match to.aliases.iter().find(|a| a.name == old.name) {
+            None => changes.push(Change::Removed(old)),
+            Some(new) if !same_definition(old, new) => {
+                changes.push(Change::Changed { from: old, to: new })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for new in &to.aliases {
+        if !from.aliases.iter().any(|a| a.name == new.name) {
+            changes.push(Change::Added(new));
+        }
+    }
+
+    changes
+}
+
+fn same_definition(a: &Alias, b: &Alias) -> bool {
+    a.command == b.command
+        && a.description == b.description
+        && a.group == b.group
+        && a.tags == b.tags
+        && a.enabled == b.enabled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(name: &str, command: &str) -> Alias {
+        Alias::new(name.to_string(), command.to_string())
+    }
+
+    #[test]
+    fn test_diff_stores() {
+        let from = AliasStore::from_aliases(vec![
+            alias("gs", "git status"),
+            alias("ll", "ls -la"),
+            alias("old", "echo old"),
+        ]);
+        let to = AliasStore::from_aliases(vec![
+            alias("gs", "git status"),
+            alias("ll", "ls -lah"),
+            alias("new", "echo new"),
+        ]);
+
+        let changes = diff_stores(&from, &to);
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(changes[0], Change::Changed { to, .. } if to.command == "ls -lah"));
+        assert!(matches!(changes[1], Change::Removed(a) if a.name == "old"));
+        assert!(matches!(changes[2], Change::Added(a) if a.name == "new"));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::alias::Alias;
+use crate::error::{AlxError, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use regex::Regex;
+
+/// Conditions an alias must all meet, built up one at a time.
+///
+/// ```
+/// use alx::alias::Alias;
+/// use alx::alias::filter::Filter;
+/// use alx::alias::store::AliasStore;
+///
+/// let store = AliasStore::from_aliases(vec![
+///     Alias::new("gs".to_string(), "git status".to_string()).with_group("git".to_string()),
+///     Alias::new("gp".to_string(), "git push".to_string()).with_group("git".to_string()),
+///     Alias::new("ll".to_string(), "ls -la".to_string()),
+/// ]);
+///
+/// let filter = Filter::new()
+///     .with_group("git")
+///     .with_command_regex("push|pull")
+///     .unwrap();
+/// let names: Vec<&str> = store.filter(&filter).iter().map(|a| a.name.as_str()).collect();
+/// assert_eq!(names, vec!["gp"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    group: Option<String>,
+    tags: Vec<String>,
+    excluded_groups: Vec<String>,
+    excluded_tags: Vec<String>,
+    enabled: Option<bool>,
+    keyword: Option<String>,
+    name_regex: Option<Regex>,
+    command_regex: Option<Regex>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    updated_after: Option<DateTime<Utc>>,
+    updated_before: Option<DateTime<Utc>>,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
+    // Every tag given must be present
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    // Leave out the aliases in `group`
+    pub fn without_group(mut self, group: &str) -> Self {
+        self.excluded_groups.push(group.to_string());
+        self
+    }
+
+    // Leave out the aliases tagged `tag`
+    pub fn without_tag(mut self, tag: &str) -> Self {
+        self.excluded_tags.push(tag.to_string());
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    // Case-insensitive text in the name, command or description
+    pub fn with_keyword(mut self, keyword: &str) -> Self {
+        self.keyword = Some(keyword.to_lowercase());
+        self
+    }
+
+    pub fn with_name_regex(mut self, pattern: &str) -> Result<Self> {
+        self.name_regex = Some(regex(pattern)?);
+        Ok(self)
+    }
+
+    pub fn with_command_regex(mut self, pattern: &str) -> Result<Self> {
+        self.command_regex = Some(regex(pattern)?);
+        Ok(self)
+    }
+
+    pub fn with_created_after(mut self, time: DateTime<Utc>) -> Self {
+        self.created_after = Some(time);
+        self
+    }
+
+    pub fn with_created_before(mut self, time: DateTime<Utc>) -> Self {
+        self.created_before = Some(time);
+        self
+    }
+
+    pub fn with_updated_after(mut self, time: DateTime<Utc>) -> Self {
+        self.updated_after = Some(time);
+        self
+    }
+
+    pub fn with_updated_before(mut self, time: DateTime<Utc>) -> Self {
+        self.updated_before = Some(time);
+        self
+    }
+
+    // Add a condition written as on the command line: group=NAME, tag=NAME,
+    // group!=NAME, tag!=NAME, enabled=true|false, name~REGEX, command~REGEX,
+    // or created/updated followed by <, <=, > or >= and a YYYY-MM-DD date.
+    // A date starts at midnight, so <= takes in the whole day and > is >=.
+    pub fn with_condition(self, condition: &str) -> Result<Self> {
+        let invalid = || {
+            AlxError::ConfigError(format!(
+                "Invalid condition: {} (expected e.g. group=git, tag!=work, enabled=false, \
+                 command~REGEX or created<2024-01-01)",
+                condition
+            ))
+        };
+        let at = condition.find(['=', '~', '<', '>']).ok_or_else(invalid)?;
+        let or_equal =
+            matches!(&condition[at..at + 1], "<" | ">") && condition[at + 1..].starts_with('=');
+        let end = at + 1 + usize::from(or_equal);
+        let (key, value) = (condition[..at].trim(), condition[end..].trim());
+        let (key, operator) = match key.strip_suffix('!') {
+            Some(key) if &condition[at..end] == "=" => (key.trim(), "!="),
+            _ => (key, &condition[at..end]),
+        };
+
+        match (key, operator) {
+            ("group", "=") => Ok(self.with_group(value)),
+            ("tag", "=") => Ok(self.with_tag(value)),
+            ("group", "!=") => Ok(self.without_group(value)),
+            ("tag", "!=") => Ok(self.without_tag(value)),
+            ("enabled", "=") => match value {
+                "true" => Ok(self.with_enabled(true)),
+                "false" => Ok(self.with_enabled(false)),
+                _ => Err(invalid()),
+            },
+            ("name", "~") => self.with_name_regex(value),
+            ("command", "~") => self.with_command_regex(value),
+            ("created", "<") => Ok(self.with_created_before(date(value)?)),
+            ("created", "<=") => Ok(self.with_created_before(day_after(value)?)),
+            ("created", ">" | ">=") => Ok(self.with_created_after(date(value)?)),
+            ("updated", "<") => Ok(self.with_updated_before(date(value)?)),
+            ("updated", "<=") => Ok(self.with_updated_before(day_after(value)?)),
+            ("updated", ">" | ">=") => Ok(self.with_updated_after(date(value)?)),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn matches(&self, alias: &Alias) -> bool {
+        let keyword_matches = |keyword: &String| {
+            alias.name.to_lowercase().contains(keyword)
+                || alias.command.to_lowercase().contains(keyword)
+                || alias
+                    .description
+                    .as_ref()
+                    .is_some_and(|d| d.to_lowercase().contains(keyword))
+        };
+
+        self.group
+            .as_ref()
+            .is_none_or(|g| alias.group.as_ref() == Some(g))
+            && self.tags.iter().all(|t| alias.has_tag(t))
+            && !alias
+                .group
+                .as_ref()
+                .is_some_and(|g| self.excluded_groups.contains(g))
+            && !self.excluded_tags.iter().any(|t| alias.has_tag(t))
+            && self.enabled.is_none_or(|e| alias.enabled == e)
+            && self.keyword.as_ref().is_none_or(keyword_matches)
+            && self
+                .name_regex
+                .as_ref()
+                .is_none_or(|r| r.is_match(&alias.name))
+            && self
+                .command_regex
+                .as_ref()
+                .is_none_or(|r| r.is_match(&alias.command))
+            && self.created_after.is_none_or(|t| alias.created_at >= t)
+            && self.created_before.is_none_or(|t| alias.created_at < t)
+            && self.updated_after.is_none_or(|t| alias.updated_at >= t)
+            && self.updated_before.is_none_or(|t| alias.updated_at < t)
+    }
+}
+
+fn regex(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern)
+        .map_err(|e| AlxError::ConfigError(format!("Invalid regex '{}': {}", pattern, e)))
+}
+
+// The start of a YYYY-MM-DD date in local time
+fn date(value: &str) -> Result<DateTime<Utc>> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| {
+            AlxError::ConfigError(format!("Invalid date: {} (expected YYYY-MM-DD)", value))
+        })
+}
+
+// The start of the day after a YYYY-MM-DD date
+fn day_after(value: &str) -> Result<DateTime<Utc>> {
+    let next = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.succ_opt())
+        .map_or_else(|| value.to_string(), |d| d.format("%Y-%m-%d").to_string());
+    date(&next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_matches() {
+        let mut old = Alias::new("gl".to_string(), "git log".to_string())
+            .with_group("git".to_string())
+            .with_tags(vec!["work".to_string()]);
+        old.created_at = Utc::now() - Duration::days(400);
+        old.enabled = false;
+        let new = Alias::new("dps".to_string(), "docker ps".to_string())
+            .with_description("List containers".to_string());
+
+        let filter = |conditions: &[&str]| {
+            conditions
+                .iter()
+                .try_fold(Filter::new(), |f, c| f.with_condition(c))
+                .unwrap()
+        };
+        assert!(filter(&["group=git", "tag=work", "enabled=false"]).matches(&old));
+        assert!(!filter(&["group=git", "tag=home"]).matches(&old));
+        assert!(!filter(&["group!=git"]).matches(&old));
+        assert!(filter(&["group!=git"]).matches(&new));
+        assert!(!filter(&["tag != work"]).matches(&old));
+        assert!(!Filter::new().without_tag("work").matches(&old));
+        assert!(filter(&["name~^g", "command~log$"]).matches(&old));
+        assert!(!filter(&["name~^g"]).matches(&new));
+
+        let year_ago = (Utc::now() - Duration::days(365)).format("%Y-%m-%d");
+        let before = format!("created<{}", year_ago);
+        assert!(filter(&[&before]).matches(&old));
+        assert!(!filter(&[&before]).matches(&new));
+        let today = Local::now().format("%Y-%m-%d");
+        assert!(filter(&[&format!("created>={}", today)]).matches(&new));
+        assert!(filter(&[&format!("created<={}", today)]).matches(&new));
+        assert!(!filter(&[&format!("created<{}", today)]).matches(&new));
+
+        assert!(Filter::new().with_keyword("CONTAINERS").matches(&new));
+        assert!(Filter::new().with_condition("group").is_err());
+        assert!(Filter::new().with_condition("name!~g").is_err());
+        assert!(Filter::new().with_condition("name~(").is_err());
+        assert!(Filter::new().with_condition("created<soon").is_err());
+        assert!(Filter::new().with_condition("created<=soon").is_err());
+        assert!(Filter::new().with_condition("name>=a").is_err());
+    }
+}
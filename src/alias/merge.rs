@@ -0,0 +1,299 @@
+use crate::alias::Alias;
+use crate::alias::store::AliasStore;
+use crate::error::{AlxError, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    Ours,
+    Theirs,
+    Newest,
+}
+
+impl MergeStrategy {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "ours" => Ok(MergeStrategy::Ours),
+            "theirs" => Ok(MergeStrategy::Theirs),
+            "newest" => Ok(MergeStrategy::Newest),
+            _ => Err(AlxError::ConfigError(format!(
+                "Unknown merge strategy: {}",
+                name
+            ))),
+        }
+    }
+
+    // The version of a conflicting alias this strategy keeps
+    pub fn resolve<'a>(
+        &self,
+        ours: Option<&'a Alias>,
+        theirs: Option<&'a Alias>,
+    ) -> Option<&'a Alias> {
+        match (self, ours, theirs) {
+            (MergeStrategy::Ours, o, _) => o,
+            (MergeStrategy::Theirs, _, t) => t,
+            // A deletion never beats an edit
+            (MergeStrategy::Newest, Some(o), Some(t)) => {
+                if t.updated_at > o.updated_at {
+                    Some(t)
+                } else {
+                    Some(o)
+                }
+            }
+            (MergeStrategy::Newest, o, t) => o.or(t),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    pub conflicts: Vec<String>,
+}
+
+// An alias both sides changed, each in its own way. A side that removed it
+// has None.
+#[derive(Debug, Clone, Copy)]
+pub struct Conflict<'a> {
+    pub name: &'a str,
+    pub ours: Option<&'a Alias>,
+    pub theirs: Option<&'a Alias>,
+}
+
+impl Conflict<'_> {
+    // Whether the sides disagree on what the alias runs, rather than only on
+    // its description, group, tags or timestamps
+    pub fn changes_command(&self) -> bool {
+        self.ours.map(|a| &a.command) != self.theirs.map(|a| &a.command)
+    }
+}
+
+// Three-way merge of two stores that share a common ancestor. An alias only
+// counts as a conflict when both sides changed it differently; those are
+// resolved with the given strategy.
+pub fn merge_stores(
+    base: &AliasStore,
+    ours: &AliasStore,
+    theirs: &AliasStore,
+    strategy: MergeStrategy,
+) -> (AliasStore, MergeReport) {
+    merge_stores_with(base, ours, theirs, |conflict| {
+        strategy.resolve(conflict.ours, conflict.theirs)
+    })
+}
+
+// The conflicts `merge_stores` would have to resolve, in merge order
+pub fn conflicts<'a>(
+    base: &'a AliasStore,
+    ours: &'a AliasStore,
+    theirs: &'a AliasStore,
+) -> Vec<Conflict<'a>> {
+    merge_order(base, ours, theirs)
+        .into_iter()
+        .filter_map(|name| match side(base, ours, theirs, name) {
+            Side::Conflict(conflict) => Some(conflict),
+            _ => None,
+        })
+        .collect()
+}
+
+// Like `merge_stores`, with `resolve` picking the version of each conflict
+// to keep, or None to leave the alias out
+pub fn merge_stores_with<'a>(
+    base: &'a AliasStore,
+    ours: &'a AliasStore,
+    theirs: &'a AliasStore,
+    mut resolve: impl FnMut(&Conflict<'a>) -> Option<&'a Alias>,
+) -> (AliasStore, MergeReport) {
+    let mut merged = AliasStore::new();
+    let mut report = MergeReport::default();
+
+    for name in merge_order(base, ours, theirs) {
+        let resolved = match side(base, ours, theirs, name) {
+            Side::Ours(alias) => alias,
+            Side::Theirs(alias) => {
+                match (find(ours, name), alias) {
+                    (None, Some(_)) => report.added.push(name.to_string()),
+                    (Some(_), None) => report.removed.push(name.to_string()),
+                    _ => report.updated.push(name.to_string()),
+                }
+                alias
+            }
+            Side::Conflict(conflict) => {
+                report.conflicts.push(name.to_string());
+                resolve(&conflict)
+            }
+        };
+
+        if let Some(alias) = resolved {
+            merged.aliases.push(alias.clone());
+        }
+    }
+
+    // Pack records are metadata only; keep ours and add any we don't know
+    merged.packs = ours.packs.clone();
+    for pack in &theirs.packs {
+        if !merged.packs.iter().any(|p| p.name == pack.name) {
+            merged.packs.push(pack.clone());
+        }
+    }
+
+    (merged, report)
+}
+
+// Our order first, then anything only the other side knows about
+fn merge_order<'a>(
+    base: &'a AliasStore,
+    ours: &'a AliasStore,
+    theirs: &'a AliasStore,
+) -> Vec<&'a str> {
+    let mut names: Vec<&str> = ours.aliases.iter().map(|a| a.name.as_str()).collect();
+    for alias in theirs.aliases.iter().chain(base.aliases.iter()) {
+        if !names.contains(&alias.name.as_str()) {
+            names.push(&alias.name);
+        }
+    }
+    names
+}
+
+// Which side's version of `name` the merge takes without asking
+enum Side<'a> {
+    Ours(Option<&'a Alias>),
+    Theirs(Option<&'a Alias>),
+    Conflict(Conflict<'a>),
+}
+
+fn side<'a>(
+    base: &'a AliasStore,
+    ours: &'a AliasStore,
+    theirs: &'a AliasStore,
+    name: &'a str,
+) -> Side<'a> {
+    let b = find(base, name);
+    let o = find(ours, name);
+    let t = find(theirs, name);
+
+    if o == t || t == b {
+        Side::Ours(o)
+    } else if o == b {
+        Side::Theirs(t)
+    } else {
+        Side::Conflict(Conflict {
+            name,
+            ours: o,
+            theirs: t,
+        })
+    }
+}
+
+fn find<'a>(store: &'a AliasStore, name: &str) -> Option<&'a Alias> {
+    store.aliases.iter().find(|a| a.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn store(aliases: &[&Alias]) -> AliasStore {
+        AliasStore::from_aliases(aliases.iter().map(|a| (*a).clone()).collect())
+    }
+
+    fn names(store: &AliasStore) -> Vec<&str> {
+        store.aliases.iter().map(|a| a.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_non_conflicting_changes() {
+        let ll = Alias::new("ll".to_string(), "ls -la".to_string());
+        let gs = Alias::new("gs".to_string(), "git status".to_string());
+        let gp = Alias::new("gp".to_string(), "git push".to_string());
+
+        let base = store(&[&ll, &gs]);
+        let ours = store(&[&ll, &gs, &gp]);
+        let theirs = store(&[&ll]);
+
+        let (merged, report) = merge_stores(&base, &ours, &theirs, MergeStrategy::Ours);
+
+        assert_eq!(names(&merged), vec!["ll", "gp"]);
+        assert_eq!(report.removed, vec!["gs"]);
+        assert!(report.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_conflict_strategies() {
+        let base_gs = Alias::new("gs".to_string(), "git status".to_string());
+        let mut our_gs = base_gs.clone();
+        our_gs.update_command("git status -sb".to_string());
+        let mut their_gs = base_gs.clone();
+        their_gs.command = "git status --short".to_string();
+        their_gs.updated_at = our_gs.updated_at + Duration::seconds(10);
+
+        let base = store(&[&base_gs]);
+        let ours = store(&[&our_gs]);
+        let theirs = store(&[&their_gs]);
+
+        let (merged, report) = merge_stores(&base, &ours, &theirs, MergeStrategy::Ours);
+        assert_eq!(merged.aliases[0].command, "git status -sb");
+        assert_eq!(report.conflicts, vec!["gs"]);
+
+        let (merged, _) = merge_stores(&base, &ours, &theirs, MergeStrategy::Theirs);
+        assert_eq!(merged.aliases[0].command, "git status --short");
+
+        let (merged, _) = merge_stores(&base, &ours, &theirs, MergeStrategy::Newest);
+        assert_eq!(merged.aliases[0].command, "git status --short");
+    }
+
+    #[test]
+    fn test_newest_keeps_edit_over_delete() {
+        let base_gs = Alias::new("gs".to_string(), "git status".to_string());
+        let mut our_gs = base_gs.clone();
+        our_gs.update_command("git status -sb".to_string());
+
+        let base = store(&[&base_gs]);
+        let ours = store(&[&our_gs]);
+        let theirs = store(&[]);
+
+        let (merged, report) = merge_stores(&base, &ours, &theirs, MergeStrategy::Newest);
+        assert_eq!(names(&merged), vec!["gs"]);
+        assert_eq!(report.conflicts, vec!["gs"]);
+    }
+
+    #[test]
+    fn test_merge_without_common_ancestor() {
+        let ll = Alias::new("ll".to_string(), "ls -la".to_string());
+        let gs = Alias::new("gs".to_string(), "git status".to_string());
+        let mut their_gs = gs.clone().with_description("Status".to_string());
+        their_gs.updated_at = gs.updated_at + Duration::seconds(10);
+        let gp = Alias::new("gp".to_string(), "git push".to_string());
+        let mut their_gp = gp.clone();
+        their_gp.command = "git push --force-with-lease".to_string();
+
+        let base = AliasStore::new();
+        let ours = store(&[&ll, &gs, &gp]);
+        let theirs = store(&[&their_gs, &their_gp]);
+
+        let found = conflicts(&base, &ours, &theirs);
+        let changed: Vec<(&str, bool)> = found
+            .iter()
+            .map(|c| (c.name, c.changes_command()))
+            .collect();
+        assert_eq!(changed, vec![("gs", false), ("gp", true)]);
+
+        let (merged, report) = merge_stores_with(&base, &ours, &theirs, |c| c.theirs);
+        assert_eq!(names(&merged), vec!["ll", "gs", "gp"]);
+        assert_eq!(merged.aliases[2].command, "git push --force-with-lease");
+        assert_eq!(report.conflicts, vec!["gs", "gp"]);
+        assert!(report.removed.is_empty());
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!(
+            MergeStrategy::parse("theirs").unwrap(),
+            MergeStrategy::Theirs
+        );
+        assert!(MergeStrategy::parse("both").is_err());
+    }
+}
@@ -1,19 +1,53 @@
+pub mod diff;
+pub mod filter;
+pub mod merge;
 pub mod store;
 pub mod validator;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+// A link runs another alias: its command is the target's name, and the
+// target's command is written to the shell files in its place
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AliasKind {
+    #[default]
+    Command,
+    Link,
+}
+
+impl AliasKind {
+    fn is_command(&self) -> bool {
+        *self == AliasKind::Command
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Alias {
     pub name: String,
     pub command: String,
     pub description: Option<String>,
     pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pack: Option<String>,
+    #[serde(default, skip_serializing_if = "AliasKind::is_command")]
+    pub kind: AliasKind,
+    // Removing it or changing its command needs --force and a confirmation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+fn default_enabled() -> bool {
+    true
+}
+
 impl Alias {
     pub fn new(name: String, command: String) -> Self {
         let now = Utc::now();
@@ -22,11 +56,27 @@ impl Alias {
             command,
             description: None,
             group: None,
+            tags: Vec::new(),
+            enabled: true,
+            pack: None,
+            kind: AliasKind::Command,
+            protected: false,
             created_at: now,
             updated_at: now,
         }
     }
 
+    pub fn link(name: String, target: String) -> Self {
+        Self {
+            kind: AliasKind::Link,
+            ..Self::new(name, target)
+        }
+    }
+
+    pub fn is_link(&self) -> bool {
+        self.kind == AliasKind::Link
+    }
+
     pub fn with_description(mut self, description: String) -> Self {
         self.description = Some(description);
         self
@@ -37,6 +87,15 @@ impl Alias {
         self
     }
 
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
     pub fn update_command(&mut self, command: String) {
         self.command = command;
         self.updated_at = Utc::now();
@@ -54,6 +113,8 @@ mod tests {
         assert_eq!(alias.command, "ls -la");
         assert!(alias.description.is_none());
         assert!(alias.group.is_none());
+        assert!(alias.tags.is_empty());
+        assert!(alias.enabled);
     }
 
     #[test]
@@ -69,4 +130,39 @@ mod tests {
             Alias::new("gs".to_string(), "git status".to_string()).with_group("git".to_string());
         assert_eq!(alias.group, Some("git".to_string()));
     }
+
+    #[test]
+    fn test_alias_with_tags() {
+        let alias = Alias::new("gs".to_string(), "git status".to_string())
+            .with_tags(vec!["work".to_string()]);
+        assert!(alias.has_tag("work"));
+        assert!(!alias.has_tag("home"));
+    }
+
+    #[test]
+    fn test_deserialize_without_tags_or_enabled() {
+        let alias: Alias = toml::from_str(
+            r#"
+name = "ll"
+command = "ls -la"
+created_at = "2024-01-01T00:00:00Z"
+updated_at = "2024-01-01T00:00:00Z"
+"#,
+        )
+        .unwrap();
+        assert!(alias.tags.is_empty());
+        assert!(alias.enabled);
+        assert!(!alias.is_link());
+    }
+
+    #[test]
+    fn test_link_kind_round_trip() {
+        let link = Alias::link("gst".to_string(), "gs".to_string());
+        let toml = toml::to_string(&link).unwrap();
+        assert!(toml.contains("kind = \"link\""));
+        assert!(toml::from_str::<Alias>(&toml).unwrap().is_link());
+
+        let plain = toml::to_string(&Alias::new("gs".to_string(), "git status".to_string()));
+        assert!(!plain.unwrap().contains("kind"));
+    }
 }
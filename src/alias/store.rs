@@ -1,29 +1,127 @@
 use crate::alias::Alias;
+use crate::alias::filter::Filter;
 use crate::error::{AlxError, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
-#[derive(Debug, Serialize, Deserialize)]
+// Groups for well-known programs, used by infer_group when no alias running
+// the program has a group yet
+const KNOWN_GROUPS: &[(&str, &[&str])] = &[
+    ("git", &["git", "gh", "tig", "lazygit"]),
+    ("docker", &["docker", "docker-compose", "podman"]),
+    (
+        "k8s",
+        &[
+            "kubectl", "helm", "k9s", "kubectx", "kubens", "minikube", "kind",
+        ],
+    ),
+    (
+        "node",
+        &["npm", "npx", "yarn", "pnpm", "node", "bun", "deno"],
+    ),
+    ("rust", &["cargo", "rustup"]),
+    (
+        "python",
+        &["python", "python3", "pip", "pip3", "poetry", "uv"],
+    ),
+    ("go", &["go"]),
+    ("terraform", &["terraform", "tofu"]),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InstalledPack {
+    pub name: String,
+    pub version: String,
+    pub group: String,
+    pub installed_at: DateTime<Utc>,
+}
+
+// A group created on its own, with a description. Groups that aliases merely
+// name have no entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Group {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Group {
+    pub fn new(name: String, description: Option<String>) -> Self {
+        Self {
+            name,
+            description,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AliasStore {
     pub aliases: Vec<Alias>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packs: Vec<InstalledPack>,
+    #[serde(default, rename = "groups", skip_serializing_if = "Vec::is_empty")]
+    pub group_entries: Vec<Group>,
+    // Match names regardless of case, from the ignore_case setting
+    #[serde(skip)]
+    pub ignore_case: bool,
 }
 
 impl AliasStore {
     pub fn new() -> Self {
+        Self::from_aliases(Vec::new())
+    }
+
+    pub fn from_aliases(aliases: Vec<Alias>) -> Self {
         Self {
-            aliases: Vec::new(),
+            aliases,
+            packs: Vec::new(),
+            group_entries: Vec::new(),
+            ignore_case: false,
         }
     }
 
+    pub fn with_ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    fn same_name(&self, a: &str, b: &str) -> bool {
+        a == b || (self.ignore_case && a.to_lowercase() == b.to_lowercase())
+    }
+
+    // Where the alias called `name` is, preferring an exact match over one
+    // that only matches ignoring case
+    fn position(&self, name: &str) -> Option<usize> {
+        self.aliases
+            .iter()
+            .position(|a| a.name == name)
+            .or_else(|| {
+                self.aliases
+                    .iter()
+                    .position(|a| self.same_name(&a.name, name))
+            })
+    }
+
     pub fn load(path: &PathBuf) -> Result<Self> {
         if !path.exists() {
             return Ok(Self::new());
         }
 
-        let content = fs::read_to_string(path)?;
-        let store: AliasStore = toml::from_str(&content)?;
-        Ok(store)
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| AlxError::ConfigError(e.to_string()))
     }
 
     pub fn save(&self, path: &PathBuf) -> Result<()> {
@@ -31,9 +129,7 @@ impl AliasStore {
             fs::create_dir_all(parent)?;
         }
 
-        let content =
-            toml::to_string_pretty(self).map_err(|e| AlxError::ConfigError(e.to_string()))?;
-        fs::write(path, content)?;
+        fs::write(path, self.to_toml()?)?;
         Ok(())
     }
 
@@ -47,58 +143,405 @@ impl AliasStore {
 
     pub fn remove(&mut self, name: &str) -> Result<Alias> {
         let index = self
-            .aliases
-            .iter()
-            .position(|a| a.name == name)
+            .position(name)
             .ok_or_else(|| AlxError::AliasNotFound(name.to_string()))?;
 
         Ok(self.aliases.remove(index))
     }
 
+    /// Look up an alias by name.
+    ///
+    /// ```
+    /// use alx::alias::Alias;
+    /// use alx::alias::store::AliasStore;
+    ///
+    /// let mut store = AliasStore::new();
+    /// store.add(Alias::new("gs".to_string(), "git status".to_string())).unwrap();
+    ///
+    /// assert_eq!(store.get("gs").unwrap().command, "git status");
+    /// assert!(store.get("gp").is_none());
+    /// ```
+    pub fn get(&self, name: &str) -> Option<&Alias> {
+        self.position(name).map(|i| &self.aliases[i])
+    }
+
     pub fn get_mut(&mut self, name: &str) -> Option<&mut Alias> {
-        self.aliases.iter_mut().find(|a| a.name == name)
+        self.position(name).map(|i| &mut self.aliases[i])
     }
 
     pub fn exists(&self, name: &str) -> bool {
-        self.aliases.iter().any(|a| a.name == name)
+        self.position(name).is_some()
+    }
+
+    // Names that are the same ignoring case, such as GS and gs, in store
+    // order. These conflict once ignore_case is on.
+    pub fn case_conflicts(&self) -> Vec<(&str, &str)> {
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        let mut conflicts = Vec::new();
+        for alias in &self.aliases {
+            match seen.get(&alias.name.to_lowercase()) {
+                Some(first) => conflicts.push((*first, alias.name.as_str())),
+                None => {
+                    seen.insert(alias.name.to_lowercase(), &alias.name);
+                }
+            }
+        }
+        conflicts
     }
 
     pub fn list(&self) -> &[Alias] {
         &self.aliases
     }
 
+    pub fn filter(&self, filter: &Filter) -> Vec<&Alias> {
+        self.aliases.iter().filter(|a| filter.matches(a)).collect()
+    }
+
     pub fn list_by_group(&self, group: &str) -> Vec<&Alias> {
+        self.filter(&Filter::new().with_group(group))
+    }
+
+    /// Case-insensitive search over names, commands and descriptions.
+    ///
+    /// ```
+    /// use alx::alias::Alias;
+    /// use alx::alias::store::AliasStore;
+    ///
+    /// let store = AliasStore::from_aliases(vec![
+    ///     Alias::new("gs".to_string(), "git status".to_string()),
+    ///     Alias::new("ll".to_string(), "ls -la".to_string()),
+    /// ]);
+    /// assert_eq!(store.search("GIT").len(), 1);
+    /// ```
+    pub fn search(&self, keyword: &str) -> Vec<&Alias> {
+        self.filter(&Filter::new().with_keyword(keyword))
+    }
+
+    // Find aliases whose command contains the keyword, returning the byte
+    // offsets of every match so callers can highlight them
+    pub fn grep(&self, keyword: &str, ignore_case: bool) -> Vec<(&Alias, Vec<usize>)> {
+        if keyword.is_empty() {
+            return Vec::new();
+        }
+        let ignore_case = ignore_case || self.ignore_case;
+
+        let needle = if ignore_case {
+            keyword.to_ascii_lowercase()
+        } else {
+            keyword.to_string()
+        };
+
         self.aliases
             .iter()
-            .filter(|a| a.group.as_deref() == Some(group))
+            .filter_map(|a| {
+                let haystack = if ignore_case {
+                    a.command.to_ascii_lowercase()
+                } else {
+                    a.command.clone()
+                };
+                let offsets: Vec<usize> = haystack.match_indices(&needle).map(|(i, _)| i).collect();
+                if offsets.is_empty() {
+                    None
+                } else {
+                    Some((a, offsets))
+                }
+            })
             .collect()
     }
 
-    pub fn search(&self, keyword: &str) -> Vec<&Alias> {
-        let keyword_lower = keyword.to_lowercase();
+    // Aliases whose name, command or a word of the command is a near miss for
+    // `query`, for "did you mean" hints. Only the closest ones are returned,
+    // as git does for mistyped commands.
+    pub fn similar(&self, query: &str) -> Vec<&Alias> {
+        let query = query.to_lowercase();
+        // Roughly one typo per three characters
+        let allowed = (query.chars().count() / 3).max(1);
+
+        let mut best = Vec::new();
+        let mut best_distance = allowed;
+        for alias in &self.aliases {
+            let command = alias.command.to_lowercase();
+            let distance = std::iter::once(alias.name.to_lowercase().as_str())
+                .chain([command.as_str()])
+                .chain(command.split_whitespace())
+                .map(|candidate| edit_distance(&query, candidate))
+                .min()
+                .unwrap_or(usize::MAX);
+            if distance < best_distance {
+                best_distance = distance;
+                best.clear();
+            }
+            if distance == best_distance {
+                best.push(alias);
+            }
+        }
+        best
+    }
+
+    pub fn list_by_pack(&self, pack: &str) -> Vec<&Alias> {
         self.aliases
             .iter()
-            .filter(|a| {
-                a.name.to_lowercase().contains(&keyword_lower)
-                    || a.command.to_lowercase().contains(&keyword_lower)
-                    || a.description
-                        .as_ref()
-                        .map(|d| d.to_lowercase().contains(&keyword_lower))
-                        .unwrap_or(false)
+            .filter(|a| a.pack.as_deref() == Some(pack))
+            .collect()
+    }
+
+    pub fn find_pack(&self, name: &str) -> Option<&InstalledPack> {
+        self.packs.iter().find(|p| p.name == name)
+    }
+
+    // Links from outside the pack to its aliases, as (link, target) pairs
+    pub fn pack_links(&self, name: &str) -> Vec<(String, String)> {
+        self.list_by_pack(name)
+            .iter()
+            .flat_map(|target| {
+                self.links_to(&target.name)
+                    .into_iter()
+                    .filter(|link| {
+                        self.get(link)
+                            .is_some_and(|l| l.pack.as_deref() != Some(name))
+                    })
+                    .map(|link| (link.to_string(), target.name.clone()))
             })
             .collect()
     }
 
-    pub fn groups(&self) -> Vec<String> {
-        let mut groups: Vec<String> = self
+    // Remove a pack record and every alias it installed
+    pub fn remove_pack(&mut self, name: &str) -> Option<InstalledPack> {
+        let index = self.packs.iter().position(|p| p.name == name)?;
+        self.aliases.retain(|a| a.pack.as_deref() != Some(name));
+        Some(self.packs.remove(index))
+    }
+
+    // Every group, whether named by an alias or created on its own
+    pub fn groups(&self) -> Vec<&str> {
+        let mut groups: Vec<&str> = self
             .aliases
             .iter()
-            .filter_map(|a| a.group.clone())
+            .filter_map(|a| a.group.as_deref())
+            .chain(self.group_entries.iter().map(|g| g.name.as_str()))
             .collect();
         groups.sort();
         groups.dedup();
         groups
     }
+
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.group_entries.iter().find(|g| g.name == name)
+    }
+
+    pub fn add_group(&mut self, group: Group) -> Result<()> {
+        if self.group(&group.name).is_some() {
+            return Err(AlxError::ConfigError(format!(
+                "Group '{}' already exists",
+                group.name
+            )));
+        }
+        self.group_entries.push(group);
+        Ok(())
+    }
+
+    // Give groups that differ only in case a single spelling: lowercase, or
+    // else the one most aliases use (the earliest on a tie). Returns each
+    // rename with the number of aliases moved.
+    pub fn normalize_groups(&mut self, lowercase: bool) -> Vec<(String, String, usize)> {
+        let mut spellings: Vec<(String, usize)> = Vec::new();
+        for group in self.aliases.iter().filter_map(|a| a.group.as_deref()) {
+            match spellings.iter_mut().find(|(g, _)| g == group) {
+                Some((_, count)) => *count += 1,
+                None => spellings.push((group.to_string(), 1)),
+            }
+        }
+
+        let mut targets: HashMap<String, String> = HashMap::new();
+        for (group, count) in &spellings {
+            let key = group.to_lowercase();
+            let target = if lowercase {
+                key.clone()
+            } else {
+                spellings
+                    .iter()
+                    .filter(|(g, _)| g.to_lowercase() == key)
+                    .fold(
+                        (group, *count),
+                        |best, (g, c)| {
+                            if *c > best.1 { (g, *c) } else { best }
+                        },
+                    )
+                    .0
+                    .clone()
+            };
+            targets.entry(key).or_insert(target);
+        }
+
+        let mut renames: Vec<(String, String, usize)> = Vec::new();
+        let now = Utc::now();
+        for alias in &mut self.aliases {
+            let Some(group) = &alias.group else {
+                continue;
+            };
+            let target = &targets[&group.to_lowercase()];
+            if group == target {
+                continue;
+            }
+            match renames.iter_mut().find(|(from, _, _)| from == group) {
+                Some((_, _, count)) => *count += 1,
+                None => renames.push((group.clone(), target.clone(), 1)),
+            }
+            alias.group = Some(target.clone());
+            alias.updated_at = now;
+        }
+        // Entries follow their aliases; of two that now share a name, the
+        // older one stays
+        for group in &mut self.group_entries {
+            if let Some(target) = targets.get(&group.name.to_lowercase()) {
+                group.name = target.clone();
+            }
+        }
+        self.group_entries.sort_by_key(|g| g.created_at);
+        let mut seen = HashSet::new();
+        self.group_entries.retain(|g| seen.insert(g.name.clone()));
+        renames
+    }
+
+    // Follow links from `name` to the alias whose command actually runs
+    pub fn resolve_link(&self, name: &str) -> Result<&Alias> {
+        let mut seen = vec![name];
+        let mut alias = self
+            .get(name)
+            .ok_or_else(|| AlxError::AliasNotFound(name.to_string()))?;
+        while alias.is_link() {
+            let target = alias.command.as_str();
+            if seen.contains(&target) {
+                seen.push(target);
+                return Err(AlxError::InvalidCommand(format!(
+                    "links form a loop: {}",
+                    seen.join(" -> ")
+                )));
+            }
+            seen.push(target);
+            alias = self.get(target).ok_or_else(|| {
+                AlxError::InvalidCommand(format!(
+                    "'{}' links to '{}', which doesn't exist",
+                    alias.name, target
+                ))
+            })?;
+        }
+        Ok(alias)
+    }
+
+    // The names of the links that point directly at `name`
+    pub fn links_to(&self, name: &str) -> Vec<&str> {
+        self.aliases
+            .iter()
+            .filter(|a| a.is_link() && self.same_name(&a.command, name))
+            .map(|a| a.name.as_str())
+            .collect()
+    }
+
+    // The store with each link's command replaced by its target's, as written
+    // to the shell files. Borrowed when there are no links.
+    pub fn resolve_links(&self) -> Result<Cow<'_, AliasStore>> {
+        if !self.aliases.iter().any(|a| a.is_link()) {
+            return Ok(Cow::Borrowed(self));
+        }
+
+        let mut resolved = self.clone();
+        for alias in resolved.aliases.iter_mut().filter(|a| a.is_link()) {
+            alias.command = self.resolve_link(&alias.name)?.command.clone();
+        }
+        Ok(Cow::Owned(resolved))
+    }
+
+    // A group for an alias running `command`, from the program it starts:
+    // the group most aliases running that program already have, else a
+    // well-known one (git, docker, k8s, ...)
+    pub fn infer_group(&self, command: &str) -> Option<String> {
+        let wanted = program(command)?;
+
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for alias in &self.aliases {
+            let Some(group) = alias.group.as_deref() else {
+                continue;
+            };
+            if alias.is_link() || program(&alias.command) != Some(wanted) {
+                continue;
+            }
+            match counts.iter_mut().find(|(g, _)| *g == group) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((group, 1)),
+            }
+        }
+        // The earliest group wins a tie
+        if let Some((group, _)) = counts.iter().rev().max_by_key(|(_, count)| *count) {
+            return Some(group.to_string());
+        }
+
+        KNOWN_GROUPS
+            .iter()
+            .find(|(_, programs)| programs.contains(&wanted))
+            .map(|(group, _)| group.to_string())
+    }
+
+    // Rename an alias, pointing the links to it at the new name
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<()> {
+        let index = self
+            .position(old)
+            .ok_or_else(|| AlxError::AliasNotFound(old.to_string()))?;
+        // Changing only the case of a name doesn't conflict with itself
+        if self.position(new).is_some_and(|i| i != index) {
+            return Err(AlxError::AliasExists(new.to_string()));
+        }
+        let now = Utc::now();
+        let alias = &mut self.aliases[index];
+        let old = std::mem::replace(&mut alias.name, new.to_string());
+        alias.updated_at = now;
+
+        let ignore_case = self.ignore_case;
+        let links_here = |a: &Alias| {
+            a.command == old || (ignore_case && a.command.to_lowercase() == old.to_lowercase())
+        };
+        for alias in self
+            .aliases
+            .iter_mut()
+            .filter(|a| a.is_link() && links_here(a))
+        {
+            alias.command = new.to_string();
+            alias.updated_at = now;
+        }
+        Ok(())
+    }
+}
+
+// The program a command starts, skipping variable assignments and sudo
+fn program(command: &str) -> Option<&str> {
+    let word = command
+        .split_whitespace()
+        .find(|w| !w.contains('=') && *w != "sudo")?;
+    Some(word.rsplit('/').next().unwrap_or(word))
+}
+
+// Edits (insertions, deletions, substitutions and swaps of neighbouring
+// characters) that turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Three rows of the table: two back, the previous one and the current one
+    let mut before: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
 }
 
 impl Default for AliasStore {
@@ -147,6 +590,87 @@ mod tests {
         assert_eq!(results[0].name, "gs");
     }
 
+    #[test]
+    fn test_similar() {
+        let store = AliasStore::from_aliases(vec![
+            Alias::new("gco".to_string(), "git checkout".to_string()),
+            Alias::new("gcm".to_string(), "git commit -m".to_string()),
+            Alias::new("dps".to_string(), "docker ps".to_string()),
+        ]);
+        let names = |query: &str| -> Vec<String> {
+            store
+                .similar(query)
+                .iter()
+                .map(|a| a.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names("gcoo"), vec!["gco"]);
+        assert_eq!(names("GCO"), vec!["gco"]);
+        assert_eq!(names("dokcer"), vec!["dps"]);
+        assert_eq!(names("git chekcout"), vec!["gco"]);
+        assert_eq!(names("gc"), vec!["gco", "gcm"]);
+        assert!(names("terraform").is_empty());
+        // "ps" is two edits away, one more than a two-letter query allows
+        assert!(names("zz").is_empty());
+        assert_eq!(edit_distance("stauts", "status"), 1);
+        assert_eq!(edit_distance("", "ls"), 2);
+    }
+
+    #[test]
+    fn test_grep() {
+        let mut store = AliasStore::new();
+        store
+            .add(Alias::new(
+                "dc".to_string(),
+                "docker compose -f ~/work/compose.yml".to_string(),
+            ))
+            .unwrap();
+        store
+            .add(Alias::new("gs".to_string(), "git status".to_string()))
+            .unwrap();
+
+        let results = store.grep("COMPOSE", true);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.name, "dc");
+        assert_eq!(results[0].1, vec![7, 25]);
+
+        assert!(store.grep("COMPOSE", false).is_empty());
+        assert!(store.grep("", true).is_empty());
+    }
+
+    #[test]
+    fn test_remove_pack() {
+        let mut store = AliasStore::new();
+        let mut kgp = Alias::new("kgp".to_string(), "kubectl get pods".to_string());
+        kgp.pack = Some("k8s".to_string());
+        store.add(kgp).unwrap();
+        store
+            .add(Alias::new("ll".to_string(), "ls -la".to_string()))
+            .unwrap();
+        store.packs.push(InstalledPack {
+            name: "k8s".to_string(),
+            version: "1.0.0".to_string(),
+            group: "k8s".to_string(),
+            installed_at: Utc::now(),
+        });
+
+        assert_eq!(store.list_by_pack("k8s").len(), 1);
+        assert!(store.pack_links("k8s").is_empty());
+        store
+            .add(Alias::link("pods".to_string(), "kgp".to_string()))
+            .unwrap();
+        assert_eq!(
+            store.pack_links("k8s"),
+            vec![("pods".to_string(), "kgp".to_string())]
+        );
+        store.remove("pods").unwrap();
+        assert!(store.remove_pack("k8s").is_some());
+        assert_eq!(store.aliases.len(), 1);
+        assert!(store.packs.is_empty());
+        assert!(store.remove_pack("k8s").is_none());
+    }
+
     #[test]
     fn test_groups() {
         let mut store = AliasStore::new();
@@ -168,7 +692,156 @@ mod tests {
 
         let groups = store.groups();
         assert_eq!(groups.len(), 2);
-        assert!(groups.contains(&"git".to_string()));
-        assert!(groups.contains(&"docker".to_string()));
+        assert_eq!(groups, vec!["docker", "git"]);
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let mut store = AliasStore::from_aliases(vec![
+            Alias::new("gs".to_string(), "git status".to_string()),
+            Alias::new("Gp".to_string(), "git push".to_string()),
+            Alias::new("GS".to_string(), "git stash".to_string()),
+        ]);
+        assert!(!store.exists("gp"));
+        assert_eq!(store.case_conflicts(), vec![("gs", "GS")]);
+
+        store.ignore_case = true;
+        assert_eq!(store.get("GP").unwrap().command, "git push");
+        // An exact match wins over one that only differs in case
+        assert_eq!(store.get("GS").unwrap().command, "git stash");
+        assert!(
+            store
+                .add(Alias::new("GP".to_string(), "x".to_string()))
+                .is_err()
+        );
+        store.rename("gp", "gP").unwrap();
+        assert_eq!(store.remove("GP").unwrap().name, "gP");
+        assert_eq!(store.grep("STATUS", false).len(), 1);
+    }
+
+    #[test]
+    fn test_infer_group() {
+        let mut store = AliasStore::from_aliases(vec![
+            Alias::new("gs".to_string(), "git status".to_string()),
+            Alias::new("dps".to_string(), "docker ps".to_string()).with_group("ops".to_string()),
+        ]);
+        assert_eq!(store.infer_group("git log"), Some("git".to_string()));
+        assert_eq!(
+            store.infer_group("sudo docker images"),
+            Some("ops".to_string())
+        );
+        assert_eq!(
+            store.infer_group("PAGER= /usr/bin/kubectl get pods"),
+            Some("k8s".to_string())
+        );
+        assert_eq!(store.infer_group("ls -la"), None);
+
+        store.get_mut("gs").unwrap().group = Some("vcs".to_string());
+        assert_eq!(store.infer_group("git log"), Some("vcs".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_groups() {
+        let grouped = |name: &str, group: &str| {
+            Alias::new(name.to_string(), "true".to_string()).with_group(group.to_string())
+        };
+        let aliases = vec![
+            grouped("a", "Git"),
+            grouped("b", "git"),
+            grouped("c", "git"),
+            grouped("d", "Docker"),
+        ];
+
+        let mut store = AliasStore::from_aliases(aliases.clone());
+        assert_eq!(
+            store.normalize_groups(false),
+            vec![("Git".to_string(), "git".to_string(), 1)]
+        );
+        assert_eq!(store.groups(), vec!["Docker", "git"]);
+        assert!(store.normalize_groups(false).is_empty());
+
+        let mut store = AliasStore::from_aliases(aliases);
+        assert_eq!(store.normalize_groups(true).len(), 2);
+        assert_eq!(store.groups(), vec!["docker", "git"]);
+    }
+
+    #[test]
+    fn test_group_entries() {
+        let mut store = AliasStore::from_aliases(vec![
+            Alias::new("gs".to_string(), "git status".to_string()).with_group("git".to_string()),
+        ]);
+        store
+            .add_group(Group::new(
+                "Git".to_string(),
+                Some("Git shortcuts".to_string()),
+            ))
+            .unwrap();
+        store
+            .add_group(Group::new("notes".to_string(), None))
+            .unwrap();
+        assert!(
+            store
+                .add_group(Group::new("notes".to_string(), None))
+                .is_err()
+        );
+        assert_eq!(store.groups(), vec!["Git", "git", "notes"]);
+
+        let toml = store.to_toml().unwrap();
+        assert!(toml.contains("[[groups]]"));
+        let mut store = AliasStore::parse(&toml).unwrap();
+        assert_eq!(store.group_entries.len(), 2);
+
+        store.normalize_groups(false);
+        assert_eq!(store.groups(), vec!["git", "notes"]);
+        assert_eq!(
+            store.group("git").unwrap().description.as_deref(),
+            Some("Git shortcuts")
+        );
+    }
+
+    #[test]
+    fn test_links() {
+        let mut store = AliasStore::from_aliases(vec![
+            Alias::new("gs".to_string(), "git status".to_string()),
+            Alias::link("gst".to_string(), "gs".to_string()),
+            Alias::link("st".to_string(), "gst".to_string()),
+            Alias::new("ll".to_string(), "ls -la".to_string()),
+        ]);
+
+        assert_eq!(store.resolve_link("st").unwrap().name, "gs");
+        assert_eq!(store.links_to("gs"), vec!["gst"]);
+        let resolved = store.resolve_links().unwrap();
+        assert_eq!(resolved.get("st").unwrap().command, "git status");
+        assert!(resolved.get("st").unwrap().is_link());
+
+        store.rename("gs", "gstat").unwrap();
+        assert_eq!(store.get("gst").unwrap().command, "gstat");
+        assert_eq!(store.resolve_link("st").unwrap().command, "git status");
+        assert!(matches!(
+            store.rename("ll", "gst"),
+            Err(AlxError::AliasExists(_))
+        ));
+
+        store.get_mut("gstat").unwrap().command = "st".to_string();
+        store.get_mut("gstat").unwrap().kind = crate::alias::AliasKind::Link;
+        assert!(
+            store
+                .resolve_link("st")
+                .unwrap_err()
+                .to_string()
+                .contains("loop")
+        );
+
+        let plain = AliasStore::from_aliases(vec![Alias::new("ll".to_string(), "ls".to_string())]);
+        assert!(matches!(plain.resolve_links().unwrap(), Cow::Borrowed(_)));
+
+        let mut store = AliasStore::from_aliases(vec![
+            Alias::new("gs".to_string(), "git status".to_string()),
+            Alias::link("l1".to_string(), "GS".to_string()),
+        ])
+        .with_ignore_case(true);
+        assert_eq!(store.links_to("gs"), vec!["l1"]);
+        store.rename("gs", "gst").unwrap();
+        assert_eq!(store.get("l1").unwrap().command, "gst");
     }
 }
@@ -1,4 +1,41 @@
+use crate::alias::store::AliasStore;
 use crate::error::{AlxError, Result};
+use std::collections::HashSet;
+
+// How strictly new aliases are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationProfile {
+    // Names every supported shell accepts; warnings are printed
+    #[default]
+    Standard,
+    // Like standard, but warnings such as reserved keywords are errors
+    Strict,
+    // Any name at least one shell accepts
+    Lenient,
+}
+
+impl ValidationProfile {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "standard" => Ok(ValidationProfile::Standard),
+            "strict" => Ok(ValidationProfile::Strict),
+            "lenient" => Ok(ValidationProfile::Lenient),
+            _ => Err(AlxError::ConfigError(format!(
+                "Unknown validation profile: {} (expected standard, strict or lenient)",
+                name
+            ))),
+        }
+    }
+}
+
+// Everything that decides whether a new alias is accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Validation {
+    pub profile: ValidationProfile,
+    // Allow letters and digits outside ASCII, e.g. 漢字, under the standard
+    // and strict profiles
+    pub unicode_names: bool,
+}
 
 pub struct AliasValidator;
 
@@ -40,6 +77,189 @@ impl AliasValidator {
         Ok(())
     }
 
+    // Standard names without the unicode_names setting: ASCII only, so they
+    // work the same whatever the shell or locale
+    pub fn validate_name_ascii(name: &str) -> Result<()> {
+        if let Some(c) = name.chars().find(|c| !c.is_ascii()) {
+            return Err(AlxError::InvalidAliasName(format!(
+                "Alias name contains non-ASCII character: '{}' (run 'alx config set unicode_names true' to allow it)",
+                c
+            )));
+        }
+        Self::validate_name(name)
+    }
+
+    // Lenient names only avoid characters that break alias definitions or
+    // quoting in every shell
+    pub fn validate_name_lenient(name: &str) -> Result<()> {
+        if name.is_empty() {
+            return Err(AlxError::InvalidAliasName(
+                "Alias name cannot be empty".to_string(),
+            ));
+        }
+
+        if name.starts_with('-') {
+            return Err(AlxError::InvalidAliasName(
+                "Alias name cannot start with '-'".to_string(),
+            ));
+        }
+
+        for c in name.chars() {
+            if c.is_whitespace() || "'\"`$=/;|&<>()\\".contains(c) {
+                return Err(AlxError::InvalidAliasName(format!(
+                    "Alias name contains invalid character: '{}'",
+                    c
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Validate a new alias under `profile`, returning warnings that didn't
+    // block it
+    pub fn check(name: &str, command: &str, profile: ValidationProfile) -> Result<Vec<String>> {
+        let validation = Validation {
+            profile,
+            unicode_names: false,
+        };
+        Self::check_in_store(&AliasStore::new(), name, command, validation)
+    }
+
+    // Like check, but also warns about commands that loop back to the alias
+    // through others in `store`
+    pub fn check_in_store(
+        store: &AliasStore,
+        name: &str,
+        command: &str,
+        validation: Validation,
+    ) -> Result<Vec<String>> {
+        let profile = validation.profile;
+        match profile {
+            ValidationProfile::Lenient => Self::validate_name_lenient(name)?,
+            _ if validation.unicode_names => Self::validate_name(name)?,
+            _ => Self::validate_name_ascii(name)?,
+        }
+        Self::validate_command(command)?;
+
+        let mut warnings = Vec::new();
+        if Self::is_reserved_keyword(name) {
+            warnings.push(format!("'{}' is a reserved shell keyword", name));
+        }
+        // bash, zsh and fish all take UTF-8 names, but only read them back
+        // correctly under a UTF-8 locale
+        if !name.is_ascii() && !utf8_locale() {
+            warnings.push(format!(
+                "'{}' is not ASCII and the locale is not UTF-8, so the shell may not recognise it",
+                name
+            ));
+        }
+        warnings.extend(Self::recursion_warning(store, name, command));
+
+        if profile == ValidationProfile::Strict && !warnings.is_empty() {
+            return Err(AlxError::InvalidAliasName(format!(
+                "{} (validation is strict)",
+                warnings.join(", ")
+            )));
+        }
+        Ok(warnings)
+    }
+
+    // Validate a new command for the existing alias `name`
+    pub fn check_command(
+        store: &AliasStore,
+        name: &str,
+        command: &str,
+        profile: ValidationProfile,
+    ) -> Result<Vec<String>> {
+        Self::validate_command(command)?;
+
+        let warnings: Vec<String> = Self::recursion_warning(store, name, command)
+            .into_iter()
+            .collect();
+        if profile == ValidationProfile::Strict && !warnings.is_empty() {
+            return Err(AlxError::InvalidCommand(format!(
+                "{} (validation is strict)",
+                warnings.join(", ")
+            )));
+        }
+        Ok(warnings)
+    }
+
+    fn recursion_warning(store: &AliasStore, name: &str, command: &str) -> Option<String> {
+        let path = Self::find_loop(store, name, command)?;
+        Some(if path.len() == 2 {
+            format!(
+                "'{}' runs itself; shells differ in whether that reaches the original command",
+                name
+            )
+        } else {
+            format!(
+                "'{}' loops back to itself ({}); this behaves differently across shells and can hang",
+                name,
+                path.join(" -> ")
+            )
+        })
+    }
+
+    // The chain of aliases through which `command`, as the command of `name`,
+    // runs `name` again, e.g. ["a", "b", "a"]. Only words in command position
+    // are expanded as aliases, so those are the ones followed.
+    pub fn find_loop(store: &AliasStore, name: &str, command: &str) -> Option<Vec<String>> {
+        let mut path = vec![name.to_string()];
+        let mut seen = HashSet::new();
+        Self::walk(store, name, command, &mut path, &mut seen).then_some(path)
+    }
+
+    fn walk<'a>(
+        store: &'a AliasStore,
+        target: &str,
+        command: &'a str,
+        path: &mut Vec<String>,
+        seen: &mut HashSet<&'a str>,
+    ) -> bool {
+        for word in command_words(command) {
+            if word == target {
+                path.push(word.to_string());
+                return true;
+            }
+            let Some(alias) = store.get(word).filter(|a| a.enabled) else {
+                continue;
+            };
+            if !seen.insert(word) {
+                continue;
+            }
+            path.push(word.to_string());
+            if Self::walk(store, target, &alias.command, path, seen) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    // Groups aren't nested, so names that look like paths are rejected along
+    // with blank ones and control characters that break the generated files
+    pub fn validate_group(group: &str) -> Result<()> {
+        if group.trim().is_empty() {
+            return Err(AlxError::InvalidGroupName(
+                "Group name cannot be empty".to_string(),
+            ));
+        }
+
+        if let Some(c) = group
+            .chars()
+            .find(|c| matches!(c, '/' | '\\') || c.is_control())
+        {
+            return Err(AlxError::InvalidGroupName(format!(
+                "Group name contains invalid character: {:?}",
+                c
+            )));
+        }
+
+        Ok(())
+    }
+
     // Validate alias command
     // - Cannot be empty
     pub fn validate_command(command: &str) -> Result<()> {
@@ -64,9 +284,28 @@ impl AliasValidator {
     }
 }
 
+fn utf8_locale() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+// The first word of each command in a list or pipeline
+fn command_words(command: &str) -> impl Iterator<Item = &str> {
+    command
+        .split(['|', '&', ';', '\n'])
+        .filter_map(|part| part.split_whitespace().next())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::alias::Alias;
 
     #[test]
     fn test_valid_names() {
@@ -98,6 +337,83 @@ mod tests {
         assert!(AliasValidator::validate_command("   ").is_err());
     }
 
+    #[test]
+    fn test_profiles() {
+        let lenient = ValidationProfile::Lenient;
+        assert!(AliasValidator::check("git:st", "git status", lenient).is_ok());
+        assert!(AliasValidator::check("1up", "cd ..", lenient).is_ok());
+        assert!(AliasValidator::check("a=b", "true", lenient).is_err());
+        assert!(AliasValidator::check("-x", "true", lenient).is_err());
+
+        let standard = ValidationProfile::Standard;
+        assert!(AliasValidator::check("git:st", "git status", standard).is_err());
+        assert_eq!(
+            AliasValidator::check("time", "date", standard)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(AliasValidator::check("time", "date", ValidationProfile::Strict).is_err());
+
+        assert!(ValidationProfile::parse("paranoid").is_err());
+    }
+
+    #[test]
+    fn test_unicode_names() {
+        let store = AliasStore::new();
+        let mut validation = Validation::default();
+        assert!(AliasValidator::check_in_store(&store, "漢字", "ls", validation).is_err());
+        assert!(AliasValidator::check_in_store(&store, "café", "ls", validation).is_err());
+
+        validation.unicode_names = true;
+        assert!(AliasValidator::check_in_store(&store, "漢字", "ls", validation).is_ok());
+        assert!(AliasValidator::check_in_store(&store, "a\u{200b}b", "ls", validation).is_err());
+        assert!(AliasValidator::check_in_store(&store, "漢 字", "ls", validation).is_err());
+    }
+
+    #[test]
+    fn test_validate_group() {
+        assert!(AliasValidator::validate_group("git").is_ok());
+        assert!(AliasValidator::validate_group("My Tools").is_ok());
+        assert!(AliasValidator::validate_group("   ").is_err());
+        assert!(AliasValidator::validate_group("work/infra").is_err());
+        assert!(AliasValidator::validate_group("a\nb").is_err());
+    }
+
+    #[test]
+    fn test_find_loop() {
+        let store = AliasStore::from_aliases(vec![
+            Alias::new("a".to_string(), "echo start && b --flag".to_string()),
+            Alias::new("b".to_string(), "c | less".to_string()),
+            Alias::new("ls".to_string(), "ls --color".to_string()),
+        ]);
+
+        assert_eq!(
+            AliasValidator::find_loop(&store, "c", "a"),
+            Some(vec![
+                "c".to_string(),
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string()
+            ])
+        );
+        assert_eq!(
+            AliasValidator::find_loop(&store, "ls", "ls -G")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(AliasValidator::find_loop(&store, "c", "echo a b").is_none());
+        assert!(AliasValidator::find_loop(&store, "d", "a").is_none());
+
+        let standard = Validation::default();
+        let warnings = AliasValidator::check_in_store(&store, "c", "a", standard).unwrap();
+        assert!(warnings[0].contains("c -> a -> b -> c"));
+        assert!(
+            AliasValidator::check_command(&store, "c", "a", ValidationProfile::Strict).is_err()
+        );
+    }
+
     #[test]
     fn test_reserved_keywords() {
         assert!(AliasValidator::is_reserved_keyword("if"));
@@ -0,0 +1,136 @@
+use crate::error::{AlxError, Result};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EXTENSION: &str = "toml";
+
+#[derive(Debug)]
+pub struct Backup {
+    pub id: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+impl Backup {
+    // Ids are local timestamps, so they can be parsed back for display
+    pub fn created_at(&self) -> Option<DateTime<Local>> {
+        let stamp = self.id.get(..15)?;
+        chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d-%H%M%S")
+            .ok()?
+            .and_local_timezone(Local)
+            .single()
+    }
+}
+
+// Copy `file` into `dir` under a timestamped name and drop all but the `keep`
+// most recent backups. Nothing is written when `file` doesn't exist yet.
+pub fn create(file: &Path, dir: &Path, keep: usize) -> Result<Option<Backup>> {
+    if !file.exists() {
+        return Ok(None);
+    }
+    fs::create_dir_all(dir)?;
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    // Number same-second backups after the newest one, even if older ones
+    // with that stamp were already rotated away
+    let id = match list(dir)?.first().map(|b| sort_key(&b.id)) {
+        Some((newest, n)) if newest == stamp => format!("{}-{}", stamp, n + 1),
+        _ => stamp,
+    };
+
+    let path = backup_path(dir, &id);
+    let size = fs::copy(file, &path)?;
+    rotate(dir, keep)?;
+
+    Ok(Some(Backup { id, path, size }))
+}
+
+// Backups in `dir`, newest first
+pub fn list(dir: &Path) -> Result<Vec<Backup>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(EXTENSION) {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        backups.push(Backup {
+            id: id.to_string(),
+            size: fs::metadata(&path)?.len(),
+            path,
+        });
+    }
+
+    backups.sort_by(|a, b| sort_key(&b.id).cmp(&sort_key(&a.id)));
+    Ok(backups)
+}
+
+pub fn find(dir: &Path, id: &str) -> Result<Backup> {
+    list(dir)?.into_iter().find(|b| b.id == id).ok_or_else(|| {
+        AlxError::ConfigError(format!(
+            "Backup not found: {} (run 'alx backup list' to see available backups)",
+            id
+        ))
+    })
+}
+
+fn rotate(dir: &Path, keep: usize) -> Result<()> {
+    for backup in list(dir)?.into_iter().skip(keep) {
+        fs::remove_file(backup.path)?;
+    }
+    Ok(())
+}
+
+fn backup_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.{}", id, EXTENSION))
+}
+
+// Order same-second backups by their numeric suffix
+fn sort_key(id: &str) -> (&str, u32) {
+    match id.get(15..).and_then(|s| s.strip_prefix('-')) {
+        Some(n) => (&id[..15], n.parse().unwrap_or(0)),
+        None => (id, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_and_rotate() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("aliases.toml");
+        let dir = temp.path().join("backups");
+
+        assert!(create(&file, &dir, 3).unwrap().is_none());
+
+        fs::write(&file, "aliases = []\n").unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..5 {
+            ids.push(create(&file, &dir, 3).unwrap().unwrap().id);
+        }
+
+        let backups = list(&dir).unwrap();
+        assert_eq!(backups.len(), 3);
+        assert_eq!(backups[0].id, ids[4]);
+        assert_eq!(backups[2].id, ids[2]);
+        assert!(find(&dir, &ids[0]).is_err());
+        assert_eq!(find(&dir, &ids[3]).unwrap().size, 13);
+    }
+
+    #[test]
+    fn test_sort_key() {
+        assert!(sort_key("20260101-120000-10") > sort_key("20260101-120000-2"));
+        assert!(sort_key("20260101-120000-2") > sort_key("20260101-120000"));
+        assert!(sort_key("20260101-120001") > sort_key("20260101-120000-9"));
+    }
+}
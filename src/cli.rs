@@ -10,6 +10,18 @@ use clap::{Parser, Subcommand};
     after_help = "EXAMPLES:\n    alx add ll 'ls -la' -d 'List all files with details'\n    alx list -g dev\n    alx search git\n    alx remove temp-alias\n    alx export -o aliases.json\n\nFor more information on a specific command, use: alx <COMMAND> --help"
 )]
 pub struct Cli {
+    /// Use this directory instead of ~/.config/alx (or set ALX_CONFIG_DIR)
+    #[arg(long, global = true, value_name = "DIR")]
+    pub config_dir: Option<std::path::PathBuf>,
+
+    /// Regenerate shell aliases after this change even if auto_sync is off
+    #[arg(long, global = true, conflicts_with = "no_sync")]
+    pub sync: bool,
+
+    /// Don't regenerate shell aliases after this change
+    #[arg(long, global = true)]
+    pub no_sync: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -19,13 +31,26 @@ pub enum Commands {
     /// Initialize alx configuration
     ///
     /// Example: alx init
-    Init,
+    #[command(
+        after_help = "EXAMPLES:\n    alx init\n    alx init --rc-file ~/.zshrc.local\n    alx init --repair"
+    )]
+    Init {
+        /// Fix an existing setup: recreate missing directories and the
+        /// generated file, and update the block in your shell's rc file
+        #[arg(long)]
+        repair: bool,
+
+        /// Write the source line to this file instead of the shell's rc file
+        /// (remembered as the rc_file setting)
+        #[arg(long, value_name = "PATH")]
+        rc_file: Option<String>,
+    },
 
     /// Add a new alias
     ///
     /// Example: alx add ll 'ls -la' -d 'List all files' -g utils
     #[command(
-        after_help = "EXAMPLES:\n    alx add ll 'ls -la' -d 'List all files with details'\n    alx add gs 'git status' -d 'Show git status' -g git\n    alx add serve 'python -m http.server' -d 'Start HTTP server' -g dev"
+        after_help = "EXAMPLES:\n    alx add ll 'ls -la' -d 'List all files with details'\n    alx add gs 'git status' -d 'Show git status' -g git -t work\n    alx add serve 'python -m http.server' -d 'Start HTTP server' -g dev"
     )]
     Add {
         /// Name of the alias
@@ -41,6 +66,42 @@ pub enum Commands {
         /// Group/category for the alias
         #[arg(short, long)]
         group: Option<String>,
+
+        /// Tag for the alias (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+    },
+
+    /// Add an alias that runs another alias, following it when that alias
+    /// is edited or renamed
+    ///
+    /// Example: alx link gst gs
+    #[command(after_help = "EXAMPLES:\n    alx link gst gs\n    alx link st gs -d 'Status' -g git")]
+    Link {
+        /// Name of the new alias
+        name: String,
+
+        /// Alias it runs
+        target: String,
+
+        /// Description of the alias
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Group/category for the alias
+        #[arg(short, long)]
+        group: Option<String>,
+    },
+
+    /// Rename an alias, updating the links that point at it
+    ///
+    /// Example: alx rename gs gst
+    Rename {
+        /// Current name
+        old: String,
+
+        /// New name
+        new: String,
     },
 
     /// Remove one or more aliases
@@ -49,39 +110,132 @@ pub enum Commands {
     #[command(after_help = "EXAMPLES:\n    alx remove ll\n    alx remove ll gs serve")]
     Remove {
         /// Names of the aliases to remove
+        #[arg(required_unless_present = "conditions")]
+        names: Vec<String>,
+
+        /// Also remove every alias matching a condition (repeatable, all must match): group=NAME, tag=NAME, group!=NAME, tag!=NAME, enabled=true|false, name~REGEX, command~REGEX, created<DATE, created<=DATE, created>DATE, updated<DATE, updated<=DATE or updated>DATE
+        #[arg(long = "where", value_name = "CONDITION")]
+        conditions: Vec<String>,
+
+        /// Remove aliases in locked groups, and protected aliases after confirming
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Protect aliases from being removed or getting a new command by accident
+    ///
+    /// Example: alx protect gs
+    Protect {
+        /// Names of the aliases to protect
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+
+    /// Remove the protection from aliases
+    ///
+    /// Example: alx unprotect gs
+    Unprotect {
+        /// Names of the aliases to unprotect
+        #[arg(required = true)]
         names: Vec<String>,
     },
 
     /// List all aliases
     ///
     /// Example: alx list -g git
-    #[command(after_help = "EXAMPLES:\n    alx list\n    alx list -g git\n    alx list -g dev")]
+    #[command(
+        after_help = "EXAMPLES:\n    alx list\n    alx list -g git\n    alx list --show-dates --sort updated"
+    )]
     List {
         /// Filter by group
         #[arg(short, long)]
         group: Option<String>,
+
+        /// Only list aliases matching a condition (repeatable), as for remove --where
+        #[arg(long = "where", value_name = "CONDITION")]
+        conditions: Vec<String>,
+
+        /// Leave out the aliases in a group (repeatable)
+        #[arg(long, value_name = "GROUP")]
+        exclude_group: Vec<String>,
+
+        /// Leave out the aliases with a tag (repeatable)
+        #[arg(long, value_name = "TAG")]
+        exclude_tag: Vec<String>,
+
+        /// Add Created and Updated columns
+        #[arg(long)]
+        show_dates: bool,
+
+        /// Add a Last used column from usage tracking
+        #[arg(long)]
+        last_used: bool,
+
+        /// Only aliases not used within this age (e.g. 90d) and older than it
+        #[arg(long, value_name = "AGE")]
+        unused_since: Option<String>,
+
+        /// Sort by name, or newest first by created or updated date
+        #[arg(long, value_parser = ["name", "created", "updated"])]
+        sort: Option<String>,
+
+        /// Output format: a table, or alias lines for the current shell
+        #[arg(long, value_parser = ["table", "alias"])]
+        format: Option<String>,
+    },
+
+    /// Show everything about one alias, including when it was last used
+    ///
+    /// Example: alx show gs
+    Show {
+        /// Alias name
+        name: String,
     },
 
     /// Search aliases by keyword
     ///
     /// Example: alx search git
     #[command(
-        after_help = "EXAMPLES:\n    alx search git\n    alx search 'list files'\n    alx search server"
+        after_help = "EXAMPLES:\n    alx search git\n    alx search 'list files'\n    alx search server\n    alx search docker --format alias >> ~/.scratch-aliases"
     )]
     Search {
         /// Keyword to search for
         keyword: String,
+
+        /// Output format: a table, or alias lines for the current shell
+        #[arg(long, value_parser = ["table", "alias"])]
+        format: Option<String>,
     },
 
-    /// Edit an alias
+    /// Search alias commands and highlight matches
+    ///
+    /// Example: alx grep ~/work
+    #[command(
+        after_help = "EXAMPLES:\n    alx grep ~/work\n    alx grep -- --force\n    alx grep -i DOCKER -C 10"
+    )]
+    Grep {
+        /// Text to look for in alias commands
+        pattern: String,
+
+        /// Match case-insensitively
+        #[arg(short, long)]
+        ignore_case: bool,
+
+        /// Number of characters to show around each match
+        #[arg(short = 'C', long, default_value_t = 20)]
+        context: usize,
+    },
+
+    /// Edit an alias (opens it in your editor when no changes are given)
     ///
     /// Example: alx edit ll -c 'ls -lah' -d 'Updated description'
     #[command(
-        after_help = "EXAMPLES:\n    alx edit ll -c 'ls -lah'\n    alx edit gs -d 'Show git status with branch info'\n    alx edit serve -g webdev"
+        after_help = "EXAMPLES:\n    alx edit ll -c 'ls -lah'\n    alx edit gs -d 'Show git status with branch info'\n    alx edit serve -g webdev\n    alx edit serve\n    alx export -o - -f json | jq '[.aliases[] | select(.command | contains(\"old-host\")) | {name, command: (.command | sub(\"old-host\"; \"new-host\"))}]' | alx edit --batch"
     )]
     Edit {
         /// Name of the alias to edit
-        name: String,
+        #[arg(required_unless_present = "batch")]
+        name: Option<String>,
 
         /// New command (optional)
         #[arg(short, long)]
@@ -94,33 +248,404 @@ pub enum Commands {
         /// New group (optional)
         #[arg(short, long)]
         group: Option<String>,
+
+        /// Replace the tags (repeatable, optional)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+
+        /// Edit an alias in a locked group, or change a protected alias's command after confirming
+        #[arg(long)]
+        force: bool,
+
+        /// Read a JSON array of changes from stdin, e.g. [{"name": "gs", "command": "git status -s"}]
+        #[arg(long, conflicts_with_all = ["name", "command", "description", "group", "tags"])]
+        batch: bool,
     },
 
     /// Export aliases to a file
     ///
     /// Example: alx export -o aliases.json -f json
     #[command(
-        after_help = "EXAMPLES:\n    alx export\n    alx export -o my-aliases.json\n    alx export -o aliases.toml -f toml"
+        after_help = "EXAMPLES:\n    alx export\n    alx export -o my-aliases.json\n    alx export -o aliases.toml -f toml\n    alx export -o ALIASES.md -f markdown\n    alx export -o aliases.zsh -f zsh\n    alx export -o - -f jsonl | jq -r .name\n    alx export -o git.json -g git --enabled-only\n    alx export -o new.json --since 2024-06-01\n    alx export -o old.json --until 2023-12-31 --by updated"
     )]
     Export {
-        /// Output file path
+        /// Output file or directory (defaults to the export_path setting, - for stdout)
         #[arg(short, long)]
         output: Option<String>,
 
-        /// Export format (json or toml)
-        #[arg(short, long, default_value = "json")]
-        format: String,
+        /// Export format: json, jsonl, toml, markdown, bash, zsh or fish (defaults to the export_format setting, then json)
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// Only export aliases in this group
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Only export aliases with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Skip disabled aliases
+        #[arg(long)]
+        enabled_only: bool,
+
+        /// Only export aliases matching a condition (repeatable), as for remove --where
+        #[arg(long = "where", value_name = "CONDITION")]
+        conditions: Vec<String>,
+
+        /// Leave out the aliases in a group (repeatable)
+        #[arg(long, value_name = "GROUP")]
+        exclude_group: Vec<String>,
+
+        /// Leave out the aliases with a tag (repeatable)
+        #[arg(long, value_name = "TAG")]
+        exclude_tag: Vec<String>,
+
+        /// Only export aliases created (or with --by updated, changed) on or after this date, as YYYY-MM-DD
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        /// Only export aliases created (or with --by updated, changed) on or before this date, as YYYY-MM-DD
+        #[arg(long, value_name = "DATE")]
+        until: Option<String>,
+
+        /// Which date --since and --until look at
+        #[arg(long, value_parser = ["created", "updated"], default_value = "created")]
+        by: String,
+
+        /// Write one file per group into the output directory
+        #[arg(long)]
+        split_by_group: bool,
     },
 
     /// Import aliases from a file
     ///
     /// Example: alx import aliases.json
     #[command(
-        after_help = "EXAMPLES:\n    alx import aliases.json\n    alx import backup.toml\n    alx import ~/Downloads/shared-aliases.json"
+        after_help = "EXAMPLES:\n    alx import aliases.json\n    alx import backup.toml\n    alx import ~/Downloads/shared-aliases.json\n    alx import gist:aa5a315d61ae9438b18d\n    alx import ~/.config/pet/snippet.toml --from pet\n    alias -p | alx import - --from shell\n    fish -ic alias | alx import - --from shell\n    alx import macros.doskey --from doskey\n    alx import aliases.json --report report.json\n    alx import team.json --map-group vcs=git --map-group misc=\n    alx import shared.json --into-group shared"
     )]
     Import {
-        /// Input file path
+        /// Input file path, gist:<id> to fetch a GitHub gist, or - for stdin
+        file: String,
+
+        /// Read another tool's format (aka, pet, shell for `alias -p` / `alias` output, or doskey macrofiles)
+        #[arg(long, value_parser = ["aka", "pet", "shell", "doskey"])]
+        from: Option<String>,
+
+        /// Write a JSON report of added, skipped, conflicting and failed aliases
+        #[arg(long, value_name = "PATH")]
+        report: Option<String>,
+
+        /// Rename an incoming group (repeatable; old= drops the group)
+        #[arg(long, value_name = "OLD=NEW")]
+        map_group: Vec<String>,
+
+        /// Put every imported alias in this group
+        #[arg(long, value_name = "GROUP", conflicts_with = "map_group")]
+        into_group: Option<String>,
+    },
+
+    /// Merge another exported store into this one
+    ///
+    /// Example: alx merge laptop.json
+    #[command(
+        after_help = "EXAMPLES:\n    alx merge laptop.json\n    alx merge laptop.json --strategy newest\n    alx merge laptop.toml --base last-sync.toml\n    alx merge laptop.json --dry-run"
+    )]
+    Merge {
+        /// Exported store to merge in (JSON or TOML)
         file: String,
+
+        /// Store both sides started from, so removals carry over too
+        #[arg(long, value_name = "FILE")]
+        base: Option<String>,
+
+        /// Resolve conflicting commands without asking
+        #[arg(long, value_parser = ["ours", "theirs", "newest"])]
+        strategy: Option<String>,
+
+        /// Show what would change without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Enable aliases so they are written to the shell file again
+    ///
+    /// Example: alx enable gs gp
+    Enable {
+        /// Alias names
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+
+    /// Disable aliases without removing them
+    ///
+    /// Example: alx disable gs gp
+    Disable {
+        /// Alias names
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+
+    /// Pick which aliases are enabled from a checklist
+    ///
+    /// Example: alx toggle -g git
+    Toggle {
+        /// Only show aliases in this group
+        #[arg(short, long)]
+        group: Option<String>,
+    },
+
+    /// Copy an alias's command to the clipboard
+    ///
+    /// Example: alx copy gs
+    Copy {
+        /// Alias name
+        name: String,
+    },
+
+    /// Share aliases with others
+    ///
+    /// Example: alx share gs
+    #[command(
+        args_conflicts_with_subcommands = true,
+        after_help = "EXAMPLES:\n    alx share gs\n    alx share gist\n    alx share gist -g git --public\n    alx share gist -f toml -d 'My aliases'"
+    )]
+    Share {
+        /// Print a ready-to-paste `alx add` command for this alias and copy it
+        name: Option<String>,
+
+        #[command(subcommand)]
+        command: Option<ShareCommands>,
+    },
+
+    /// Sync aliases across machines with git
+    ///
+    /// Example: alx git push
+    #[command(
+        after_help = "EXAMPLES:\n    alx git init\n    alx git remote git@github.com:me/aliases.git\n    alx git push\n    alx git pull --strategy theirs\n    alx git status"
+    )]
+    Git {
+        #[command(subcommand)]
+        command: GitCommands,
+    },
+
+    /// Install bundled alias presets
+    ///
+    /// Example: alx preset apply git
+    #[command(
+        after_help = "EXAMPLES:\n    alx preset list\n    alx preset apply git\n    alx preset apply kubectl -g k8s"
+    )]
+    Preset {
+        #[command(subcommand)]
+        command: PresetCommands,
+    },
+
+    /// Install community alias packs from a pack index
+    ///
+    /// Example: alx pack install git-extras
+    #[command(
+        after_help = "EXAMPLES:\n    alx pack search git\n    alx pack install git-extras\n    alx pack update\n    alx pack remove git-extras"
+    )]
+    Pack {
+        #[command(subcommand)]
+        command: PackCommands,
+    },
+
+    /// List and restore automatic backups of the aliases file
+    ///
+    /// Example: alx backup list
+    #[command(
+        after_help = "EXAMPLES:\n    alx backup list\n    alx backup restore 20250101-093000"
+    )]
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommands,
+    },
+
+    /// Save and restore named snapshots of all aliases
+    ///
+    /// Example: alx snapshot create before-import
+    #[command(
+        after_help = "EXAMPLES:\n    alx snapshot create before-import\n    alx snapshot diff before-import\n    alx snapshot restore before-import\n    alx snapshot list"
+    )]
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+
+    /// Show the log of changes and syncs (enable with 'alx config set log_enabled true')
+    ///
+    /// Example: alx log tail
+    #[command(after_help = "EXAMPLES:\n    alx log tail\n    alx log tail -n 100")]
+    Log {
+        #[command(subcommand)]
+        command: LogCommands,
+    },
+
+    /// Read and change settings in config.toml
+    ///
+    /// Example: alx config set auto_sync false
+    #[command(
+        after_help = "EXAMPLES:\n    alx config list\n    alx config get default_shell\n    alx config set auto_sync false\n    alx config unset pack_index_url\n    alx config doctor --fix"
+    )]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Choose which shell aliases are generated for
+    ///
+    /// Example: alx shell set zsh
+    #[command(after_help = "EXAMPLES:\n    alx shell show\n    alx shell set zsh")]
+    Shell {
+        #[command(subcommand)]
+        command: ShellCommands,
+    },
+
+    /// Regenerate the shell aliases file from the store
+    ///
+    /// Example: alx sync
+    Sync,
+
+    /// Print shell code that warns at the prompt when aliases are out of date
+    ///
+    /// Example: eval "$(alx shell-init)"
+    #[command(
+        after_help = "EXAMPLES:\n    eval \"$(alx shell-init)\"\n    eval \"$(alx shell-init zsh)\"\n    alx shell-init fish | source"
+    )]
+    ShellInit {
+        /// Shell to print the check for (defaults to the current shell)
+        #[arg(value_parser = ["bash", "zsh", "fish"])]
+        shell: Option<String>,
+    },
+
+    /// Print alias definitions for a shell to stdout, for eval in scripts
+    ///
+    /// Example: eval "$(alx env bash --group ci)"
+    #[command(
+        after_help = "EXAMPLES:\n    eval \"$(alx env bash)\"\n    eval \"$(alx env bash --group ci)\"\n    alx env fish --tag shared | source"
+    )]
+    Env {
+        /// Shell to print definitions for (defaults to the current shell)
+        #[arg(value_parser = ["bash", "zsh", "fish"])]
+        shell: Option<String>,
+
+        /// Only include aliases in this group
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Only include aliases with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Print a definition of an alias to eval in the current shell only, without saving it
+    ///
+    /// Example: eval "$(alx try gs)"
+    #[command(
+        after_help = "EXAMPLES:\n    eval \"$(alx try gs)\"\n    eval \"$(alx try gl --command 'git log --oneline -20')\"\n    alx try gs fish | source"
+    )]
+    Try {
+        /// Alias name, looked up in the store unless --command is given
+        name: String,
+
+        /// Try this command under the name instead of the stored one
+        #[arg(short, long)]
+        command: Option<String>,
+
+        /// Shell to print the definition for (defaults to the current shell)
+        #[arg(value_parser = ["bash", "zsh", "fish"])]
+        shell: Option<String>,
+    },
+
+    /// Re-sync shell aliases whenever aliases.toml or config.toml changes on disk
+    ///
+    /// Example: alx watch
+    Watch,
+
+    /// Serve a local JSON API over the alias store for editors and GUI frontends
+    ///
+    /// Example: alx serve --listen 127.0.0.1:7423
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:7423")]
+        listen: String,
+    },
+
+    /// Show the most and least used aliases (enable with 'alx config set track_usage true')
+    ///
+    /// Example: alx top --since 30d
+    #[command(
+        after_help = "EXAMPLES:\n    alx top\n    alx top --since 90d\n    alx top --since 2w -n 20"
+    )]
+    Top {
+        /// Time window for counting uses, e.g. 12h, 30d or 8w
+        #[arg(long, default_value = "30d")]
+        since: String,
+
+        /// Number of aliases to show per section
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// Suggest aliases for commands you type often, from the shell history
+    ///
+    /// Example: alx suggest
+    #[command(after_help = "EXAMPLES:\n    alx suggest\n    alx suggest --min-count 10 -n 5")]
+    Suggest {
+        /// Only commands typed at least this many times
+        #[arg(long, default_value_t = 3)]
+        min_count: usize,
+
+        /// Number of suggestions to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// Only activate a group inside certain directories
+    ///
+    /// Example: alx scope add k8s ~/work/infra
+    #[command(
+        after_help = "EXAMPLES:\n    alx scope add k8s ~/work/infra\n    alx scope list\n    alx scope remove k8s ~/work/infra\n    alx scope remove k8s"
+    )]
+    Scope {
+        #[command(subcommand)]
+        command: ScopeCommands,
+    },
+
+    /// Check every alias for problems such as broken quoting, duplicates and missing programs
+    ///
+    /// Example: alx lint --fix
+    Lint {
+        /// Fix the issues that can be fixed automatically
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Find and repair damage in aliases.toml, such as duplicate names or invalid timestamps
+    ///
+    /// Example: alx fsck --auto
+    Fsck {
+        /// Apply every fix without asking
+        #[arg(long)]
+        auto: bool,
+    },
+
+    /// Show which aliases hide a program, builtin or alias of the same name
+    ///
+    /// Example: alx check-shadowing
+    CheckShadowing {
+        /// List every alias, including those that shadow nothing
+        #[arg(short, long)]
+        all: bool,
+    },
+
+    /// Manage groups
+    ///
+    /// Example: alx group normalize
+    Group {
+        #[command(subcommand)]
+        command: GroupCommands,
     },
 
     /// Show all available groups
@@ -128,20 +653,383 @@ pub enum Commands {
     /// Example: alx groups
     Groups,
 
+    /// Print alias, group and disabled counts for prompts and status bars
+    ///
+    /// Example: alx count aliases
+    #[command(after_help = "EXAMPLES:\n    alx count\n    alx count aliases\n    alx count --json")]
+    Count {
+        /// Print just this number
+        #[arg(value_parser = ["aliases", "groups", "disabled"], conflicts_with = "json")]
+        what: Option<String>,
+
+        /// Print the counts as a JSON object
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print or install the shell completion script for alx
+    ///
+    /// Example: alx completions --install
+    #[command(
+        after_help = "EXAMPLES:\n    alx completions --install\n    alx completions zsh --install\n    alx completions bash > /etc/bash_completion.d/alx"
+    )]
+    Completions {
+        /// Shell to generate completions for (defaults to the current shell)
+        #[arg(value_parser = ["bash", "zsh", "fish"])]
+        shell: Option<String>,
+
+        /// Write the script where the shell loads completions from
+        #[arg(long)]
+        install: bool,
+    },
+
+    /// Run the alx-<name> executable on PATH as `alx <name>`
+    #[command(external_subcommand)]
+    External(Vec<String>),
+
+    /// Download and install the latest release of alx
+    ///
+    /// Example: alx self-update
+    #[command(after_help = "EXAMPLES:\n    alx self-update\n    alx self-update --check")]
+    SelfUpdate {
+        /// Only report whether a newer release is available
+        #[arg(long)]
+        check: bool,
+    },
+
     /// Show information about alx
     ///
     /// Example: alx info
-    Info,
+    #[command(after_help = "EXAMPLES:\n    alx info\n    alx info --json > alx-info.json")]
+    Info {
+        /// Print paths, counts, shell and settings as a JSON document
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Open the config directory or one of its files
+    ///
+    /// Example: alx open aliases
+    #[command(after_help = "EXAMPLES:\n    alx open\n    alx open aliases\n    alx open config")]
+    Open {
+        /// File to open in your editor (opens the config directory when omitted)
+        #[arg(value_parser = ["aliases", "config", "shell"])]
+        target: Option<String>,
+    },
 
     /// Migrate aliases from shell configuration file
     ///
     /// Example: alx migrate -f ~/.bashrc
     #[command(
-        after_help = "EXAMPLES:\n    alx migrate\n    alx migrate -f ~/.bashrc\n    alx migrate -f ~/.zshrc"
+        after_help = "EXAMPLES:\n    alx migrate\n    alx migrate -f ~/.bashrc\n    alx migrate -f ~/.zshrc\n    alx migrate -f ~/.bash_aliases -g shell\n    alx migrate --group-by-source\n    alx migrate -f ~/.aliases --shell zsh\n    alx migrate --omz git\n    alx migrate --omz"
     )]
     Migrate {
         /// Shell configuration file to migrate from (optional)
         #[arg(short, long)]
         from: Option<String>,
+
+        /// Migrate aliases from an oh-my-zsh plugin (every enabled plugin when no name is given)
+        #[arg(long, value_name = "PLUGIN", conflicts_with = "from")]
+        omz: Option<Option<String>>,
+
+        /// Parse with this shell's syntax instead of guessing from the file name
+        #[arg(long, value_parser = ["bash", "zsh", "fish"], conflicts_with = "omz")]
+        shell: Option<String>,
+
+        /// How many levels of sourced files to follow (0 reads only the config file)
+        #[arg(long, default_value_t = 3)]
+        depth: usize,
+
+        /// Put all migrated aliases into this group
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Group aliases by the file they were defined in
+        #[arg(long, conflicts_with_all = ["group", "omz"])]
+        group_by_source: bool,
     },
 }
+
+#[derive(Subcommand)]
+pub enum ShareCommands {
+    /// Publish aliases as a GitHub gist (token read from ALX_GITHUB_TOKEN or GITHUB_TOKEN)
+    ///
+    /// Example: alx share gist -g git
+    Gist {
+        /// Gist file format (json or toml)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+
+        /// Only share aliases in this group
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Only share aliases with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Skip disabled aliases
+        #[arg(long)]
+        enabled_only: bool,
+
+        /// Create a public gist instead of a secret one
+        #[arg(long)]
+        public: bool,
+
+        /// Gist description
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GitCommands {
+    /// Put the config directory under git
+    Init,
+
+    /// Set the remote to sync with
+    Remote {
+        /// Remote repository URL
+        url: String,
+    },
+
+    /// Push committed changes to the remote
+    Push,
+
+    /// Pull changes from the remote, merging alias conflicts
+    Pull {
+        /// How to resolve aliases changed on both sides (ours, theirs or newest)
+        #[arg(short, long, default_value = "newest", value_parser = ["ours", "theirs", "newest"])]
+        strategy: String,
+    },
+
+    /// Show branch, remote and sync state
+    Status,
+}
+
+#[derive(Subcommand)]
+pub enum PresetCommands {
+    /// List bundled presets
+    List,
+
+    /// Add a preset's aliases to the store
+    Apply {
+        /// Preset name
+        name: String,
+
+        /// Group for the installed aliases (defaults to the preset name)
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Replace existing aliases that have a different command
+        #[arg(long)]
+        overwrite: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PackCommands {
+    /// Search the pack index (lists every pack when no query is given)
+    Search {
+        /// Text to match against pack names and descriptions
+        query: Option<String>,
+    },
+
+    /// Install a pack
+    Install {
+        /// Pack name
+        name: String,
+
+        /// Group for the installed aliases (defaults to the pack name)
+        #[arg(short, long)]
+        group: Option<String>,
+    },
+
+    /// Update installed packs to the latest version in the index
+    Update {
+        /// Pack to update (all installed packs when omitted)
+        name: Option<String>,
+    },
+
+    /// Remove a pack and all of its aliases
+    Remove {
+        /// Pack name
+        name: String,
+    },
+
+    /// List installed packs
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    /// List backups, newest first
+    List,
+
+    /// Replace the aliases file with a backup
+    Restore {
+        /// Backup id as shown by `alx backup list`
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotCommands {
+    /// Save the current aliases under a name
+    Create {
+        /// Snapshot name
+        name: String,
+
+        /// Replace an existing snapshot with the same name
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Replace the current aliases with a snapshot
+    Restore {
+        /// Snapshot name
+        name: String,
+    },
+
+    /// Show what changed since a snapshot was taken
+    Diff {
+        /// Snapshot name
+        name: String,
+    },
+
+    /// List snapshots
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum LogCommands {
+    /// Print the most recent log entries
+    Tail {
+        /// Number of entries to print
+        #[arg(short = 'n', long, default_value_t = 20)]
+        lines: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GroupCommands {
+    /// Create a group before adding aliases to it
+    Create {
+        /// Group name
+        group: String,
+
+        /// What the group's aliases are for, shown by 'alx groups'
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Require --force to edit or remove the group's aliases
+    Lock {
+        /// Group name
+        group: String,
+    },
+
+    /// Allow the group's aliases to be edited and removed again
+    Unlock {
+        /// Group name
+        group: String,
+    },
+
+    /// Set the color a group is listed in
+    Color {
+        /// Group name
+        group: String,
+
+        /// red, green, yellow, blue, magenta, cyan or none (omit to go back to the picked color)
+        color: Option<String>,
+    },
+
+    /// Merge groups whose names differ only in case, e.g. Git and git
+    Normalize {
+        /// Lowercase every group name instead of keeping the most used spelling
+        #[arg(long)]
+        lower: bool,
+
+        /// Show the renames without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Suggest groups for ungrouped aliases from the program each one runs
+    Infer {
+        /// Assign the suggested groups
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ScopeCommands {
+    /// Activate a group only inside a directory and its subdirectories
+    Add {
+        /// Group name
+        group: String,
+
+        /// Directory the group is active in
+        dir: String,
+    },
+
+    /// Stop scoping a group to a directory (or to all its directories)
+    Remove {
+        /// Group name
+        group: String,
+
+        /// Directory to remove (all of them when omitted)
+        dir: Option<String>,
+    },
+
+    /// Show scoped groups and their directories
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the value of a setting
+    Get {
+        /// Setting name
+        key: String,
+    },
+
+    /// Change a setting
+    Set {
+        /// Setting name
+        key: String,
+
+        /// New value
+        value: String,
+    },
+
+    /// Reset a setting to its default
+    Unset {
+        /// Setting name
+        key: String,
+    },
+
+    /// Show all settings
+    List,
+
+    /// Check config.toml for unknown keys and invalid values
+    Doctor {
+        /// Rewrite a corrected config.toml, keeping a copy of the old one
+        #[arg(long)]
+        fix: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ShellCommands {
+    /// Generate aliases for this shell from now on
+    Set {
+        /// Shell name
+        #[arg(value_parser = ["bash", "zsh", "fish"])]
+        shell: String,
+    },
+
+    /// Show the shell aliases are generated for
+    Show,
+}
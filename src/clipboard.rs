@@ -0,0 +1,9 @@
+use crate::error::{AlxError, Result};
+
+pub fn copy(text: &str) -> Result<()> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| AlxError::ClipboardError(e.to_string()))?;
+    clipboard
+        .set_text(text)
+        .map_err(|e| AlxError::ClipboardError(e.to_string()))
+}
@@ -1,47 +1,246 @@
-use crate::alias::Alias;
-use crate::alias::store::AliasStore;
-use crate::alias::validator::AliasValidator;
-use crate::config::manager::ConfigManager;
-use crate::error::{self, Result};
-use crate::shell::bash::BashHandler;
-use crate::shell::detector::ShellDetector;
-use crate::shell::fish::FishHandler;
-use crate::shell::zsh::ZshHandler;
-use crate::shell::{ShellHandler, ShellType};
+use crate::completions;
+use alx::alias::diff::{Change, diff_stores};
+use alx::alias::filter::Filter;
+use alx::alias::merge::{self, MergeStrategy};
+use alx::alias::store::{AliasStore, Group, InstalledPack};
+use alx::alias::validator::{AliasValidator, Validation};
+use alx::alias::{Alias, AliasKind};
+use alx::backup;
+use alx::clipboard;
+use alx::config::doctor;
+use alx::config::manager::ConfigManager;
+use alx::config::paths;
+use alx::config::{Config, Settings};
+use alx::editor;
+use alx::error::{self, Result};
+use alx::export;
+use alx::fsck;
+use alx::gist;
+use alx::git::{GitSync, PullOutcome};
+use alx::hooks::{self, AliasEvent};
+use alx::importer;
+use alx::lint::{self, Severity};
+use alx::logging;
+use alx::pack::PackIndex;
+use alx::plugin;
+use alx::preset::Preset;
+use alx::report::{
+    self, GROUP_COLORS, ImportConflict, ImportFailure, ImportSummary, TableStyle, alias_table,
+    paint_group, progress_bar, relative_time,
+};
+use alx::secrets::{self, Secret};
+use alx::shadow;
+use alx::shell::detector::ShellDetector;
+use alx::shell::history;
+use alx::shell::omz;
+use alx::shell::rc;
+use alx::shell::registry;
+use alx::shell::sources;
+use alx::shell::syntax;
+use alx::shell::{self, ShellHandler, ShellType};
+use alx::snapshot;
+use alx::suggest;
+use alx::sync::{
+    current_shell, current_shell_aliases_file, render_env, shell_aliases_stale, sync_alias,
+    sync_aliases,
+};
+use alx::update::{self, UpdateCheck};
+use alx::usage;
+use alx::watch;
 use comfy_table::{
     Cell, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_BORDERS_ONLY,
 };
-use dialoguer::{Confirm, Select};
+use console::{Term, style};
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::Read;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-fn sync_aliases() -> Result<()> {
-    let config_manager = ConfigManager::new()?;
-    let store = AliasStore::load(config_manager.aliases_file())?;
+// Set once from the global --sync / --no-sync flags
+static SYNC_OVERRIDE: OnceLock<bool> = OnceLock::new();
+// Set when the command rewrote the shell file, for reload_hint
+static SYNCED: AtomicBool = AtomicBool::new(false);
 
-    let shell_type = ShellDetector::detect()?;
+pub fn set_sync_override(sync: bool) {
+    let _ = SYNC_OVERRIDE.set(sync);
+}
 
-    let handler: Box<dyn ShellHandler> = match shell_type {
-        ShellType::Bash => Box::new(BashHandler::new()),
-        ShellType::Zsh => Box::new(ZshHandler::new()),
-        ShellType::Fish => Box::new(FishHandler::new()),
-    };
+// Regenerate the shell file after a change, unless auto_sync is off
+fn sync_after_change(config_manager: &ConfigManager) -> Result<()> {
+    if auto_sync(config_manager)? {
+        sync_aliases(config_manager)?;
+        SYNCED.store(true, Ordering::Relaxed);
+        return Ok(());
+    }
+    warn_if_stale(config_manager)
+}
+
+// Like sync_after_change, but only patches the lines of the one changed alias
+pub(crate) fn sync_alias_after_change(
+    config_manager: &ConfigManager,
+    store: &AliasStore,
+    name: &str,
+    old: Option<&Alias>,
+) -> Result<()> {
+    if auto_sync(config_manager)? {
+        sync_alias(config_manager, store, name, old)?;
+        SYNCED.store(true, Ordering::Relaxed);
+        return Ok(());
+    }
+    warn_if_stale(config_manager)
+}
+
+fn auto_sync(config_manager: &ConfigManager) -> Result<bool> {
+    Ok(match SYNC_OVERRIDE.get() {
+        Some(sync) => *sync,
+        None => config_manager.load_config()?.settings.auto_sync,
+    })
+}
+
+fn warn_if_stale(config_manager: &ConfigManager) -> Result<()> {
+    if shell_aliases_stale(config_manager)? {
+        eprintln!("  Shell aliases are out of date; run 'alx sync' to update them");
+    }
+    Ok(())
+}
+
+// The running shell still has the old aliases until it loads the file again
+pub fn reload_hint() {
+    if SYNCED.load(Ordering::Relaxed) && Term::stdout().is_term() {
+        println!("  Run alx-reload to use the changes in this shell");
+    }
+}
+
+// Save the store, keeping a backup of the previous version when enabled
+pub(crate) fn save_store(config_manager: &ConfigManager, store: &AliasStore) -> Result<()> {
+    backup_aliases(config_manager)?;
+    config_manager.save_aliases(store)
+}
+
+fn backup_aliases(config_manager: &ConfigManager) -> Result<()> {
+    let settings = config_manager.load_config()?.settings;
+    if settings.backup_enabled {
+        backup::create(
+            config_manager.aliases_file(),
+            &config_manager.backup_dir(),
+            settings.backup_keep,
+        )?;
+    }
+    Ok(())
+}
+
+// Parse the command with the current shell when validate_syntax is on
+pub(crate) fn check_syntax(config_manager: &ConfigManager, command: &str) -> Result<()> {
+    if !config_manager.load_config()?.settings.validate_syntax {
+        return Ok(());
+    }
+    let shell_type = current_shell(config_manager)?;
+    if !syntax::check(shell_type, command)? {
+        eprintln!(
+            "Warning: {} is not installed; skipped the syntax check",
+            shell_type.as_str()
+        );
+    }
+    Ok(())
+}
+
+// A group name from the user, cased per group_case and validated. Warns when
+// the group already exists with different casing.
+pub(crate) fn group_name(settings: &Settings, store: &AliasStore, group: &str) -> Result<String> {
+    let group = settings.group_name(group);
+    AliasValidator::validate_group(&group)?;
+    if let Some(existing) = store
+        .groups()
+        .into_iter()
+        .find(|g| *g != group && g.to_lowercase() == group.to_lowercase())
+    {
+        eprintln!(
+            "Warning: group '{}' also exists as '{}'; run 'alx group normalize' to merge them",
+            group, existing
+        );
+    }
+    Ok(group)
+}
 
-    let aliases: Vec<&crate::alias::Alias> = store.list().iter().collect();
-    let content = handler.generate_aliases_file(&aliases);
+// Commit the config directory when it is under git so every change is recorded
+pub(crate) fn record_change(config_manager: &ConfigManager, message: &str) -> Result<()> {
+    tracing::info!("{}", message);
+    if !config_manager.load_config()?.settings.auto_commit {
+        return Ok(());
+    }
 
-    let shell_aliases_file = config_manager.shell_aliases_file();
-    fs::write(&shell_aliases_file, content)?;
+    let mut committed = Vec::new();
+    if GitSync::is_repo(config_manager.config_dir()) {
+        GitSync::open(config_manager.config_dir())?.commit_all(message)?;
+        committed.push(fs::canonicalize(config_manager.config_dir())?);
+    }
 
+    // Files in someone else's repository, such as a dotfiles repository, are
+    // only committed once it tracks them, and nothing else in it is touched
+    let files = [
+        config_manager.aliases_file().as_path(),
+        config_manager.config_file().as_path(),
+    ];
+    for file in files {
+        let Some(git) = file.parent().and_then(GitSync::discover) else {
+            continue;
+        };
+        let Some(workdir) = git.workdir().and_then(|dir| fs::canonicalize(dir).ok()) else {
+            continue;
+        };
+        if committed.contains(&workdir) {
+            continue;
+        }
+        if let Err(e) = git.commit_tracked(&files, message) {
+            eprintln!("Warning: Failed to commit to {}: {}", workdir.display(), e);
+        }
+        committed.push(workdir);
+    }
     Ok(())
 }
 
-pub fn init() -> Result<()> {
+// Run the hook configured for `event` on each alias. The change is already
+// saved by now, so a failing hook only warns.
+pub(crate) fn run_alias_hooks(
+    config_manager: &ConfigManager,
+    event: AliasEvent,
+    aliases: &[(&Alias, Option<&Alias>)],
+) {
+    let hooks = match config_manager.load_config() {
+        Ok(config) => config.hooks,
+        Err(e) => {
+            eprintln!("Warning: {}", e);
+            return;
+        }
+    };
+    let Some(command) = hooks.for_event(event) else {
+        return;
+    };
+    for (alias, previous) in aliases {
+        if let Err(e) = hooks::run_alias_event(event, command, alias, *previous) {
+            eprintln!("Warning: {}", e);
+        }
+    }
+}
+
+pub fn init(repair: bool, rc_file: Option<String>) -> Result<()> {
     let config_manager = ConfigManager::new()?;
+    if repair {
+        return init_repair(&config_manager, rc_file);
+    }
     if config_manager.is_initialized() {
         println!(
             "alx is already initialized at: {:?}",
             config_manager.config_dir()
         );
+        if rc_file.is_some() {
+            println!("Use 'alx init --repair --rc-file <path>' to move the source line");
+        }
         return Ok(());
     }
 
@@ -89,23 +288,60 @@ pub fn init() -> Result<()> {
         }
     };
 
-    let handler: Box<dyn ShellHandler> = match selected_shell {
-        ShellType::Bash => Box::new(BashHandler::new()),
-        ShellType::Zsh => Box::new(ZshHandler::new()),
-        ShellType::Fish => Box::new(FishHandler::new()),
-    };
+    // Remember the choice so syncing doesn't depend on $SHELL
+    let handler = registry::handler(selected_shell);
+    let mut config = config_manager.load_config()?;
+    config.settings.default_shell = Some(selected_shell.as_str().to_string());
+    let mut autoload = None;
+    match rc_file {
+        Some(path) => config.settings.rc_file = Some(portable_rc_file(&path)),
+        None => {
+            autoload = select_autoload(handler.as_ref())?;
+            if autoload.is_none() {
+                config.settings.rc_startup = select_rc_startup(handler.as_ref(), selected_shell)?;
+            }
+        }
+    }
+    if let Some(path) = &autoload {
+        let key = format!("{}_output_path", selected_shell.as_str());
+        config.settings.set(&key, &paths::portable_path(path))?;
+    }
+    config_manager.save_config(&config)?;
+
+    match autoload {
+        Some(path) => {
+            sync_aliases(&ConfigManager::new()?)?;
+            println!(
+                "✓ Aliases will be written to '{}', which {} loads on its own",
+                path.display(),
+                selected_shell.as_str()
+            );
+        }
+        None => offer_source_line(&config_manager, &config.settings, selected_shell)?,
+    }
 
-    let shell_aliases_file = config_manager.shell_aliases_file();
-    let aliases_path = shell_aliases_file.display();
+    println!("\nNext steps:");
+    println!("  1. Add aliases with: alx add <name> <command>");
+    println!("  2. Run 'alx list' to see your aliases");
 
-    let source_line = if selected_shell == ShellType::Fish {
-        format!("source '{}'", aliases_path)
-    } else {
-        format!("[ -f '{}' ] && source '{}'", aliases_path, aliases_path)
-    };
+    Ok(())
+}
+
+// Print the line that loads the aliases and offer to add it to the rc file
+fn offer_source_line(
+    config_manager: &ConfigManager,
+    settings: &Settings,
+    shell_type: ShellType,
+) -> Result<()> {
+    let handler = registry::handler(shell_type);
+
+    // Keep the path $HOME-relative so rc files stay portable across machines
+    let aliases_path = paths::portable_path(&config_manager.shell_aliases_file_for(shell_type));
+
+    let source_line = rc::source_line(shell_type, &aliases_path, settings.source_in_login_shells);
 
     // Ask if user wants to add source line automatically
-    let config_file = handler.config_file_path()?;
+    let config_file = rc_file_path(settings, handler.as_ref())?;
 
     println!("\nTo enable aliases, add the following line to your shell config:");
     println!("     # Add to '{}'", config_file.display());
@@ -141,9 +377,7 @@ pub fn init() -> Result<()> {
             }
 
             // Add comment and source line
-            file_content.push_str("\n# alx - alias manager\n");
-            file_content.push_str(&source_line);
-            file_content.push('\n');
+            file_content.push_str(&rc::block(&source_line));
 
             fs::write(&config_file, file_content)?;
             println!("✓ Added source line to '{}'", config_file.display());
@@ -152,58 +386,432 @@ pub fn init() -> Result<()> {
         }
     }
 
-    println!("\nNext steps:");
-    println!("  1. Add aliases with: alx add <name> <command>");
-    println!("  2. Run 'alx list' to see your aliases");
+    Ok(())
+}
+
+// Fix what a finished init set up, without asking again: missing
+// directories, the generated file and the block in the rc file
+fn init_repair(config_manager: &ConfigManager, rc_file: Option<String>) -> Result<()> {
+    if !config_manager.is_initialized() {
+        return Err(error::AlxError::ConfigError(
+            "alx is not initialized, run 'alx init' first".to_string(),
+        ));
+    }
+    let mut config = config_manager.load_config()?;
+    if let Some(path) = rc_file {
+        config.settings.rc_file = Some(portable_rc_file(&path));
+        config_manager.save_config(&config)?;
+    }
+
+    for dir in [config_manager.shell_dir(), config_manager.backup_dir()] {
+        if !dir.exists() {
+            println!("✓ Recreated {}", dir.display());
+        }
+    }
+    config_manager.init()?;
+    sync_aliases(config_manager)?;
+
+    let shell_type = current_shell(config_manager)?;
+    let handler = registry::handler(shell_type);
+    let aliases_file = config_manager.shell_aliases_file_for(shell_type);
+    // Nothing to source from the rc file
+    if handler.autoload_file_path()?.as_ref() == Some(&aliases_file) {
+        println!(
+            "✓ {} loads '{}' on its own",
+            shell_type.as_str(),
+            aliases_file.display()
+        );
+        return Ok(());
+    }
+    let aliases_path = paths::portable_path(&aliases_file);
+    let source_line = rc::source_line(
+        shell_type,
+        &aliases_path,
+        config.settings.source_in_login_shells,
+    );
+    let config_file = rc_file_path(&config.settings, handler.as_ref())?;
+
+    let content = if config_file.exists() {
+        fs::read_to_string(&config_file)?
+    } else {
+        String::new()
+    };
+    let repaired = rc::repair(&content, &source_line);
+    if repaired.changes.is_empty() {
+        println!("✓ '{}' already loads alx", config_file.display());
+        return Ok(());
+    }
+
+    fs::write(&config_file, &repaired.content)?;
+    for change in &repaired.changes {
+        println!("✓ {}", change);
+    }
+    println!(
+        "\nRepaired '{}'; restart your shell or run:",
+        config_file.display()
+    );
+    println!("     source '{}'", config_file.display());
 
     Ok(())
 }
 
+// The rc file that gets the source line: the rc_file setting, or the shell's
+// usual config file for interactive or login shells as rc_startup says
+fn rc_file_path(settings: &Settings, handler: &dyn ShellHandler) -> Result<std::path::PathBuf> {
+    match &settings.rc_file {
+        Some(path) => {
+            let home = dirs::home_dir().unwrap_or_default();
+            Ok(paths::expand_path(path, &home))
+        }
+        None if settings.rc_startup.as_deref() == Some("login") => handler.login_config_file_path(),
+        None => handler.config_file_path(),
+    }
+}
+
+// Ask whether to write the aliases where the shell loads them by itself, for
+// shells that have such a place
+fn select_autoload(handler: &dyn ShellHandler) -> Result<Option<std::path::PathBuf>> {
+    let Some(path) = handler.autoload_file_path()? else {
+        return Ok(None);
+    };
+    let items = [
+        format!("{} (loaded automatically)", path.display()),
+        format!("Source it from {}", handler.config_file_path()?.display()),
+    ];
+    let selection = Select::new()
+        .with_prompt("Where should the aliases go?")
+        .items(&items)
+        .default(0)
+        .interact()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to select a file: {}", e)))?;
+    Ok((selection == 0).then_some(path))
+}
+
+// Ask whether interactive or login shells should load the aliases, for
+// shells that read a different file for each
+fn select_rc_startup(handler: &dyn ShellHandler, shell_type: ShellType) -> Result<Option<String>> {
+    let interactive = handler.config_file_path()?;
+    let login = handler.login_config_file_path()?;
+    if interactive == login {
+        return Ok(None);
+    }
+
+    let items = [
+        format!("{} (interactive shells)", interactive.display()),
+        format!(
+            "{} (login shells, as macOS terminals open)",
+            login.display()
+        ),
+    ];
+    // A login bash never reads .bashrc, and macOS terminals start login shells
+    let default = usize::from(cfg!(target_os = "macos") && shell_type == ShellType::Bash);
+    let selection = Select::new()
+        .with_prompt("Which file should load the aliases?")
+        .items(&items)
+        .default(default)
+        .interact()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to select rc file: {}", e)))?;
+    Ok(Some(["interactive", "login"][selection].to_string()))
+}
+
+// An rc file given on the command line, made absolute and $HOME-relative
+fn portable_rc_file(path: &str) -> String {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    paths::portable_path(&paths::expand_path(path, &cwd))
+}
+
 pub fn add(
     name: String,
     command: String,
     description: Option<String>,
     group: Option<String>,
+    tags: Vec<String>,
 ) -> Result<()> {
-    AliasValidator::validate_name(&name)?;
-    AliasValidator::validate_command(&command)?;
-
-    if AliasValidator::is_reserved_keyword(&name) {
-        eprintln!("Warning: '{}' is a reserved shell keyword", name);
+    let config_manager = ConfigManager::new()?;
+    let settings = config_manager.load_config()?.settings;
+    let mut store = config_manager.load_aliases()?;
+    let validation = settings.validation();
+    for warning in AliasValidator::check_in_store(&store, &name, &command, validation)? {
+        eprintln!("Warning: {}", warning);
     }
+    check_syntax(&config_manager, &command)?;
 
-    let config_manager = ConfigManager::new()?;
-    let mut store = AliasStore::load(config_manager.aliases_file())?;
+    let inferred = match &group {
+        None if settings.infer_groups => store.infer_group(&command),
+        _ => None,
+    };
+    let name = if store.exists(&name) {
+        let group = group.as_deref().or(inferred.as_deref());
+        free_name(&store, validation, &name, &command, group)?
+    } else {
+        name
+    };
 
     let mut alias = Alias::new(name.clone(), command);
     if let Some(desc) = description {
         alias = alias.with_description(desc);
     }
-    if let Some(grp) = group {
+    if let Some(grp) = group.or(inferred.clone()) {
+        let grp = group_name(&settings, &store, &grp)?;
+        if inferred.is_none() {
+            create_group(&mut store, &grp)?;
+        }
         alias = alias.with_group(grp);
     }
+    if !tags.is_empty() {
+        alias = alias.with_tags(tags);
+    }
 
     store.add(alias)?;
-    store.save(config_manager.aliases_file())?;
+    save_store(&config_manager, &store)?;
 
-    sync_aliases()?;
+    sync_alias_after_change(&config_manager, &store, &name, None)?;
+    record_change(&config_manager, &format!("alx: add alias '{}'", name))?;
+    if let Some(alias) = store.get(&name) {
+        run_alias_hooks(&config_manager, AliasEvent::Add, &[(alias, None)]);
+    }
 
     println!("✓ Added alias: {}", name);
+    let group = store.get(&name).and_then(|a| a.group.as_ref());
+    if let Some(group) = group.filter(|_| inferred.is_some()) {
+        println!(
+            "  Grouped under '{}' from its command (infer_groups)",
+            group
+        );
+    }
+
+    Ok(())
+}
+
+// Record a group named with -g for the first time. At a terminal, ask first,
+// since a new name is often a typo of an existing one, and take a description.
+fn create_group(store: &mut AliasStore, group: &str) -> Result<()> {
+    if store
+        .groups()
+        .iter()
+        .any(|g| g.to_lowercase() == group.to_lowercase())
+    {
+        return Ok(());
+    }
+    if !Term::stdout().is_term() {
+        return store.add_group(Group::new(group.to_string(), None));
+    }
+
+    let create = Confirm::new()
+        .with_prompt(format!("Group '{}' doesn't exist yet. Create it?", group))
+        .default(true)
+        .interact()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to confirm: {}", e)))?;
+    if !create {
+        return Err(error::AlxError::ConfigError(format!(
+            "Group '{}' was not created; nothing was added",
+            group
+        )));
+    }
+    let description: String = Input::new()
+        .with_prompt("Description (optional)")
+        .allow_empty(true)
+        .interact_text()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to read input: {}", e)))?;
+    let description = Some(description.trim().to_string()).filter(|d| !d.is_empty());
+    store.add_group(Group::new(group.to_string(), description))
+}
+
+// Another name for an alias whose name is taken, picked at the prompt from
+// variants such as gst, git-gs or gs2. Without a terminal the error lists
+// them instead.
+fn free_name(
+    store: &AliasStore,
+    validation: Validation,
+    name: &str,
+    command: &str,
+    group: Option<&str>,
+) -> Result<String> {
+    let options: Vec<String> = suggest::variants(name, command, group)
+        .into_iter()
+        .filter(|n| {
+            !store.exists(n)
+                && AliasValidator::check_in_store(store, n, command, validation).is_ok()
+        })
+        .take(4)
+        .collect();
+    if options.is_empty() {
+        return Err(error::AlxError::AliasExists(name.to_string()));
+    }
+    if !Term::stdout().is_term() {
+        let options = quoted_list(options.iter().map(String::as_str)).unwrap_or_default();
+        return Err(error::AlxError::AliasTaken(name.to_string(), options));
+    }
+
+    let existing = store.get(name).map_or("", |a| a.command.as_str());
+    let mut items: Vec<String> = options.iter().map(|n| format!("Add as {}", n)).collect();
+    items.push("Cancel".to_string());
+    let choice = Select::new()
+        .with_prompt(format!("'{}' already runs: {}", name, existing))
+        .items(&items)
+        .default(0)
+        .interact()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to pick a name: {}", e)))?;
+    options
+        .get(choice)
+        .cloned()
+        .ok_or_else(|| error::AlxError::AliasExists(name.to_string()))
+}
+
+pub fn link(
+    name: String,
+    target: String,
+    description: Option<String>,
+    group: Option<String>,
+) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let settings = config_manager.load_config()?.settings;
+    let mut store = config_manager.load_aliases()?;
+    let validation = settings.validation();
+    for warning in AliasValidator::check_in_store(&store, &name, &target, validation)? {
+        eprintln!("Warning: {}", warning);
+    }
+    if name == target {
+        return Err(error::AlxError::InvalidCommand(format!(
+            "'{}' can't link to itself",
+            name
+        )));
+    }
+    let command = store.resolve_link(&target)?.command.clone();
+    // As stored, not as typed, when names ignore case
+    let target = store
+        .get(&target)
+        .map_or(target.clone(), |a| a.name.clone());
+
+    let mut alias = Alias::link(name.clone(), target.clone());
+    if let Some(desc) = description {
+        alias = alias.with_description(desc);
+    }
+    if let Some(grp) = group {
+        alias = alias.with_group(group_name(&settings, &store, &grp)?);
+    }
+
+    store.add(alias)?;
+    save_store(&config_manager, &store)?;
+
+    sync_alias_after_change(&config_manager, &store, &name, None)?;
+    record_change(
+        &config_manager,
+        &format!("alx: link alias '{}' to '{}'", name, target),
+    )?;
+    if let Some(alias) = store.get(&name) {
+        run_alias_hooks(&config_manager, AliasEvent::Add, &[(alias, None)]);
+    }
+
+    println!("✓ Linked {} -> {} ({})", name, target, command);
+
+    Ok(())
+}
+
+pub fn rename(old: String, new: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let settings = config_manager.load_config()?.settings;
+    let mut store = config_manager.load_aliases()?;
+    let previous = store
+        .get(&old)
+        .ok_or_else(|| error::AlxError::AliasNotFound(old.clone()))?
+        .clone();
+    for warning in
+        AliasValidator::check_in_store(&store, &new, &previous.command, settings.validation())?
+    {
+        eprintln!("Warning: {}", warning);
+    }
+
+    let links = store
+        .links_to(&old)
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    store.rename(&old, &new)?;
+    save_store(&config_manager, &store)?;
+
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!("alx: rename alias '{}' to '{}'", old, new),
+    )?;
+    if let Some(alias) = store.get(&new) {
+        run_alias_hooks(
+            &config_manager,
+            AliasEvent::Edit,
+            &[(alias, Some(&previous))],
+        );
+    }
+
+    println!("✓ Renamed {} to {}", old, new);
+    if !links.is_empty() {
+        println!("  Updated links: {}", links.join(", "));
+    }
 
     Ok(())
 }
 
-pub fn remove(names: Vec<String>) -> Result<()> {
+pub fn remove(mut names: Vec<String>, conditions: Vec<String>, force: bool) -> Result<()> {
     let config_manager = ConfigManager::new()?;
-    let mut store = AliasStore::load(config_manager.aliases_file())?;
+    let config = config_manager.load_config()?;
+    let mut store = config_manager.load_aliases()?;
+
+    if !conditions.is_empty() {
+        let filter = alias_filter(None, None, false, &conditions)?;
+        let matched: Vec<String> = store
+            .filter(&filter)
+            .iter()
+            .map(|a| a.name.clone())
+            .filter(|name| !names.contains(name))
+            .collect();
+        if matched.is_empty() && names.is_empty() {
+            return Err(error::AlxError::ConfigError(format!(
+                "No aliases match {}",
+                conditions.join(" ")
+            )));
+        }
+        println!(
+            "Removing {} matching aliases: {}",
+            matched.len(),
+            matched.join(", ")
+        );
+        names.extend(matched);
+    }
 
-    let mut removed_count = 0;
+    let mut removed = Vec::new();
     let mut errors = Vec::new();
+    let mut blocked = None;
 
     for name in &names {
+        let check = store.get(name).map(|a| {
+            config
+                .check_unlocked(a, force)
+                .and_then(|()| check_protected(a, force, "remove"))
+        });
+        if let Some(Err(e)) = check {
+            errors.push(format!("{}: {}", name, e));
+            blocked.get_or_insert(e);
+            continue;
+        }
+
+        // Removing a link's target would leave the link with nothing to run
+        let linked: Vec<&str> = store
+            .links_to(name)
+            .into_iter()
+            .filter(|link| !names.iter().any(|n| n == link))
+            .collect();
+        if !linked.is_empty() {
+            errors.push(format!(
+                "{}: linked from {}; remove or relink them first",
+                name,
+                linked.join(", ")
+            ));
+            continue;
+        }
+
         match store.remove(name) {
-            Ok(_) => {
-                removed_count += 1;
+            Ok(alias) => {
+                removed.push(alias);
             }
             Err(e) => {
                 errors.push(format!("{}: {}", name, e));
@@ -211,9 +819,19 @@ pub fn remove(names: Vec<String>) -> Result<()> {
         }
     }
 
+    let removed_count = removed.len();
     if removed_count > 0 {
-        store.save(config_manager.aliases_file())?;
-        sync_aliases()?;
+        save_store(&config_manager, &store)?;
+        match removed.as_slice() {
+            [alias] => sync_alias_after_change(&config_manager, &store, &alias.name, Some(alias))?,
+            _ => sync_after_change(&config_manager)?,
+        }
+        record_change(
+            &config_manager,
+            &format!("alx: remove aliases {}", names.join(", ")),
+        )?;
+        let removed: Vec<(&Alias, Option<&Alias>)> = removed.iter().map(|a| (a, None)).collect();
+        run_alias_hooks(&config_manager, AliasEvent::Remove, &removed);
     }
 
     if removed_count > 0 {
@@ -232,6 +850,11 @@ pub fn remove(names: Vec<String>) -> Result<()> {
     }
 
     if removed_count == 0 && !errors.is_empty() {
+        // Keep the locked or protected exit code when that is what stopped
+        // the removal
+        if let Some(e) = blocked.filter(|_| errors.len() == 1) {
+            return Err(e);
+        }
         return Err(error::AlxError::ConfigError(
             "No aliases were removed".to_string(),
         ));
@@ -240,204 +863,2814 @@ pub fn remove(names: Vec<String>) -> Result<()> {
     Ok(())
 }
 
-pub fn list(group: Option<String>) -> Result<()> {
+pub fn set_enabled(names: Vec<String>, enabled: bool) -> Result<()> {
     let config_manager = ConfigManager::new()?;
-    let store = AliasStore::load(config_manager.aliases_file())?;
+    let mut store = config_manager.load_aliases()?;
 
-    let aliases: Vec<&Alias> = if let Some(grp) = group {
-        store.list_by_group(&grp)
-    } else {
-        store.list().iter().collect()
-    };
+    let mut changed = Vec::new();
+    for name in &names {
+        let alias = store
+            .get_mut(name)
+            .ok_or_else(|| error::AlxError::AliasNotFound(name.clone()))?;
+        if alias.enabled != enabled {
+            alias.enabled = enabled;
+            alias.updated_at = chrono::Utc::now();
+            changed.push(name.as_str());
+        }
+    }
 
-    if aliases.is_empty() {
-        println!("No aliases found");
+    let action = if enabled { "Enabled" } else { "Disabled" };
+    if changed.is_empty() {
+        println!("Nothing to change; already {}", action.to_lowercase());
         return Ok(());
     }
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_BORDERS_ONLY);
-    table.apply_modifier(UTF8_ROUND_CORNERS);
-    table.set_content_arrangement(ContentArrangement::Dynamic);
-    table.set_header(vec!["Name", "Command", "Description", "Group"]);
-
-    for alias in aliases {
-        let description = alias.description.as_deref().unwrap_or("-");
-        let group = alias.group.as_deref().unwrap_or("-");
-
-        table.add_row(vec![
-            Cell::new(&alias.name),
-            Cell::new(&alias.command),
-            Cell::new(description),
-            Cell::new(group),
-        ]);
-    }
+    save_store(&config_manager, &store)?;
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!("alx: {} {}", action.to_lowercase(), changed.join(", ")),
+    )?;
 
-    println!("{table}");
+    println!(
+        "✓ {} {} aliases: {}",
+        action,
+        changed.len(),
+        changed.join(", ")
+    );
 
     Ok(())
 }
 
-pub fn search(keyword: String) -> Result<()> {
-    let config_manager = ConfigManager::new()?;
-    let store = AliasStore::load(config_manager.aliases_file())?;
+pub fn toggle(group: Option<String>) -> Result<()> {
+    if !Term::stdout().is_term() {
+        return Err(error::AlxError::ConfigError(
+            "alx toggle needs an interactive terminal; use 'alx enable' or 'alx disable'"
+                .to_string(),
+        ));
+    }
 
-    let results = store.search(&keyword);
+    let config_manager = ConfigManager::new()?;
+    let mut store = config_manager.load_aliases()?;
 
-    if results.is_empty() {
-        println!("No aliases found matching '{}'", keyword);
+    let names: Vec<String> = store
+        .list()
+        .iter()
+        .filter(|a| group.is_none() || a.group == group)
+        .map(|a| a.name.clone())
+        .collect();
+    if names.is_empty() {
+        println!("No aliases found");
         return Ok(());
     }
 
-    println!("Search results for '{}':\n", keyword);
-
-    let mut table = Table::new();
-    table.load_preset(UTF8_BORDERS_ONLY);
-    table.apply_modifier(UTF8_ROUND_CORNERS);
-    table.set_content_arrangement(ContentArrangement::Dynamic);
-    table.set_header(vec!["Name", "Command", "Description", "Group"]);
+    let (labels, defaults): (Vec<String>, Vec<bool>) = names
+        .iter()
+        .filter_map(|name| store.get(name))
+        .map(|a| (format!("{} = {}", a.name, a.command), a.enabled))
+        .unzip();
+    let chosen = MultiSelect::new()
+        .with_prompt("Enabled aliases (space to toggle, enter to apply)")
+        .items(&labels)
+        .defaults(&defaults)
+        .interact()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to select aliases: {}", e)))?;
 
-    for alias in results {
-        let description = alias.description.as_deref().unwrap_or("-");
-        let group = alias.group.as_deref().unwrap_or("-");
+    let (mut enabled, mut disabled) = (0, 0);
+    for (i, name) in names.iter().enumerate() {
+        let Some(alias) = store.get_mut(name) else {
+            continue;
+        };
+        let on = chosen.contains(&i);
+        if alias.enabled != on {
+            alias.enabled = on;
+            alias.updated_at = chrono::Utc::now();
+            if on {
+                enabled += 1;
+            } else {
+                disabled += 1;
+            }
+        }
+    }
 
-        table.add_row(vec![
-            Cell::new(&alias.name),
-            Cell::new(&alias.command),
-            Cell::new(description),
-            Cell::new(group),
-        ]);
+    if enabled + disabled == 0 {
+        println!("No changes");
+        return Ok(());
     }
 
-    println!("{table}");
+    save_store(&config_manager, &store)?;
+    sync_after_change(&config_manager)?;
+    record_change(&config_manager, "alx: toggle aliases")?;
+
+    println!("✓ Enabled {} and disabled {} aliases", enabled, disabled);
 
     Ok(())
 }
 
-pub fn edit(
-    name: String,
-    command: Option<String>,
-    description: Option<String>,
+// Protected aliases are only removed or given a new command with --force and
+// a confirmation at the terminal
+fn check_protected(alias: &Alias, force: bool, action: &str) -> Result<()> {
+    if !alias.protected {
+        return Ok(());
+    }
+    if !force {
+        return Err(error::AlxError::AliasProtected(alias.name.clone()));
+    }
+    if !Term::stderr().is_term() {
+        return Err(error::AlxError::ConfigError(format!(
+            "'{}' is protected and there is no terminal to confirm; run 'alx unprotect {}' first",
+            alias.name, alias.name
+        )));
+    }
+    let confirmed = Confirm::new()
+        .with_prompt(format!(
+            "'{}' is protected. Really {} it?",
+            alias.name, action
+        ))
+        .default(false)
+        .interact()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to confirm: {}", e)))?;
+    if !confirmed {
+        return Err(error::AlxError::AliasProtected(alias.name.clone()));
+    }
+    Ok(())
+}
+
+pub fn protect(names: Vec<String>, protect: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut store = config_manager.load_aliases()?;
+    let state = if protect { "protected" } else { "unprotected" };
+
+    let mut changed = Vec::new();
+    for name in &names {
+        let alias = store
+            .get_mut(name)
+            .ok_or_else(|| error::AlxError::AliasNotFound(name.clone()))?;
+        if alias.protected == protect {
+            println!("'{}' is already {}", alias.name, state);
+            continue;
+        }
+        alias.protected = protect;
+        changed.push(alias.name.clone());
+    }
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    // Protection doesn't change the generated shell files
+    save_store(&config_manager, &store)?;
+    record_change(
+        &config_manager,
+        &format!(
+            "alx: {} {}",
+            if protect { "protect" } else { "unprotect" },
+            changed.join(", ")
+        ),
+    )?;
+
+    for name in &changed {
+        println!("✓ '{}' is now {}", name, state);
+    }
+
+    Ok(())
+}
+
+pub fn list(
+    group: Option<String>,
+    conditions: Vec<String>,
+    show_dates: bool,
+    show_last_used: bool,
+    unused_since: Option<String>,
+    sort: Option<String>,
+    format: Option<String>,
+) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+
+    let filter = alias_filter(group.as_deref(), None, false, &conditions)?;
+    let mut aliases = store.filter(&filter);
+
+    let last_used = if show_last_used || unused_since.is_some() {
+        warn_if_untracked(&config_manager)?;
+        Some(roll_up_usage(&config_manager)?.last_used())
+    } else {
+        None
+    };
+    // Aliases added within the period haven't had the chance to be used
+    if let (Some(age), Some(last_used)) = (&unused_since, &last_used) {
+        let cutoff = chrono::Utc::now() - usage::parse_age(age)?;
+        aliases.retain(|a| {
+            let active = last_used
+                .get(&a.name)
+                .map_or(a.created_at, |used| (*used).max(a.created_at));
+            active < cutoff
+        });
+    }
+
+    // Dates sort newest first
+    match sort.as_deref() {
+        Some("name") => aliases.sort_by(|a, b| a.name.cmp(&b.name)),
+        Some("created") => aliases.sort_by_key(|a| Reverse(a.created_at)),
+        Some("updated") => aliases.sort_by_key(|a| Reverse(a.updated_at)),
+        _ => {}
+    }
+
+    if format.as_deref() == Some("alias") {
+        return print_alias_lines(&config_manager, &store, &aliases);
+    }
+    if aliases.is_empty() {
+        println!("No aliases found");
+        return Ok(());
+    }
+
+    let config = config_manager.load_config()?;
+    let mut style = TableStyle::from_settings(&config.settings)
+        .with_dates(show_dates)
+        .with_group_colors(&config.group_colors);
+    if let Some(last_used) = last_used {
+        style = style.with_last_used(last_used);
+    }
+    let table = alias_table(&aliases, &style);
+
+    println!("{table}");
+
+    Ok(())
+}
+
+pub fn show(name: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+    let alias = store.get(&name).ok_or_else(|| not_found(&store, &name))?;
+    let now = chrono::Utc::now();
+    let date = |time: chrono::DateTime<chrono::Utc>| {
+        format!(
+            "{} ({})",
+            time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            relative_time(time, now)
+        )
+    };
+
+    println!("{}", alias.name);
+    if alias.is_link() {
+        println!("  Links to: {}", alias.command);
+        println!("  Command: {}", store.resolve_link(&alias.name)?.command);
+    } else {
+        println!("  Command: {}", alias.command);
+    }
+    println!(
+        "  Description: {}",
+        alias.description.as_deref().unwrap_or("-")
+    );
+    println!("  Group: {}", alias.group.as_deref().unwrap_or("-"));
+    if !alias.tags.is_empty() {
+        println!("  Tags: {}", alias.tags.join(", "));
+    }
+    if let Some(pack) = &alias.pack {
+        println!("  Pack: {}", pack);
+    }
+    println!("  Enabled: {}", if alias.enabled { "yes" } else { "no" });
+    if alias.protected {
+        println!("  Protected: yes");
+    }
+    println!("  Created: {}", date(alias.created_at));
+    println!("  Updated: {}", date(alias.updated_at));
+
+    let last_used = if config_manager.load_config()?.settings.track_usage {
+        roll_up_usage(&config_manager)?
+            .aliases
+            .get(&alias.name)
+            .map_or("never".to_string(), |stats| date(stats.last_used))
+    } else {
+        "not tracked (alx config set track_usage true)".to_string()
+    };
+    println!("  Last used: {}", last_used);
+
+    Ok(())
+}
+
+// Last-used times only cover the time tracking was on
+fn warn_if_untracked(config_manager: &ConfigManager) -> Result<()> {
+    if !config_manager.load_config()?.settings.track_usage {
+        eprintln!(
+            "Warning: usage tracking is off, so uses aren't being recorded (alx config set track_usage true)"
+        );
+    }
+    Ok(())
+}
+
+pub fn search(keyword: String, format: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+
+    let results = store.search(&keyword);
+    if format.as_deref() == Some("alias") {
+        return print_alias_lines(&config_manager, &store, &results);
+    }
+
+    if results.is_empty() {
+        println!("No aliases found matching '{}'", keyword);
+        if let Some(similar) = similar_names(&store, &keyword) {
+            println!("Did you mean {}?", similar);
+        }
+        return Ok(());
+    }
+
+    println!("Search results for '{}':\n", keyword);
+
+    let config = config_manager.load_config()?;
+    let style = TableStyle::from_settings(&config.settings).with_group_colors(&config.group_colors);
+    let table = alias_table(&results, &style);
+
+    println!("{table}");
+
+    Ok(())
+}
+
+// AliasNotFound, naming the closest aliases when there are any
+fn not_found(store: &AliasStore, name: &str) -> error::AlxError {
+    match similar_names(store, name) {
+        Some(similar) => error::AlxError::AliasMisspelled(name.to_string(), similar),
+        None => error::AlxError::AliasNotFound(name.to_string()),
+    }
+}
+
+// The names of the aliases closest to a mistyped `query`, as "'gco'" or
+// "'gco', 'gcm' or 'gc'"
+fn similar_names(store: &AliasStore, query: &str) -> Option<String> {
+    quoted_list(store.similar(query).iter().map(|a| a.name.as_str()))
+}
+
+// Names quoted and joined as "'a', 'b' or 'c'", None when there are none
+fn quoted_list<'a>(names: impl Iterator<Item = &'a str>) -> Option<String> {
+    let names: Vec<String> = names.map(|n| format!("'{}'", n)).collect();
+    match names.split_last() {
+        None => None,
+        Some((last, [])) => Some(last.clone()),
+        Some((last, rest)) => Some(format!("{} or {}", rest.join(", "), last)),
+    }
+}
+
+// Print `aliases` as definitions for the current shell, ready to paste into
+// an rc file. Links get their target's command; secret placeholders are left
+// unresolved so nothing sensitive ends up in chat logs.
+fn print_alias_lines(
+    config_manager: &ConfigManager,
+    store: &AliasStore,
+    aliases: &[&Alias],
+) -> Result<()> {
+    let handler = registry::handler(current_shell(config_manager)?);
+    for alias in aliases {
+        let line = if alias.is_link() {
+            let mut resolved = (*alias).clone();
+            resolved.command = store.resolve_link(&alias.name)?.command.clone();
+            handler.generate_alias_line(&resolved)
+        } else {
+            handler.generate_alias_line(alias)
+        };
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+pub fn grep(pattern: String, ignore_case: bool, context: usize) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+
+    let results = store.grep(&pattern, ignore_case);
+
+    if results.is_empty() {
+        println!("No alias commands match '{}'", pattern);
+        return Ok(());
+    }
+
+    for (alias, offsets) in &results {
+        let snippet = highlight_snippet(&alias.command, offsets, pattern.len(), context);
+        println!("{}: {}", style(&alias.name).bold(), snippet);
+    }
+
+    Ok(())
+}
+
+// Cut the command down to the matched region plus `context` characters on
+// either side, highlighting every match inside it
+fn highlight_snippet(command: &str, offsets: &[usize], len: usize, context: usize) -> String {
+    let first = offsets[0];
+    let last = offsets[offsets.len() - 1] + len;
+
+    let start = command[..first]
+        .char_indices()
+        .rev()
+        .nth(context)
+        .map(|(i, c)| i + c.len_utf8())
+        .unwrap_or(0);
+    let end = command[last..]
+        .char_indices()
+        .nth(context)
+        .map(|(i, _)| last + i)
+        .unwrap_or(command.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+
+    let mut cursor = start;
+    for &offset in offsets {
+        if offset < cursor {
+            continue;
+        }
+        snippet.push_str(&command[cursor..offset]);
+        snippet.push_str(
+            &style(&command[offset..offset + len])
+                .red()
+                .bold()
+                .to_string(),
+        );
+        cursor = offset + len;
+    }
+    snippet.push_str(&command[cursor..end]);
+
+    if end < command.len() {
+        snippet.push('…');
+    }
+
+    snippet
+}
+
+pub fn edit(
+    name: String,
+    command: Option<String>,
+    description: Option<String>,
+    group: Option<String>,
+    tags: Vec<String>,
+    force: bool,
+) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut store = config_manager.load_aliases()?;
+
+    let alias = store
+        .get_mut(&name)
+        .ok_or_else(|| error::AlxError::AliasNotFound(name.clone()))?;
+    config_manager.load_config()?.check_unlocked(alias, force)?;
+    let old = alias.clone();
+
+    // With nothing to change on the command line, edit the alias in an editor
+    if command.is_none() && description.is_none() && group.is_none() && tags.is_empty() {
+        let settings = config_manager.load_config()?.settings;
+        let editor = editor::configured(&settings).ok_or_else(|| {
+            error::AlxError::ConfigError(
+                "No editor configured; run 'alx config set editor <command>' or set $EDITOR"
+                    .to_string(),
+            )
+        })?;
+
+        let Some(edited) = editor::edit_alias(&editor, alias)? else {
+            println!("No changes to alias: {}", name);
+            return Ok(());
+        };
+
+        AliasValidator::validate_command(&edited.command)?;
+        if edited.command != alias.command {
+            check_protected(alias, force, "change the command of")?;
+            alias.kind = AliasKind::Command;
+        }
+        alias.command = edited.command;
+        alias.description = edited.description;
+        alias.group = edited.group;
+        alias.tags = edited.tags;
+        alias.updated_at = chrono::Utc::now();
+    }
+
+    if let Some(cmd) = command {
+        AliasValidator::validate_command(&cmd)?;
+        if cmd != alias.command {
+            check_protected(alias, force, "change the command of")?;
+        }
+        // A new command turns a link into a plain alias
+        alias.kind = AliasKind::Command;
+        alias.update_command(cmd);
+    }
+
+    if let Some(desc) = description {
+        alias.description = Some(desc);
+        alias.updated_at = chrono::Utc::now();
+    }
+
+    if let Some(grp) = group {
+        alias.group = Some(grp);
+        alias.updated_at = chrono::Utc::now();
+    }
+
+    if !tags.is_empty() {
+        alias.tags = tags;
+        alias.updated_at = chrono::Utc::now();
+    }
+
+    let command = alias.command.clone();
+    let group = alias.group.clone();
+    let settings = config_manager.load_config()?.settings;
+    if command != old.command {
+        let profile = settings.validation_profile();
+        for warning in AliasValidator::check_command(&store, &name, &command, profile)? {
+            eprintln!("Warning: {}", warning);
+        }
+        check_syntax(&config_manager, &command)?;
+    }
+    if let Some(group) = group.filter(|g| old.group.as_ref() != Some(g)) {
+        let group = group_name(&settings, &store, &group)?;
+        if let Some(alias) = store.get_mut(&name) {
+            alias.group = Some(group);
+        }
+    }
+
+    save_store(&config_manager, &store)?;
+
+    sync_alias_after_change(&config_manager, &store, &name, Some(&old))?;
+    record_change(&config_manager, &format!("alx: edit alias '{}'", name))?;
+    if let Some(alias) = store.get(&name) {
+        run_alias_hooks(&config_manager, AliasEvent::Edit, &[(alias, Some(&old))]);
+    }
+
+    println!("✓ Updated alias: {}", name);
+
+    Ok(())
+}
+
+// One entry of `alx edit --batch`: the alias to change and the fields to set.
+// An empty description or group clears it.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AliasPatch {
+    name: String,
+    command: Option<String>,
+    description: Option<String>,
+    group: Option<String>,
+    tags: Option<Vec<String>>,
+    enabled: Option<bool>,
+}
+
+// Apply a JSON array of patches from stdin. Everything is checked before
+// anything is saved, so one bad entry leaves the store as it was.
+pub fn edit_batch(force: bool) -> Result<()> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let patches: Vec<AliasPatch> = serde_json::from_str(&input)?;
+
+    let config_manager = ConfigManager::new()?;
+    let config = config_manager.load_config()?;
+    let mut store = config_manager.load_aliases()?;
+
+    // The first state of each alias the batch touched, to hand to the hook
+    let mut updated: Vec<Alias> = Vec::new();
+    for (i, patch) in patches.into_iter().enumerate() {
+        let name = patch.name.clone();
+        let before = store.get(&name).cloned();
+        if let Err(e) = apply_patch(&config_manager, &config, &mut store, patch, force) {
+            eprintln!("Entry {} ({}) failed; nothing was changed", i + 1, name);
+            return Err(e);
+        }
+        if let Some(before) = before.filter(|b| !updated.iter().any(|u| u.name == b.name)) {
+            updated.push(before);
+        }
+    }
+    // Patches that changed nothing leave no trace
+    updated.retain(|old| store.get(&old.name) != Some(old));
+    if updated.is_empty() {
+        println!("No changes");
+        return Ok(());
+    }
+
+    save_store(&config_manager, &store)?;
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!("alx: batch edit {} aliases", updated.len()),
+    )?;
+    let edits: Vec<(&Alias, Option<&Alias>)> = updated
+        .iter()
+        .filter_map(|old| Some((store.get(&old.name)?, Some(old))))
+        .collect();
+    run_alias_hooks(&config_manager, AliasEvent::Edit, &edits);
+
+    println!("✓ Updated {} aliases", updated.len());
+
+    Ok(())
+}
+
+fn apply_patch(
+    config_manager: &ConfigManager,
+    config: &Config,
+    store: &mut AliasStore,
+    patch: AliasPatch,
+    force: bool,
+) -> Result<()> {
+    let group = match patch.group.as_deref() {
+        Some("") => Some(None),
+        Some(group) => Some(Some(group_name(&config.settings, store, group)?)),
+        None => None,
+    };
+    let alias = store
+        .get_mut(&patch.name)
+        .ok_or_else(|| error::AlxError::AliasNotFound(patch.name.clone()))?;
+    config.check_unlocked(alias, force)?;
+    let old = alias.clone();
+
+    if let Some(command) = patch.command.filter(|c| *c != alias.command) {
+        AliasValidator::validate_command(&command)?;
+        // stdin holds the patches, so there is no way to confirm
+        if alias.protected {
+            return Err(error::AlxError::AliasProtected(alias.name.clone()));
+        }
+        alias.kind = AliasKind::Command;
+        alias.command = command;
+    }
+    if let Some(description) = patch.description {
+        alias.description = Some(description).filter(|d| !d.is_empty());
+    }
+    if let Some(group) = group {
+        alias.group = group;
+    }
+    if let Some(tags) = patch.tags {
+        alias.tags = tags;
+    }
+    if let Some(enabled) = patch.enabled {
+        alias.enabled = enabled;
+    }
+    if *alias == old {
+        return Ok(());
+    }
+    alias.updated_at = chrono::Utc::now();
+
+    let command = alias.command.clone();
+    if command != old.command {
+        let profile = config.settings.validation_profile();
+        for warning in AliasValidator::check_command(store, &patch.name, &command, profile)? {
+            eprintln!("Warning: {}: {}", patch.name, warning);
+        }
+        check_syntax(config_manager, &command)?;
+    }
+    Ok(())
+}
+
+pub fn export(
+    output: Option<String>,
+    format: Option<String>,
     group: Option<String>,
+    tag: Option<String>,
+    enabled_only: bool,
+    conditions: Vec<String>,
+    split_by_group: bool,
 ) -> Result<()> {
     let config_manager = ConfigManager::new()?;
-    let mut store = AliasStore::load(config_manager.aliases_file())?;
+    let store = config_manager.load_aliases()?;
+    let settings = config_manager.load_config()?.settings;
+
+    let format = format
+        .or(settings.export_format)
+        .unwrap_or_else(|| "json".to_string());
+    let filter = alias_filter(group.as_deref(), tag.as_deref(), enabled_only, &conditions)?;
+    let aliases = store.filter(&filter);
+
+    let output_path = match output {
+        Some(path) if path == "-" => None,
+        Some(path) => Some(std::path::PathBuf::from(path)),
+        None => settings
+            .export_path
+            .map(|path| paths::expand_path(&path, config_manager.config_dir())),
+    };
+
+    if split_by_group {
+        let dir = output_path.ok_or_else(|| {
+            error::AlxError::ConfigError(
+                "--split-by-group writes one file per group; give a directory with -o".to_string(),
+            )
+        })?;
+        if dir.is_file() {
+            return Err(error::AlxError::ConfigError(format!(
+                "{} is a file; --split-by-group needs a directory",
+                dir.display()
+            )));
+        }
+        fs::create_dir_all(&dir)?;
+
+        // Ungrouped aliases go where the generated shell file puts them
+        let mut groups: BTreeMap<&str, Vec<&Alias>> = BTreeMap::new();
+        for alias in aliases {
+            let group = alias.group.as_deref().unwrap_or("general");
+            groups.entry(group).or_default().push(alias);
+        }
+        for (group, aliases) in &groups {
+            let path = dir.join(export::group_file_name(group, &format));
+            fs::write(&path, export::render(&store, aliases, &format)?)?;
+            println!("  {}", path.display());
+        }
+        println!("✓ Exported {} groups to: {}", groups.len(), dir.display());
+        return Ok(());
+    }
+
+    let content = export::render(&store, &aliases, &format)?;
+    match output_path {
+        Some(mut path) => {
+            // Exporting into a directory, e.g. a dotfiles repository
+            if path.is_dir() {
+                path = path.join(format!("aliases.{}", export::extension(&format)));
+            }
+            fs::write(&path, content)?;
+            println!("✓ Exported aliases to: {}", path.display());
+        }
+        None => println!("{}", content),
+    }
+
+    Ok(())
+}
+
+// The filter for the usual --group, --tag and --enabled-only options, plus
+// any --where conditions
+fn alias_filter(
+    group: Option<&str>,
+    tag: Option<&str>,
+    enabled_only: bool,
+    conditions: &[String],
+) -> Result<Filter> {
+    let mut filter = Filter::new();
+    if let Some(group) = group {
+        filter = filter.with_group(group);
+    }
+    if let Some(tag) = tag {
+        filter = filter.with_tag(tag);
+    }
+    if enabled_only {
+        filter = filter.with_enabled(true);
+    }
+    conditions
+        .iter()
+        .try_fold(filter, |filter, condition| filter.with_condition(condition))
+}
+
+pub fn share(name: Option<String>) -> Result<()> {
+    let name = name.ok_or_else(|| {
+        error::AlxError::ConfigError(
+            "Give an alias name, or use 'alx share gist' to share many".to_string(),
+        )
+    })?;
+
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+    let alias = store
+        .get(&name)
+        .ok_or_else(|| error::AlxError::AliasNotFound(name.clone()))?;
+    // The receiver may not have the target, so share what it runs
+    let command = &store.resolve_link(&alias.name)?.command;
+
+    let mut line = format!(
+        "alx add {} {}",
+        shell::quote(&alias.name),
+        shell::quote(command)
+    );
+    if let Some(desc) = &alias.description {
+        line.push_str(&format!(" -d {}", shell::quote(desc)));
+    }
+    if let Some(grp) = &alias.group {
+        line.push_str(&format!(" -g {}", shell::quote(grp)));
+    }
+    for tag in &alias.tags {
+        line.push_str(&format!(" -t {}", shell::quote(tag)));
+    }
+
+    println!("{}", line);
+    match clipboard::copy(&line) {
+        Ok(()) => eprintln!("✓ Copied to clipboard"),
+        Err(e) => eprintln!("  Not copied: {}", e),
+    }
+
+    Ok(())
+}
+
+pub fn copy(name: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+    let alias = store.resolve_link(&name)?;
+
+    clipboard::copy(&alias.command)?;
+    println!("✓ Copied to clipboard: {}", alias.command);
+
+    Ok(())
+}
+
+pub fn share_gist(
+    format: String,
+    group: Option<String>,
+    tag: Option<String>,
+    enabled_only: bool,
+    public: bool,
+    description: Option<String>,
+) -> Result<()> {
+    if format != "json" && format != "toml" {
+        return Err(error::AlxError::ConfigError(format!(
+            "Gists can only be shared as json or toml, not {}",
+            format
+        )));
+    }
+
+    let token = gist::token_from_env().ok_or_else(|| {
+        error::AlxError::ConfigError(
+            "Set ALX_GITHUB_TOKEN or GITHUB_TOKEN to a token with the gist scope".to_string(),
+        )
+    })?;
+
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+
+    let filter = alias_filter(group.as_deref(), tag.as_deref(), enabled_only, &[])?;
+    let aliases = store.filter(&filter);
+    if aliases.is_empty() {
+        println!("No aliases to share");
+        return Ok(());
+    }
+
+    let content = export::render(&store, &aliases, &format)?;
+    let description = description.unwrap_or_else(|| "Aliases shared with alx".to_string());
+    let url = gist::publish(
+        &format!("aliases.{}", format),
+        &content,
+        &description,
+        public,
+        &token,
+    )?;
+
+    println!("✓ Shared {} aliases: {}", aliases.len(), url);
+
+    Ok(())
+}
+
+pub fn import(
+    file: String,
+    from: Option<String>,
+    report: Option<String>,
+    map_group: Vec<String>,
+    into_group: Option<String>,
+) -> Result<()> {
+    let groups = importer::GroupMap::parse(&map_group, into_group)?;
+    let config_manager = ConfigManager::new()?;
+    // Exported JSON files are streamed rather than read into memory
+    if from.is_none() && file.ends_with(".json") && !file.starts_with("gist:") {
+        let progress = report::spinner("Reading");
+        let tick = |n| report::advance(&progress, n);
+        let reader = std::io::BufReader::new(fs::File::open(&file)?);
+        let aliases = importer::read_json(reader, tick)?;
+        progress.finish_and_clear();
+        return import_aliases(&config_manager, aliases, &groups, &file, report);
+    }
+    if from.is_none() && file.ends_with(".jsonl") {
+        let progress = report::spinner("Reading");
+        let tick = |n| report::advance(&progress, n);
+        let reader = std::io::BufReader::new(fs::File::open(&file)?);
+        let aliases = importer::read_jsonl(reader, tick)?;
+        progress.finish_and_clear();
+        return import_aliases(&config_manager, aliases, &groups, &file, report);
+    }
+
+    let (file, content) = match file.strip_prefix("gist:") {
+        Some(id) => gist::fetch(id, gist::token_from_env().as_deref())?,
+        None if file == "-" => {
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content)?;
+            (file, content)
+        }
+        None => {
+            let content = fs::read_to_string(&file)?;
+            (file, content)
+        }
+    };
+
+    let imported_store = match &from {
+        Some(tool) => AliasStore::from_aliases(importer::parse(tool, &content)?),
+        None => parse_store(&file, &content)?,
+    };
+
+    import_aliases(
+        &config_manager,
+        imported_store.aliases,
+        &groups,
+        &file,
+        report,
+    )
+}
+
+// An exported store, as JSON or TOML by the file's extension, or whichever
+// parses
+fn parse_store(file: &str, content: &str) -> Result<AliasStore> {
+    if file.ends_with(".json") {
+        Ok(serde_json::from_str(content)?)
+    } else if file.ends_with(".toml") {
+        Ok(toml::from_str(content)?)
+    } else {
+        Ok(serde_json::from_str(content).or_else(|_| toml::from_str(content))?)
+    }
+}
+
+// Merge an exported store into ours. Without --base the two share no history,
+// so nothing gets removed. Either way, aliases whose commands agree are
+// settled by taking the newer version, and only a differing command is put
+// to --strategy or the picker.
+pub fn merge(
+    file: String,
+    base: Option<String>,
+    strategy: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
+    let strategy = strategy.as_deref().map(MergeStrategy::parse).transpose()?;
+    let theirs = parse_store(&file, &fs::read_to_string(&file)?)?;
+    let base = match &base {
+        Some(path) => parse_store(path, &fs::read_to_string(path)?)?,
+        None => AliasStore::new(),
+    };
+    let config_manager = ConfigManager::new()?;
+    let settings = config_manager.load_config()?.settings;
+    let ours = config_manager.load_aliases()?;
+    for alias in &theirs.aliases {
+        AliasValidator::check_in_store(
+            &AliasStore::new(),
+            &alias.name,
+            &alias.command,
+            settings.validation(),
+        )
+        .and_then(|_| {
+            alias
+                .group
+                .as_deref()
+                .map_or(Ok(()), AliasValidator::validate_group)
+        })
+        .map_err(|e| error::AlxError::ConfigError(format!("{}: {}", file, e)))?;
+    }
+
+    // Settle every conflict before merging, so nothing is half done when the
+    // picker is cancelled
+    let interactive = Term::stdout().is_term() && Term::stderr().is_term();
+    let mut picks = HashMap::new();
+    let mut unresolved = Vec::new();
+    let mut same_command = Vec::new();
+    for conflict in merge::conflicts(&base, &ours, &theirs) {
+        let pick = if !conflict.changes_command() {
+            same_command.push(conflict.name);
+            MergeStrategy::Newest.resolve(conflict.ours, conflict.theirs)
+        } else if conflict.ours.is_some_and(|a| a.protected) {
+            println!("  Kept protected alias: {}", conflict.name);
+            conflict.ours
+        } else if let Some(strategy) = strategy {
+            strategy.resolve(conflict.ours, conflict.theirs)
+        } else if interactive {
+            pick_version(&conflict)?
+        } else {
+            unresolved.push(conflict.name);
+            continue;
+        };
+        picks.insert(conflict.name, pick);
+    }
+    if !unresolved.is_empty() {
+        return Err(error::AlxError::ConfigError(format!(
+            "Commands differ for {}; pass --strategy or run in a terminal to pick",
+            unresolved.join(", ")
+        )));
+    }
+
+    let (merged, report) = merge::merge_stores_with(&base, &ours, &theirs, |conflict| {
+        picks.get(conflict.name).copied().unwrap_or(conflict.ours)
+    });
+    let mut updated = report.updated.clone();
+    let mut conflicts = Vec::new();
+    for name in &report.conflicts {
+        match merged.get(name) {
+            merged_alias if merged_alias == ours.get(name) => {}
+            _ if same_command.contains(&name.as_str()) => updated.push(name.clone()),
+            Some(_) => conflicts.push(format!("{} (took theirs)", name)),
+            None => conflicts.push(format!("{} (removed)", name)),
+        }
+    }
+    for (label, names) in [
+        ("Added", &report.added),
+        ("Updated", &updated),
+        ("Removed", &report.removed),
+        ("Resolved", &conflicts),
+    ] {
+        if !names.is_empty() {
+            println!("  {}: {}", label, names.join(", "));
+        }
+    }
+
+    if merged.aliases == ours.aliases {
+        println!("Already up to date with {}", file);
+        return Ok(());
+    }
+    if dry_run {
+        println!("Dry run; nothing was changed");
+        return Ok(());
+    }
+
+    save_store(&config_manager, &merged)?;
+    sync_after_change(&config_manager)?;
+    record_change(&config_manager, &format!("alx: merge {}", file))?;
+
+    println!("✓ Merged {}", file);
+
+    Ok(())
+}
+
+// Ask which version of a conflicting alias to keep
+fn pick_version<'a>(conflict: &merge::Conflict<'a>) -> Result<Option<&'a Alias>> {
+    let command =
+        |alias: Option<&Alias>| alias.map_or("(removed)".to_string(), |a| a.command.clone());
+    let items = [
+        format!("Keep ours:   {}", command(conflict.ours)),
+        format!("Take theirs: {}", command(conflict.theirs)),
+    ];
+    let choice = Select::new()
+        .with_prompt(format!("'{}' differs", conflict.name))
+        .items(&items)
+        .default(0)
+        .interact()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to pick a version: {}", e)))?;
+    Ok(if choice == 0 {
+        conflict.ours
+    } else {
+        conflict.theirs
+    })
+}
+
+fn import_aliases(
+    config_manager: &ConfigManager,
+    mut aliases: Vec<Alias>,
+    groups: &importer::GroupMap,
+    file: &str,
+    report: Option<String>,
+) -> Result<()> {
+    for group in groups.apply(&mut aliases) {
+        eprintln!("Warning: no imported alias is in group '{}'", group);
+    }
+
+    let mut store = config_manager.load_aliases()?;
+    let summary = add_new_aliases(config_manager, &mut store, aliases, "Importing")?;
+
+    save_store(config_manager, &store)?;
+
+    sync_after_change(config_manager)?;
+    record_change(
+        config_manager,
+        &format!("alx: import {} aliases from {}", summary.added.len(), file),
+    )?;
+
+    println!("✓ Imported {} aliases", summary.added.len());
+    summary.print_table();
+
+    if let Some(path) = report {
+        summary.write_report(std::path::Path::new(&path), file)?;
+        println!("✓ Wrote import report to {}", path);
+    }
+
+    Ok(())
+}
+
+pub fn git_init() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    if !config_manager.is_initialized() {
+        return Err(error::AlxError::ConfigError(
+            "alx is not initialized, run 'alx init' first".to_string(),
+        ));
+    }
+
+    if GitSync::is_repo(config_manager.config_dir()) {
+        println!(
+            "{:?} is already a git repository",
+            config_manager.config_dir()
+        );
+        return Ok(());
+    }
+
+    GitSync::init(config_manager.config_dir())?;
+    println!(
+        "✓ Initialized git repository at: {:?}",
+        config_manager.config_dir()
+    );
+    println!("\nNext, set a remote with: alx git remote <url>");
+
+    Ok(())
+}
+
+pub fn git_remote(url: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    GitSync::open(config_manager.config_dir())?.set_remote(&url)?;
+
+    println!("✓ Remote set to: {}", url);
+
+    Ok(())
+}
+
+pub fn git_push() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let git = GitSync::open(config_manager.config_dir())?;
+
+    git.commit_all("alx: save local changes")?;
+    git.push()?;
+
+    println!("✓ Pushed aliases");
+
+    Ok(())
+}
+
+pub fn git_pull(strategy: String) -> Result<()> {
+    let strategy = MergeStrategy::parse(&strategy)?;
+    let config_manager = ConfigManager::new()?;
+    let git = GitSync::open(config_manager.config_dir())?;
+    backup_aliases(&config_manager)?;
+
+    match git.pull(strategy)? {
+        PullOutcome::UpToDate => {
+            println!("Already up to date");
+            return Ok(());
+        }
+        PullOutcome::FastForward => println!("✓ Pulled latest aliases"),
+        PullOutcome::Merged(report) => {
+            println!(
+                "✓ Merged remote aliases ({} added, {} updated, {} removed)",
+                report.added.len(),
+                report.updated.len(),
+                report.removed.len()
+            );
+            if !report.conflicts.is_empty() {
+                println!(
+                    "  Resolved {} conflicts: {}",
+                    report.conflicts.len(),
+                    report.conflicts.join(", ")
+                );
+            }
+        }
+    }
+
+    sync_after_change(&config_manager)?;
+
+    Ok(())
+}
+
+pub fn git_status() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let status = GitSync::open(config_manager.config_dir())?.status()?;
+
+    println!("Branch: {}", status.branch);
+    match status.remote_url {
+        Some(url) => println!("Remote: {}", url),
+        None => println!("Remote: (none)"),
+    }
+    println!(
+        "Ahead {}, behind {} (as of the last pull)",
+        status.ahead, status.behind
+    );
+    if status.changed_files == 0 {
+        println!("Working tree clean");
+    } else {
+        println!("Uncommitted changes: {} files", status.changed_files);
+    }
+
+    Ok(())
+}
+
+pub fn preset_list() -> Result<()> {
+    let mut table = Table::new();
+    table.load_preset(UTF8_BORDERS_ONLY);
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Preset", "Aliases", "Description"]);
+
+    for preset in Preset::all()? {
+        table.add_row(vec![
+            Cell::new(&preset.name),
+            Cell::new(preset.alias_count()),
+            Cell::new(&preset.description),
+        ]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
+pub fn preset_apply(name: String, group: Option<String>, overwrite: bool) -> Result<()> {
+    let preset = Preset::find(&name)?;
+    let group = group.unwrap_or_else(|| preset.name.clone());
+
+    let config_manager = ConfigManager::new()?;
+    let config = config_manager.load_config()?;
+    let mut store = config_manager.load_aliases()?;
+
+    let mut added = 0;
+    let mut replaced = 0;
+    let mut unchanged = 0;
+    let mut conflicts = Vec::new();
+    let mut protected = Vec::new();
+    let mut locked = Vec::new();
+
+    for alias in preset.to_aliases(&group) {
+        let existing = store.get(&alias.name).cloned();
+
+        match existing {
+            None => {
+                store.add(alias)?;
+                added += 1;
+            }
+            Some(existing) if existing.command == alias.command => unchanged += 1,
+            Some(existing) if existing.protected => protected.push(alias.name),
+            Some(existing) if overwrite && config.check_unlocked(&existing, false).is_err() => {
+                locked.push(alias.name)
+            }
+            Some(_) if overwrite => {
+                store.remove(&alias.name)?;
+                store.add(alias)?;
+                replaced += 1;
+            }
+            Some(existing) => conflicts.push((alias.name, existing.command, alias.command)),
+        }
+    }
+
+    if added + replaced > 0 {
+        save_store(&config_manager, &store)?;
+        sync_after_change(&config_manager)?;
+        record_change(
+            &config_manager,
+            &format!("alx: apply preset '{}'", preset.name),
+        )?;
+    }
+
+    println!(
+        "✓ Applied preset '{}' to group '{}': {} added, {} replaced, {} already present",
+        preset.name, group, added, replaced, unchanged
+    );
+
+    if !conflicts.is_empty() {
+        eprintln!("\nSkipped {} conflicting aliases:", conflicts.len());
+        for (name, current, wanted) in &conflicts {
+            eprintln!("  {}: '{}' (preset: '{}')", name, current, wanted);
+        }
+        eprintln!("\nRe-run with --overwrite to replace them");
+    }
+    if !protected.is_empty() {
+        eprintln!(
+            "\nKept {} protected aliases: {}; run 'alx unprotect' first to replace them",
+            protected.len(),
+            protected.join(", ")
+        );
+    }
+    if !locked.is_empty() {
+        eprintln!(
+            "\nSkipped {} aliases in locked groups: {}; run 'alx group unlock' first to replace them",
+            locked.len(),
+            locked.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+fn pack_index_url(config_manager: &ConfigManager) -> Result<String> {
+    config_manager
+        .load_config()?
+        .settings
+        .pack_index_url
+        .ok_or_else(|| {
+            error::AlxError::ConfigError(
+                "No pack index configured, set pack_index_url in config.toml".to_string(),
+            )
+        })
+}
+
+// Add a pack's aliases, skipping names already used by other aliases.
+// Returns the names that were skipped.
+fn install_pack_aliases(store: &mut AliasStore, pack: &Preset, group: &str) -> Result<Vec<String>> {
+    let mut skipped = Vec::new();
+    for mut alias in pack.to_aliases(group) {
+        if store.exists(&alias.name) {
+            skipped.push(alias.name);
+            continue;
+        }
+        alias.pack = Some(pack.name.clone());
+        store.add(alias)?;
+    }
+    Ok(skipped)
+}
+
+pub fn pack_search(query: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let index_url = pack_index_url(&config_manager)?;
+    let index = PackIndex::fetch(&index_url)?;
+    let store = config_manager.load_aliases()?;
+
+    let results = index.search(query.as_deref().unwrap_or_default());
+    if results.is_empty() {
+        println!("No packs found");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_BORDERS_ONLY);
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Pack", "Version", "Installed", "Description"]);
+
+    for entry in results {
+        let installed = store
+            .find_pack(&entry.name)
+            .map(|p| p.version.as_str())
+            .unwrap_or("-");
+        table.add_row(vec![
+            Cell::new(&entry.name),
+            Cell::new(&entry.version),
+            Cell::new(installed),
+            Cell::new(&entry.description),
+        ]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
+pub fn pack_install(name: String, group: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut store = config_manager.load_aliases()?;
+
+    if let Some(installed) = store.find_pack(&name) {
+        println!(
+            "Pack '{}' {} is already installed, use 'alx pack update' to upgrade it",
+            name, installed.version
+        );
+        return Ok(());
+    }
+
+    let index_url = pack_index_url(&config_manager)?;
+    let index = PackIndex::fetch(&index_url)?;
+    let entry = index.find(&name)?;
+    let pack = entry.fetch(&index_url)?;
+    let group = group.unwrap_or_else(|| entry.name.clone());
+
+    let skipped = install_pack_aliases(&mut store, &pack, &group)?;
+    store.packs.push(InstalledPack {
+        name: entry.name.clone(),
+        version: entry.version.clone(),
+        group: group.clone(),
+        installed_at: chrono::Utc::now(),
+    });
+
+    save_store(&config_manager, &store)?;
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!("alx: install pack '{}' {}", entry.name, entry.version),
+    )?;
+
+    println!(
+        "✓ Installed pack '{}' {} ({} aliases in group '{}')",
+        entry.name,
+        entry.version,
+        pack.alias_count() - skipped.len(),
+        group
+    );
+    if !skipped.is_empty() {
+        eprintln!("  Skipped existing aliases: {}", skipped.join(", "));
+    }
+
+    Ok(())
+}
+
+pub fn pack_update(name: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut store = config_manager.load_aliases()?;
+
+    let names: Vec<String> = match name {
+        Some(name) => {
+            if store.find_pack(&name).is_none() {
+                return Err(error::AlxError::ConfigError(format!(
+                    "Pack '{}' is not installed",
+                    name
+                )));
+            }
+            vec![name]
+        }
+        None => store.packs.iter().map(|p| p.name.clone()).collect(),
+    };
+
+    if names.is_empty() {
+        println!("No packs installed");
+        return Ok(());
+    }
+
+    let index_url = pack_index_url(&config_manager)?;
+    let index = PackIndex::fetch(&index_url)?;
+    let mut updated = Vec::new();
+
+    for name in names {
+        let entry = index.find(&name)?;
+        let Some(installed) = store.find_pack(&name).cloned() else {
+            continue;
+        };
+        if installed.version == entry.version {
+            println!("  {} is up to date ({})", name, installed.version);
+            continue;
+        }
+
+        // Replace the pack as a unit, keeping the group it was installed into
+        let pack = entry.fetch(&index_url)?;
+        let linked = store.pack_links(&name);
+        store.remove_pack(&name);
+        let skipped = install_pack_aliases(&mut store, &pack, &installed.group)?;
+        // Nothing is saved yet, so stopping here leaves the store as it was
+        let dropped: Vec<String> = linked
+            .iter()
+            .filter(|(_, target)| !store.exists(target))
+            .map(|(link, target)| format!("{} (linked from {})", target, link))
+            .collect();
+        if !dropped.is_empty() {
+            return Err(error::AlxError::ConfigError(format!(
+                "{} {} drops {}; remove or relink them first",
+                name,
+                entry.version,
+                dropped.join(", ")
+            )));
+        }
+        store.packs.push(InstalledPack {
+            version: entry.version.clone(),
+            installed_at: chrono::Utc::now(),
+            ..installed.clone()
+        });
+
+        println!(
+            "✓ Updated {} {} -> {}",
+            name, installed.version, entry.version
+        );
+        if !skipped.is_empty() {
+            eprintln!("  Skipped existing aliases: {}", skipped.join(", "));
+        }
+        updated.push(name);
+    }
+
+    if !updated.is_empty() {
+        save_store(&config_manager, &store)?;
+        sync_after_change(&config_manager)?;
+        record_change(
+            &config_manager,
+            &format!("alx: update packs {}", updated.join(", ")),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn pack_remove(name: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut store = config_manager.load_aliases()?;
+
+    let count = store.list_by_pack(&name).len();
+    let linked: Vec<String> = store
+        .pack_links(&name)
+        .iter()
+        .map(|(link, target)| format!("{} -> {}", link, target))
+        .collect();
+    if !linked.is_empty() {
+        return Err(error::AlxError::ConfigError(format!(
+            "Pack '{}' has aliases linked from {}; remove or relink them first",
+            name,
+            linked.join(", ")
+        )));
+    }
+    if store.remove_pack(&name).is_none() {
+        return Err(error::AlxError::ConfigError(format!(
+            "Pack '{}' is not installed",
+            name
+        )));
+    }
+
+    save_store(&config_manager, &store)?;
+    sync_after_change(&config_manager)?;
+    record_change(&config_manager, &format!("alx: remove pack '{}'", name))?;
+
+    println!("✓ Removed pack '{}' ({} aliases)", name, count);
+
+    Ok(())
+}
+
+pub fn pack_list() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+
+    if store.packs.is_empty() {
+        println!("No packs installed");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_BORDERS_ONLY);
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Pack", "Version", "Group", "Aliases", "Installed"]);
+
+    for pack in &store.packs {
+        table.add_row(vec![
+            Cell::new(&pack.name),
+            Cell::new(&pack.version),
+            Cell::new(&pack.group),
+            Cell::new(store.list_by_pack(&pack.name).len()),
+            Cell::new(pack.installed_at.format("%Y-%m-%d")),
+        ]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
+pub fn backup_list() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let backups = backup::list(&config_manager.backup_dir())?;
+
+    if backups.is_empty() {
+        println!("No backups found");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_BORDERS_ONLY);
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["ID", "Created", "Size"]);
+
+    for b in &backups {
+        let created = b
+            .created_at()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        table.add_row(vec![
+            Cell::new(&b.id),
+            Cell::new(created),
+            Cell::new(format!("{} B", b.size)),
+        ]);
+    }
+
+    println!("{table}");
+    println!("\nTotal: {} backups", backups.len());
+
+    Ok(())
+}
+
+pub fn backup_restore(id: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let backup = backup::find(&config_manager.backup_dir(), &id)?;
+
+    // Make sure the backup is valid before replacing anything
+    let store = AliasStore::load(&backup.path)?;
+    save_store(&config_manager, &store)?;
+
+    sync_after_change(&config_manager)?;
+    record_change(&config_manager, &format!("alx: restore backup {}", id))?;
+
+    println!(
+        "✓ Restored {} aliases from backup '{}'",
+        store.list().len(),
+        id
+    );
+
+    Ok(())
+}
+
+pub fn snapshot_create(name: String, force: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    snapshot::create(
+        config_manager.aliases_file(),
+        &config_manager.snapshot_dir(),
+        &name,
+        force,
+    )?;
+    record_change(&config_manager, &format!("alx: create snapshot '{}'", name))?;
+
+    println!("✓ Saved snapshot '{}'", name);
+
+    Ok(())
+}
+
+pub fn snapshot_restore(name: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let path = snapshot::find(&config_manager.snapshot_dir(), &name)?;
+
+    let store = AliasStore::load(&path)?;
+    save_store(&config_manager, &store)?;
+
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!("alx: restore snapshot '{}'", name),
+    )?;
+
+    println!(
+        "✓ Restored {} aliases from snapshot '{}'",
+        store.list().len(),
+        name
+    );
+
+    Ok(())
+}
+
+pub fn snapshot_diff(name: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let path = snapshot::find(&config_manager.snapshot_dir(), &name)?;
+
+    let snapshot_store = AliasStore::load(&path)?;
+    let store = config_manager.load_aliases()?;
+    let changes = diff_stores(&snapshot_store, &store);
+
+    if changes.is_empty() {
+        println!("No changes since snapshot '{}'", name);
+        return Ok(());
+    }
+
+    for change in &changes {
+        match change {
+            Change::Added(a) => {
+                println!("{}", style(format!("+ {} = {}", a.name, a.command)).green())
+            }
+            Change::Removed(a) => {
+                println!("{}", style(format!("- {} = {}", a.name, a.command)).red())
+            }
+            Change::Changed { from, to } => {
+                println!("{}", style(format!("~ {}", to.name)).yellow());
+                if from.command != to.command {
+                    println!("    command: {} -> {}", from.command, to.command);
+                }
+                if from.description != to.description {
+                    println!(
+                        "    description: {} -> {}",
+                        from.description.as_deref().unwrap_or("-"),
+                        to.description.as_deref().unwrap_or("-")
+                    );
+                }
+                if from.group != to.group {
+                    println!(
+                        "    group: {} -> {}",
+                        from.group.as_deref().unwrap_or("-"),
+                        to.group.as_deref().unwrap_or("-")
+                    );
+                }
+                if from.tags != to.tags {
+                    println!(
+                        "    tags: [{}] -> [{}]",
+                        from.tags.join(", "),
+                        to.tags.join(", ")
+                    );
+                }
+                if from.enabled != to.enabled {
+                    println!("    enabled: {} -> {}", from.enabled, to.enabled);
+                }
+            }
+        }
+    }
+
+    println!("\n{} changes since snapshot '{}'", changes.len(), name);
+
+    Ok(())
+}
+
+pub fn snapshot_list() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let names = snapshot::list(&config_manager.snapshot_dir())?;
+
+    if names.is_empty() {
+        println!("No snapshots found");
+        return Ok(());
+    }
+
+    for name in &names {
+        println!("  {}", name);
+    }
+
+    Ok(())
+}
+
+pub fn log_tail(lines: usize) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let lines = logging::tail(&config_manager.log_dir(), lines)?;
+
+    if lines.is_empty() {
+        println!("No log entries found");
+        if !config_manager.load_config()?.settings.log_enabled {
+            println!("Enable logging with: alx config set log_enabled true");
+        }
+        return Ok(());
+    }
+
+    for line in lines {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+pub fn config_get(key: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let config = config_manager.load_config()?;
+
+    println!("{}", config.get(&key)?);
+
+    Ok(())
+}
+
+pub fn config_set(key: String, value: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut config = config_manager.load_config()?;
+    config.set(&key, &value)?;
+    config_manager.save_config(&config)?;
+
+    record_change(&config_manager, &format!("alx: set {}", key))?;
+
+    println!("✓ Set {} = {}", key, config.get(&key)?);
+
+    // The usage hook lives in the generated file, and the exclusions decide
+    // what else does
+    if matches!(
+        key.as_str(),
+        "track_usage" | "sync_exclude_groups" | "sync_exclude_tags"
+    ) {
+        sync_after_change(&config_manager)?;
+        println!("  Restart your shell for this to take effect");
+    }
+
+    if key == "ignore_case" && config.settings.ignore_case {
+        let store = config_manager.load_aliases()?;
+        for (first, second) in store.case_conflicts() {
+            eprintln!(
+                "Warning: '{}' and '{}' now conflict; rename or remove one (alx lint lists them)",
+                first, second
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn config_unset(key: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut config = config_manager.load_config()?;
+    config.unset(&key)?;
+    config_manager.save_config(&config)?;
+
+    record_change(&config_manager, &format!("alx: unset {}", key))?;
+
+    println!("✓ Reset {} to its default", key);
+
+    if matches!(
+        key.as_str(),
+        "track_usage" | "sync_exclude_groups" | "sync_exclude_tags"
+    ) {
+        sync_after_change(&config_manager)?;
+        println!("  Restart your shell for this to take effect");
+    }
+
+    Ok(())
+}
+
+pub fn config_list() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let config = config_manager.load_config()?;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_BORDERS_ONLY);
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Setting", "Value"]);
+
+    for key in Config::keys() {
+        let value = config.get(key)?;
+        let value = if value.is_empty() {
+            "(not set)".to_string()
+        } else {
+            value
+        };
+        table.add_row(vec![Cell::new(key), Cell::new(value)]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
+pub fn config_doctor(fix: bool) -> Result<()> {
+    // Locate only; loading would fail on the very errors we want to report
+    let config_manager = ConfigManager::locate()?;
+    let config_file = config_manager.config_file();
+
+    if !config_file.exists() {
+        println!("No config file at {:?}; run 'alx init' first", config_file);
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(config_file)?;
+    let (issues, fixed) = doctor::check(&content);
+
+    if issues.is_empty() {
+        println!("✓ {:?} is valid", config_file);
+        return Ok(());
+    }
+
+    println!("Found {} problems in {:?}:", issues.len(), config_file);
+    for issue in &issues {
+        println!("  - {}", issue);
+    }
+
+    if !fix {
+        println!("\nRun 'alx config doctor --fix' to rewrite it with these problems corrected");
+        return Ok(());
+    }
+
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let broken = config_file.with_extension(format!("toml.{}.bak", stamp));
+    fs::copy(config_file, &broken)?;
+    config_manager.save_config(&fixed)?;
+
+    println!("\n✓ Rewrote {:?}", config_file);
+    println!("  The previous version was saved to {:?}", broken);
+
+    Ok(())
+}
+
+pub fn suggest(min_count: usize, limit: usize) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let settings = config_manager.load_config()?.settings;
+    let mut store = config_manager.load_aliases()?;
+    let shell_type = current_shell(&config_manager)?;
+    let handler = registry::handler(shell_type);
+
+    let Some((path, history)) = history::load(handler.as_ref()) else {
+        return Err(error::AlxError::ConfigError(format!(
+            "No shell history found at {:?}",
+            handler.history_file_path()?
+        )));
+    };
+    // Names that are free, valid without warnings and don't hide a program
+    let validation = settings.validation();
+    let available = |name: &str| {
+        !store.exists(name)
+            && AliasValidator::check_in_store(&AliasStore::new(), name, "true", validation)
+                .is_ok_and(|warnings| warnings.is_empty())
+            && shadow::shadowed(name, shell_type, &HashMap::new()).is_none()
+    };
+    let mut candidates = suggest::candidates(&history, &store, min_count, available);
+    candidates.truncate(limit);
+    if candidates.is_empty() {
+        println!(
+            "No commands typed {} or more times that an alias would shorten",
+            min_count
+        );
+        return Ok(());
+    }
+
+    println!("Going by {:?}:\n", path);
+    if !Term::stdout().is_term() {
+        let mut table = Table::new();
+        table.load_preset(UTF8_BORDERS_ONLY);
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec!["Name", "Command", "Uses", "Keystrokes saved"]);
+        for candidate in &candidates {
+            table.add_row(vec![
+                Cell::new(&candidate.name),
+                Cell::new(&candidate.command),
+                Cell::new(candidate.count),
+                Cell::new(candidate.saving()),
+            ]);
+        }
+        println!("{table}");
+        return Ok(());
+    }
+
+    // One key per suggestion
+    let term = Term::stdout();
+    let mut adopted = Vec::new();
+    for candidate in candidates {
+        println!(
+            "{} = {}  (typed {} times)",
+            style(&candidate.name).bold(),
+            candidate.command,
+            candidate.count
+        );
+        print!("  Add it? [y]es, [n]o, [q]uit ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let key = term.read_char()?;
+        println!("{}", key);
+        match key {
+            'y' | 'Y' => {
+                let mut alias = Alias::new(candidate.name, candidate.command);
+                if settings.infer_groups
+                    && let Some(group) = store.infer_group(&alias.command)
+                {
+                    alias = alias.with_group(group);
+                }
+                adopted.push(alias.name.clone());
+                store.add(alias)?;
+            }
+            'q' | 'Q' => break,
+            _ => {}
+        }
+    }
+
+    if adopted.is_empty() {
+        println!("No aliases added");
+        return Ok(());
+    }
+
+    save_store(&config_manager, &store)?;
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!("alx: add {} suggested aliases", adopted.len()),
+    )?;
+    let added: Vec<(&Alias, Option<&Alias>)> = adopted
+        .iter()
+        .filter_map(|name| store.get(name))
+        .map(|a| (a, None))
+        .collect();
+    run_alias_hooks(&config_manager, AliasEvent::Add, &added);
+
+    println!("✓ Added {} aliases: {}", adopted.len(), adopted.join(", "));
+
+    Ok(())
+}
+
+pub fn top(since: String, limit: usize) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+    let cutoff = chrono::Utc::now() - usage::parse_age(&since)?;
+
+    let stats = roll_up_usage(&config_manager)?;
+    if stats.aliases.is_empty() {
+        println!("No alias usage recorded yet");
+        if !config_manager.load_config()?.settings.track_usage {
+            println!("Enable tracking with: alx config set track_usage true");
+        }
+        return Ok(());
+    }
+    let usage = stats.summarize(cutoff);
+    let last_used = |u: &usage::Usage| {
+        u.last_used
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    };
+
+    let mut used = Vec::new();
+    let mut unused = Vec::new();
+    let mut never = Vec::new();
+    for alias in store.list() {
+        match usage.get(&alias.name) {
+            Some(u) if u.count > 0 => used.push((alias, u)),
+            Some(u) => unused.push((alias, u)),
+            None => never.push(alias.name.as_str()),
+        }
+    }
+    used.sort_by_key(|(_, u)| std::cmp::Reverse((u.count, u.last_used)));
+    unused.sort_by_key(|(_, u)| u.last_used);
+
+    let new_table = |header: Vec<&str>| {
+        let mut table = Table::new();
+        table.load_preset(UTF8_BORDERS_ONLY);
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(header);
+        table
+    };
+
+    if !used.is_empty() {
+        println!("Most used in the last {}:", since);
+        let mut table = new_table(vec!["Name", "Uses", "Last used"]);
+        for (alias, u) in used.iter().take(limit) {
+            table.add_row(vec![
+                Cell::new(&alias.name),
+                Cell::new(u.count),
+                Cell::new(last_used(u)),
+            ]);
+        }
+        println!("{table}");
+    }
+
+    if !unused.is_empty() {
+        println!("\nNot used in the last {}:", since);
+        let mut table = new_table(vec!["Name", "Last used"]);
+        for (alias, u) in unused.iter().take(limit) {
+            table.add_row(vec![Cell::new(&alias.name), Cell::new(last_used(u))]);
+        }
+        println!("{table}");
+    }
+
+    if !never.is_empty() {
+        println!("\nNever used since tracking started ({}):", never.len());
+        println!("  {}", never.join(", "));
+    }
+
+    Ok(())
+}
+
+pub fn scope_add(group: String, dir: String) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let dir = scope_dir(&dir)?;
+    let mut config = config_manager.load_config()?;
+
+    let dirs = config.scopes.entry(group.clone()).or_default();
+    if dirs.contains(&dir) {
+        println!("Group '{}' is already scoped to {}", group, dir);
+        return Ok(());
+    }
+    dirs.push(dir.clone());
+    config_manager.save_config(&config)?;
+
+    if config_manager
+        .load_aliases()?
+        .list_by_group(&group)
+        .is_empty()
+    {
+        eprintln!("Warning: group '{}' has no aliases yet", group);
+    }
+
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!("alx: scope group '{}' to {}", group, dir),
+    )?;
+
+    println!("✓ Group '{}' is now only active inside {}", group, dir);
+    println!("  Restart your shell for this to take effect");
+
+    Ok(())
+}
+
+pub fn scope_remove(group: String, dir: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut config = config_manager.load_config()?;
+
+    let dirs = config.scopes.get_mut(&group).ok_or_else(|| {
+        error::AlxError::ConfigError(format!("Group '{}' is not scoped to any directory", group))
+    })?;
+    match &dir {
+        Some(dir) => {
+            let dir = scope_dir(dir)?;
+            let before = dirs.len();
+            dirs.retain(|d| *d != dir);
+            if dirs.len() == before {
+                return Err(error::AlxError::ConfigError(format!(
+                    "Group '{}' is not scoped to {}",
+                    group, dir
+                )));
+            }
+        }
+        None => dirs.clear(),
+    }
+    if dirs.is_empty() {
+        config.scopes.remove(&group);
+    }
+    config_manager.save_config(&config)?;
+
+    sync_after_change(&config_manager)?;
+    record_change(&config_manager, &format!("alx: unscope group '{}'", group))?;
+
+    match config.scopes.get(&group) {
+        Some(dirs) => println!(
+            "✓ Group '{}' is now active inside {}",
+            group,
+            dirs.join(", ")
+        ),
+        None => println!("✓ Group '{}' is now active everywhere", group),
+    }
+
+    Ok(())
+}
+
+pub fn scope_list() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let config = config_manager.load_config()?;
+
+    if config.scopes.is_empty() {
+        println!("No scoped groups");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_BORDERS_ONLY);
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Group", "Directories"]);
+
+    for (group, dirs) in &config.scopes {
+        table.add_row(vec![Cell::new(group), Cell::new(dirs.join("\n"))]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
+// Keep `~` paths as given so the config works across machines; resolve
+// relative ones against the current directory
+fn scope_dir(dir: &str) -> Result<String> {
+    let dir = dir.trim_end_matches('/');
+    if dir.is_empty() || dir.starts_with('~') || dir.starts_with("$HOME") {
+        return Ok(if dir.is_empty() { "/" } else { dir }.to_string());
+    }
+
+    let path = std::env::current_dir()?.join(dir);
+    let path = fs::canonicalize(&path).unwrap_or(path);
+    Ok(path.display().to_string())
+}
+
+pub fn check_shadowing(all: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+    let store = store.resolve_links()?;
+    let shell_type = current_shell(&config_manager)?;
+    let handler = registry::handler(shell_type);
+
+    // Aliases defined in the shell's own files, where later ones win
+    let mut other_aliases = std::collections::HashMap::new();
+    if let Ok(config_path) = handler.config_file_path() {
+        let exclude = [current_shell_aliases_file(&config_manager)];
+        for file in sources::sourced_files(&config_path, 3, &exclude) {
+            for (name, command) in handler.parse_aliases_from_file(&file)? {
+                other_aliases.insert(name, (command, file.clone()));
+            }
+        }
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_BORDERS_ONLY);
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Alias", "Before", "Now"]);
+
+    let mut shadowing = 0;
+    for alias in store.list() {
+        let before = shadow::shadowed(&alias.name, shell_type, &other_aliases);
+        if before.is_some() {
+            shadowing += 1;
+        } else if !all {
+            continue;
+        }
+        let before = before.map_or_else(|| "-".to_string(), |b| b.to_string());
+        table.add_row(vec![
+            Cell::new(&alias.name),
+            Cell::new(before),
+            Cell::new(&alias.command),
+        ]);
+    }
+
+    if shadowing == 0 && !all {
+        println!(
+            "No aliases shadow an existing command in {}",
+            shell_type.as_str()
+        );
+        return Ok(());
+    }
+
+    println!("{table}");
+    println!(
+        "{} of {} aliases shadow an existing command in {}",
+        shadowing,
+        store.list().len(),
+        shell_type.as_str()
+    );
+
+    Ok(())
+}
+
+pub fn lint(fix: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut store = config_manager.load_aliases()?;
+    let shell_type = current_shell(&config_manager)?;
+
+    let mut issues = lint::lint(&store, shell_type, chrono::Utc::now());
+    if fix {
+        let fixed = lint::fix(&mut store, &issues);
+        if fixed > 0 {
+            save_store(&config_manager, &store)?;
+            sync_after_change(&config_manager)?;
+            record_change(&config_manager, "alx: fix lint issues")?;
+            println!("✓ Fixed {} issues", fixed);
+            issues = lint::lint(&store, shell_type, chrono::Utc::now());
+        }
+    }
+
+    if issues.is_empty() {
+        println!("✓ No issues found");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_BORDERS_ONLY);
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Severity", "Alias", "Check", "Message"]);
+
+    for issue in &issues {
+        let mut message = issue.message.clone();
+        if issue.fix.is_some() {
+            message.push_str(" (fixable)");
+        }
+        table.add_row(vec![
+            Cell::new(issue.severity.as_str()),
+            Cell::new(&issue.alias),
+            Cell::new(issue.check),
+            Cell::new(message),
+        ]);
+    }
+
+    println!("{table}");
+
+    let count = |severity| issues.iter().filter(|i| i.severity == severity).count();
+    println!(
+        "{} errors, {} warnings, {} notes",
+        count(Severity::Error),
+        count(Severity::Warning),
+        count(Severity::Note)
+    );
+    let fixable = issues.iter().filter(|i| i.fix.is_some()).count();
+    if fixable > 0 {
+        println!("Run 'alx lint --fix' to fix {} of them", fixable);
+    }
+
+    Ok(())
+}
+
+pub fn fsck(auto: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let aliases_file = config_manager.aliases_file();
+    let Some(content) = config_manager.read(aliases_file)? else {
+        println!("No aliases file at {:?}; nothing to check", aliases_file);
+        return Ok(());
+    };
+
+    let issues = fsck::check(&content, chrono::Utc::now());
+    if issues.is_empty() {
+        println!("✓ {:?} is intact", aliases_file);
+        return Ok(());
+    }
+
+    println!("Found {} problems in {:?}:", issues.len(), aliases_file);
+    for issue in &issues {
+        match &issue.fix {
+            Some(fix) => println!("  - {}: {} (fix: {})", issue.alias, issue.message, fix),
+            None => println!("  - {}: {}", issue.alias, issue.message),
+        }
+    }
+
+    let fixable: Vec<&fsck::Issue> = issues.iter().filter(|i| i.fix.is_some()).collect();
+    if fixable.is_empty() {
+        println!("\nNone of them can be fixed automatically; edit the file by hand");
+        return Ok(());
+    }
+
+    let chosen = if auto {
+        fixable
+    } else if !Term::stdout().is_term() {
+        println!("\nRun 'alx fsck --auto' to apply every fix");
+        return Ok(());
+    } else {
+        let mut chosen = Vec::new();
+        println!();
+        for issue in fixable {
+            let apply = Confirm::new()
+                .with_prompt(format!(
+                    "{}: {} - {}?",
+                    issue.alias,
+                    issue.message,
+                    issue
+                        .fix
+                        .as_ref()
+                        .map(|f| f.to_string())
+                        .unwrap_or_default()
+                ))
+                .default(true)
+                .interact()
+                .map_err(|e| error::AlxError::ConfigError(format!("Failed to confirm: {}", e)))?;
+            if apply {
+                chosen.push(issue);
+            }
+        }
+        chosen
+    };
+    if chosen.is_empty() {
+        println!("Nothing changed");
+        return Ok(());
+    }
+
+    let store = fsck::repair(&content, &chosen)?;
+
+    // Always keep the broken file, whether or not backups are enabled
+    let keep = config_manager
+        .load_config()
+        .map_or(Settings::default().backup_keep, |c| c.settings.backup_keep);
+    let backup = backup::create(aliases_file, &config_manager.backup_dir(), keep)?;
+    config_manager.save_aliases(&store)?;
+    sync_after_change(&config_manager)?;
+    record_change(&config_manager, "alx: repair aliases.toml")?;
+
+    println!("✓ Fixed {} problems", chosen.len());
+    if let Some(backup) = backup {
+        println!(
+            "  The previous version is backup '{}' (alx backup restore {})",
+            backup.id, backup.id
+        );
+    }
+
+    Ok(())
+}
+
+pub fn group_infer(apply: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let settings = config_manager.load_config()?.settings;
+    let mut store = config_manager.load_aliases()?;
+
+    let suggestions: Vec<(String, String)> = store
+        .list()
+        .iter()
+        .filter(|a| a.group.is_none() && !a.is_link())
+        .filter_map(|a| Some((a.name.clone(), store.infer_group(&a.command)?)))
+        .collect();
+    if suggestions.is_empty() {
+        println!("No groups to suggest for ungrouped aliases");
+        return Ok(());
+    }
+
+    for (name, group) in &suggestions {
+        println!("  {} -> {}", name, group);
+    }
+    if !apply {
+        println!("Run with --apply to assign these groups");
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now();
+    for (name, group) in &suggestions {
+        let group = group_name(&settings, &store, group)?;
+        if let Some(alias) = store.get_mut(name) {
+            alias.group = Some(group);
+            alias.updated_at = now;
+        }
+    }
+    save_store(&config_manager, &store)?;
+    sync_after_change(&config_manager)?;
+    record_change(&config_manager, "alx: infer groups")?;
+
+    println!("✓ Grouped {} aliases", suggestions.len());
+
+    Ok(())
+}
+
+pub fn group_create(group: String, description: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let settings = config_manager.load_config()?.settings;
+    let mut store = config_manager.load_aliases()?;
+
+    let group = group_name(&settings, &store, &group)?;
+    store.add_group(Group::new(group.clone(), description))?;
+    save_store(&config_manager, &store)?;
+    record_change(&config_manager, &format!("alx: create group '{}'", group))?;
+
+    println!("✓ Created group: {}", group);
+
+    Ok(())
+}
+
+pub fn group_color(group: String, color: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut config = config_manager.load_config()?;
+
+    match &color {
+        Some(color) if color != "none" && !GROUP_COLORS.contains(&color.as_str()) => {
+            return Err(error::AlxError::ConfigError(format!(
+                "Unknown color '{}' (expected none, {})",
+                color,
+                GROUP_COLORS.join(", ")
+            )));
+        }
+        Some(color) => {
+            config.group_colors.insert(group.clone(), color.clone());
+        }
+        None => {
+            config.group_colors.remove(&group);
+        }
+    }
+    config_manager.save_config(&config)?;
+    record_change(
+        &config_manager,
+        &format!("alx: set color of group '{}'", group),
+    )?;
+
+    match report::group_color(&group, &config.group_colors) {
+        "none" => println!("✓ Group '{}' is shown without color", group),
+        color => println!("✓ Group '{}' is shown in {}", group, color),
+    }
+
+    Ok(())
+}
+
+pub fn group_lock(group: String, lock: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut config = config_manager.load_config()?;
+
+    let changed = if lock {
+        config.locked_groups.insert(group.clone())
+    } else {
+        config.locked_groups.remove(&group)
+    };
+    let state = if lock { "locked" } else { "unlocked" };
+    if !changed {
+        println!("Group '{}' is already {}", group, state);
+        return Ok(());
+    }
+    if lock
+        && config_manager
+            .load_aliases()?
+            .list_by_group(&group)
+            .is_empty()
+    {
+        eprintln!("Warning: group '{}' has no aliases yet", group);
+    }
+
+    config_manager.save_config(&config)?;
+    record_change(
+        &config_manager,
+        &format!(
+            "alx: {} group '{}'",
+            if lock { "lock" } else { "unlock" },
+            group
+        ),
+    )?;
+
+    println!("✓ Group '{}' is now {}", group, state);
+
+    Ok(())
+}
+
+pub fn group_normalize(lowercase: bool, dry_run: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let settings = config_manager.load_config()?.settings;
+    let mut store = config_manager.load_aliases()?;
+
+    let lowercase = lowercase || settings.group_case.as_deref() == Some("lower");
+    let renames = store.normalize_groups(lowercase);
+    if renames.is_empty() {
+        println!("Group names are already consistent");
+        return Ok(());
+    }
+
+    for (from, to, count) in &renames {
+        println!("  {} -> {} ({} aliases)", from, to, count);
+    }
+    if dry_run {
+        println!("Dry run; nothing was changed");
+        return Ok(());
+    }
+
+    save_store(&config_manager, &store)?;
+    sync_after_change(&config_manager)?;
+    record_change(&config_manager, "alx: normalize group names")?;
+
+    println!("✓ Renamed {} groups", renames.len());
+
+    Ok(())
+}
+
+pub fn groups() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+    let config = config_manager.load_config()?;
+    let locked = config.locked_groups;
 
-    let alias = store
-        .get_mut(&name)
-        .ok_or_else(|| error::AlxError::AliasNotFound(name.clone()))?;
+    let groups_list = store.groups();
 
-    if let Some(cmd) = command {
-        AliasValidator::validate_command(&cmd)?;
-        alias.update_command(cmd);
+    if groups_list.is_empty() {
+        println!("No groups found");
+        return Ok(());
     }
 
-    if let Some(desc) = description {
-        alias.description = Some(desc);
-        alias.updated_at = chrono::Utc::now();
+    println!("Available groups:\n");
+    for group in groups_list {
+        let count = store.list_by_group(group).len();
+        let lock = if locked.contains(group) {
+            " [locked]"
+        } else {
+            ""
+        };
+        let description = store
+            .group(group)
+            .and_then(|g| g.description.as_ref())
+            .map(|d| format!(" - {}", d))
+            .unwrap_or_default();
+        println!(
+            "  {} ({} aliases){}{}",
+            paint_group(group, &config.group_colors),
+            count,
+            lock,
+            description
+        );
     }
 
-    if let Some(grp) = group {
-        alias.group = Some(grp);
-        alias.updated_at = chrono::Utc::now();
-    }
+    Ok(())
+}
+
+pub fn sync() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    sync_aliases(&config_manager)?;
+    SYNCED.store(true, Ordering::Relaxed);
 
-    store.save(config_manager.aliases_file())?;
+    println!(
+        "✓ Updated shell aliases at {}",
+        current_shell_aliases_file(&config_manager).display()
+    );
 
-    sync_aliases()?;
+    Ok(())
+}
 
-    println!("✓ Updated alias: {}", name);
+pub fn env(shell: Option<String>, group: Option<String>, tag: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let shell_type = match shell {
+        Some(name) => ShellDetector::parse_shell_name(&name)?,
+        None => current_shell(&config_manager)?,
+    };
+    let store = config_manager.load_aliases()?;
+
+    let filter = alias_filter(group.as_deref(), tag.as_deref(), false, &[])?;
+    let script = render_env(&store, shell_type, |alias| filter.matches(alias))?;
+    print!("{}", script);
 
     Ok(())
 }
 
-pub fn export(output: Option<String>, format: String) -> Result<()> {
+// A definition to eval in the current shell only: nothing is saved or synced.
+// Disabled aliases can be tried too.
+pub fn try_alias(name: String, command: Option<String>, shell: Option<String>) -> Result<()> {
     let config_manager = ConfigManager::new()?;
-    let store = AliasStore::load(config_manager.aliases_file())?;
+    let shell_type = match shell {
+        Some(name) => ShellDetector::parse_shell_name(&name)?,
+        None => current_shell(&config_manager)?,
+    };
+    let store = config_manager.load_aliases()?;
 
-    let content = match format.as_str() {
-        "json" => serde_json::to_string_pretty(&store)?,
-        "toml" => toml::to_string_pretty(&store)
-            .map_err(|e| error::AlxError::ConfigError(e.to_string()))?,
-        _ => {
-            return Err(error::AlxError::ConfigError(format!(
-                "Unsupported format: {}",
-                format
-            )));
+    let mut alias = match command {
+        Some(command) => {
+            let validation = config_manager.load_config()?.settings.validation();
+            for warning in AliasValidator::check_in_store(&store, &name, &command, validation)? {
+                eprintln!("Warning: {}", warning);
+            }
+            Alias::new(name.clone(), command)
+        }
+        None => {
+            let mut alias = store
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| not_found(&store, &name))?;
+            if alias.is_link() {
+                alias.command = store.resolve_link(&name)?.command.clone();
+            }
+            alias
         }
     };
+    alias.enabled = true;
+    let trial = AliasStore::from_aliases(vec![alias]);
+    let trial = secrets::resolve_store(&trial, Secret::lookup)?;
 
-    if let Some(output_path) = output {
-        fs::write(&output_path, content)?;
-        println!("✓ Exported aliases to: {}", output_path);
-    } else {
-        println!("{}", content);
+    let handler = registry::handler(shell_type);
+    println!("{}", handler.generate_alias_line(&trial.list()[0]));
+    if Term::stdout().is_term() {
+        let hint = match shell_type {
+            ShellType::Fish => format!("alx try {} | source", name),
+            _ => format!("eval \"$(alx try {})\"", name),
+        };
+        eprintln!(
+            "Nothing was defined; to try it in this shell, run: {}",
+            hint
+        );
     }
 
     Ok(())
 }
 
-pub fn import(file: String) -> Result<()> {
+// The prompt check compares fingerprints the shell can read on its own, so
+// it costs two file reads per prompt and never runs alx
+pub fn shell_init(shell: Option<String>) -> Result<()> {
     let config_manager = ConfigManager::new()?;
-    let content = fs::read_to_string(&file)?;
+    let shell_type = match shell {
+        Some(name) => ShellDetector::parse_shell_name(&name)?,
+        None => current_shell(&config_manager)?,
+    };
+    let handler = registry::handler(shell_type);
+    print!(
+        "{}",
+        handler.stale_check(
+            &config_manager.store_fingerprint_file(),
+            &config_manager.shell_aliases_file_for(shell_type),
+        )
+    );
+    Ok(())
+}
 
-    let imported_store: AliasStore = if file.ends_with(".json") {
-        serde_json::from_str(&content)?
-    } else if file.ends_with(".toml") {
-        toml::from_str(&content)?
-    } else {
-        // Try to detect format
-        serde_json::from_str(&content).or_else(|_| toml::from_str(&content))?
+pub fn completions(shell: Option<String>, install: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let shell_type = match shell {
+        Some(name) => ShellDetector::parse_shell_name(&name)?,
+        None => current_shell(&config_manager)?,
     };
+    let script = completions::render(shell_type);
+    if !install {
+        print!("{}", script);
+        return Ok(());
+    }
 
-    let mut store = AliasStore::load(config_manager.aliases_file())?;
-    let mut imported_count = 0;
-    let mut skipped_count = 0;
+    let home = dirs::home_dir()
+        .ok_or_else(|| error::AlxError::ConfigError("Could not find home directory".to_string()))?;
+    let xdg = |var: &str, default: &str| {
+        std::env::var_os(var)
+            .filter(|dir| !dir.is_empty())
+            .map_or_else(|| home.join(default), std::path::PathBuf::from)
+    };
+    // Where each shell picks up completions without further setup, except
+    // zsh, which needs the directory on its fpath
+    let path = match shell_type {
+        ShellType::Bash => {
+            xdg("XDG_DATA_HOME", ".local/share").join("bash-completion/completions/alx")
+        }
+        ShellType::Zsh => home.join(".zsh/completions/_alx"),
+        ShellType::Fish => xdg("XDG_CONFIG_HOME", ".config").join("fish/completions/alx.fish"),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, script)?;
+    println!(
+        "✓ Installed {} completions to {}",
+        shell_type.as_str(),
+        path.display()
+    );
 
-    for alias in imported_store.aliases {
-        if store.exists(&alias.name) {
-            skipped_count += 1;
-            eprintln!("  Skipped existing alias: {}", alias.name);
+    if shell_type == ShellType::Zsh {
+        let settings = config_manager.load_config()?.settings;
+        let handler = registry::handler(ShellType::Zsh);
+        let rc_path = if current_shell(&config_manager).ok() == Some(ShellType::Zsh) {
+            rc_file_path(&settings, handler.as_ref())?
         } else {
-            store.add(alias.clone())?;
-            imported_count += 1;
+            handler.config_file_path()?
+        };
+        let content = fs::read_to_string(&rc_path).unwrap_or_default();
+        // When compinit already runs, here or through oh-my-zsh, a second run
+        // would slow every startup, and the fpath line has to come before the
+        // first one, which only the user can place
+        let has_compinit = content.contains("compinit") || content.contains("oh-my-zsh.sh");
+        if !content.contains(".zsh/completions") {
+            if has_compinit {
+                println!(
+                    "Add this to {} before compinit runs (for oh-my-zsh, before it is sourced):",
+                    rc_path.display()
+                );
+                println!("     {}", ZSH_FPATH_LINE);
+            } else {
+                let mut file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&rc_path)?;
+                std::io::Write::write_all(&mut file, ZSH_COMPLETIONS_BLOCK.as_bytes())?;
+                println!(
+                    "✓ Added ~/.zsh/completions to fpath in {}",
+                    rc_path.display()
+                );
+            }
         }
     }
+    println!("Restart your shell to use them");
+
+    Ok(())
+}
 
-    store.save(config_manager.aliases_file())?;
+// Appended to .zshrc so compinit finds the installed completions
+const ZSH_COMPLETIONS_BLOCK: &str = "
+# alx completions
+fpath=(\"$HOME/.zsh/completions\" $fpath)
+autoload -Uz compinit && compinit
+";
 
-    sync_aliases()?;
+const ZSH_FPATH_LINE: &str = "fpath=(\"$HOME/.zsh/completions\" $fpath)";
 
-    println!("✓ Imported {} aliases", imported_count);
-    if skipped_count > 0 {
-        println!("  Skipped {} existing aliases", skipped_count);
-    }
+pub fn watch() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let files = vec![
+        config_manager.aliases_file().clone(),
+        config_manager.config_file().clone(),
+    ];
 
-    Ok(())
+    sync_aliases(&config_manager)?;
+    println!(
+        "Watching {} for changes (press Ctrl-C to stop)",
+        config_manager.aliases_file().display()
+    );
+
+    watch::watch(&files, |changed| {
+        let names: Vec<String> = changed.iter().map(|f| f.display().to_string()).collect();
+        let time = chrono::Local::now().format("%H:%M:%S");
+
+        // Reload so changed settings such as output paths apply too. A broken
+        // file is reported and left for the next save to fix.
+        match ConfigManager::new().and_then(|cm| sync_aliases(&cm)) {
+            Ok(()) => {
+                tracing::info!("watch: synced after change to {}", names.join(", "));
+                println!("[{}] ✓ Synced after change to {}", time, names.join(", "));
+            }
+            Err(e) => {
+                tracing::error!("watch: sync failed: {}", e);
+                eprintln!("[{}] Error: {}", time, e);
+            }
+        }
+        Ok(())
+    })
 }
 
-pub fn groups() -> Result<()> {
+pub fn shell_set(shell: String) -> Result<()> {
+    let shell_type = ShellDetector::parse_shell_name(&shell)?;
     let config_manager = ConfigManager::new()?;
-    let store = AliasStore::load(config_manager.aliases_file())?;
 
-    let groups_list = store.groups();
+    let mut config = config_manager.load_config()?;
+    config.settings.default_shell = Some(shell_type.as_str().to_string());
+    config_manager.save_config(&config)?;
 
-    if groups_list.is_empty() {
-        println!("No groups found");
-        return Ok(());
+    sync_aliases(&config_manager)?;
+    record_change(&config_manager, &format!("alx: set shell to {}", shell))?;
+
+    println!("✓ Shell set to {}", shell_type.as_str());
+    println!(
+        "  Aliases are generated at {}",
+        config_manager.shell_aliases_file_for(shell_type).display()
+    );
+
+    Ok(())
+}
+
+pub fn shell_show() -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+
+    match config_manager.load_config()?.settings.default_shell {
+        Some(shell) => println!("{} (set in config)", shell),
+        None => {
+            let shell_type = ShellDetector::detect()?;
+            println!(
+                "{} (detected; run 'alx shell set' to keep it)",
+                shell_type.as_str()
+            );
+        }
     }
 
-    println!("Available groups:\n");
-    for group in groups_list {
-        let count = store.list_by_group(&group).len();
-        println!("  {} ({} aliases)", group, count);
+    Ok(())
+}
+
+pub fn count(what: Option<String>, json: bool) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let store = config_manager.load_aliases()?;
+
+    let counts = [
+        ("aliases", store.list().len()),
+        ("groups", store.groups().len()),
+        (
+            "disabled",
+            store.list().iter().filter(|a| !a.enabled).count(),
+        ),
+    ];
+    if let Some(what) = what {
+        if let Some((_, n)) = counts.iter().find(|(key, _)| *key == what) {
+            println!("{}", n);
+        }
+    } else if json {
+        let object: serde_json::Map<String, serde_json::Value> = counts
+            .iter()
+            .map(|(key, n)| (key.to_string(), (*n).into()))
+            .collect();
+        println!("{}", serde_json::Value::Object(object));
+    } else {
+        let line: Vec<String> = counts
+            .iter()
+            .map(|(key, n)| format!("{}={}", key, n))
+            .collect();
+        println!("{}", line.join(" "));
     }
 
     Ok(())
 }
 
-pub fn info() -> Result<()> {
+pub fn info(json: bool) -> Result<()> {
     let config_manager = ConfigManager::new()?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&info_document(&config_manager))?
+        );
+        return Ok(());
+    }
 
     println!("alx - Modern Alias Manager");
     println!("Version: {}", env!("CARGO_PKG_VERSION"));
@@ -445,37 +3678,390 @@ pub fn info() -> Result<()> {
     println!("  Config directory: {:?}", config_manager.config_dir());
     println!("  Config file: {:?}", config_manager.config_file());
     println!("  Aliases file: {:?}", config_manager.aliases_file());
-    println!("  Shell aliases: {:?}", config_manager.shell_aliases_file());
+    println!(
+        "  Shell aliases: {:?}",
+        current_shell_aliases_file(&config_manager)
+    );
 
-    if let Ok(store) = AliasStore::load(config_manager.aliases_file()) {
+    if let Ok(store) = config_manager.load_aliases() {
         println!("\nStatistics:");
         println!("  Total aliases: {}", store.list().len());
         println!("  Groups: {}", store.groups().len());
     }
 
-    if let Ok(shell_type) = ShellDetector::detect() {
-        println!("\nDetected shell: {}", shell_type.as_str());
+    if let Ok(shell_type) = current_shell(&config_manager) {
+        println!("\nShell: {}", shell_type.as_str());
+    }
+
+    let plugins = plugin::list();
+    if !plugins.is_empty() {
+        println!("\nPlugins: {}", plugins.join(", "));
+    }
+
+    if let Ok(true) = shell_aliases_stale(&config_manager) {
+        println!("\nShell aliases are out of date; run 'alx sync' to update them");
+    }
+
+    Ok(())
+}
+
+// Everything `info` knows, for scripts and support requests. Parts that fail
+// to load are reported in place instead of failing the whole document.
+fn info_document(config_manager: &ConfigManager) -> serde_json::Value {
+    let path = |path: &std::path::Path| path.display().to_string();
+
+    let counts = match config_manager.load_aliases() {
+        Ok(store) => serde_json::json!({
+            "aliases": store.list().len(),
+            "groups": store.groups().len(),
+            "disabled": store.list().iter().filter(|a| !a.enabled).count(),
+            "links": store.list().iter().filter(|a| a.is_link()).count(),
+        }),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    };
+    let config = match config_manager.load_config() {
+        Ok(config) => Config::keys()
+            .map(|key| {
+                let value = config.get(key).unwrap_or_default();
+                let value = (!value.is_empty()).then_some(value);
+                (key.to_string(), serde_json::json!(value))
+            })
+            .collect(),
+        Err(e) => serde_json::json!({ "error": e.to_string() }),
+    };
+
+    serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "paths": {
+            "config_dir": path(config_manager.config_dir()),
+            "config_file": path(config_manager.config_file()),
+            "aliases_file": path(config_manager.aliases_file()),
+            "shell_aliases": path(&current_shell_aliases_file(config_manager)),
+        },
+        "counts": counts,
+        "shell": {
+            "current": current_shell(config_manager).ok().map(|s| s.as_str().to_string()),
+            "detected": ShellDetector::detect().ok().map(|s| s.as_str().to_string()),
+            "env": std::env::var("SHELL").ok(),
+        },
+        "shell_aliases_stale": shell_aliases_stale(config_manager).ok(),
+        "config": config,
+        "plugins": plugin::list(),
+    })
+}
+
+// Run `alx-<name>` with the rest of the arguments. The plugin gets the config
+// paths in ALX_* variables and the `info --json` document in ALX_CONTEXT, and
+// its exit status becomes ours.
+pub fn plugin(args: Vec<String>) -> Result<()> {
+    let Some((name, args)) = args.split_first() else {
+        return Err(error::AlxError::UnknownCommand(String::new()));
+    };
+    let path = plugin::find(name).ok_or_else(|| error::AlxError::UnknownCommand(name.clone()))?;
+    let config_manager = ConfigManager::new()?;
+    tracing::info!("Running plugin {}", path.display());
+
+    let mut child = Command::new(&path);
+    child
+        .args(args)
+        .env(
+            alx::config::manager::CONFIG_DIR_ENV,
+            config_manager.config_dir(),
+        )
+        .env("ALX_CONFIG_FILE", config_manager.config_file())
+        .env("ALX_ALIASES_FILE", config_manager.aliases_file())
+        .env("ALX_VERSION", env!("CARGO_PKG_VERSION"))
+        .env("ALX_CONTEXT", info_document(&config_manager).to_string());
+    if let Ok(shell_type) = current_shell(&config_manager) {
+        child.env("ALX_SHELL", shell_type.as_str());
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        child.env("ALX_BIN", exe);
+    }
+
+    let status = child.status().map_err(|e| {
+        error::AlxError::ConfigError(format!("Failed to run {}: {}", path.display(), e))
+    })?;
+    if !status.success() {
+        // Killed by a signal: exit the way a shell reports it
+        #[cfg(unix)]
+        if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+            std::process::exit(128 + signal);
+        }
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+pub fn self_update(check: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let release = update::latest(None)?;
+    let latest = release.version();
+    let _ = UpdateCheck {
+        checked_at: chrono::Utc::now(),
+        latest: Some(latest.to_string()),
+    }
+    .save();
+
+    if !update::is_newer(latest, current) {
+        println!("alx {} is the latest version", current);
+        return Ok(());
+    }
+    if check {
+        println!(
+            "alx {} is available (you have {}); run 'alx self-update' to install it",
+            latest, current
+        );
+        return Ok(());
+    }
+
+    let asset = release.asset().ok_or_else(|| {
+        error::AlxError::NetworkError(format!(
+            "Release {} has no {} build",
+            release.tag_name,
+            update::asset_name()
+        ))
+    })?;
+    println!("Downloading {}...", asset.name);
+    let path = update::install(asset)?;
+    println!(
+        "✓ Updated alx {} -> {} at {}",
+        current,
+        latest,
+        path.display()
+    );
+
+    Ok(())
+}
+
+fn roll_up_usage(config_manager: &ConfigManager) -> Result<usage::Stats> {
+    usage::roll_up(
+        &config_manager.usage_log(),
+        &config_manager.usage_stats(),
+        chrono::Utc::now(),
+    )
+}
+
+// Roll up the usage log once it grows past this, even if nothing reads it
+const USAGE_LOG_LIMIT: u64 = 64 * 1024;
+
+pub fn roll_up_large_usage_log() {
+    let Ok(config_manager) = ConfigManager::new() else {
+        return;
+    };
+    let large = fs::metadata(config_manager.usage_log()).is_ok_and(|m| m.len() > USAGE_LOG_LIMIT);
+    if large && let Err(e) = roll_up_usage(&config_manager) {
+        eprintln!("Warning: Failed to roll up the usage log: {}", e);
+    }
+}
+
+// Mention a newer release on stderr. Only runs in a terminal, asks GitHub at
+// most once a day, and never fails the command it follows.
+pub fn notify_update() {
+    if !Term::stderr().is_term() || std::env::var_os("CI").is_some() {
+        return;
+    }
+    let enabled = ConfigManager::new()
+        .and_then(|cm| cm.load_config())
+        .is_ok_and(|config| config.settings.update_check);
+    if !enabled {
+        return;
+    }
+
+    let now = chrono::Utc::now();
+    let check = match UpdateCheck::load() {
+        Some(check) if !check.is_due(now) => check,
+        _ => {
+            let timeout = std::time::Duration::from_secs(2);
+            let check = UpdateCheck {
+                checked_at: now,
+                latest: update::latest(Some(timeout))
+                    .ok()
+                    .map(|release| release.version().to_string()),
+            };
+            let _ = check.save();
+            check
+        }
+    };
+
+    let current = env!("CARGO_PKG_VERSION");
+    if let Some(latest) = check.latest.filter(|l| update::is_newer(l, current)) {
+        eprintln!(
+            "\nalx {} is available (you have {}); run 'alx self-update' to install it",
+            latest, current
+        );
+    }
+}
+
+pub fn open(target: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+
+    let path = match target.as_deref() {
+        None => config_manager.config_dir().clone(),
+        Some("aliases") => config_manager.aliases_file().clone(),
+        Some("config") => config_manager.config_file().clone(),
+        Some("shell") => current_shell_aliases_file(&config_manager),
+        Some(other) => {
+            return Err(error::AlxError::ConfigError(format!(
+                "Unknown open target: {}",
+                other
+            )));
+        }
+    };
+
+    if !path.exists() {
+        return Err(error::AlxError::ConfigError(format!(
+            "{:?} does not exist, run 'alx init' first",
+            path
+        )));
+    }
+
+    let settings = config_manager.load_config()?.settings;
+    let mut process = match editor::configured(&settings) {
+        Some(editor) if path.is_file() => editor::command(&editor)?,
+        _ => system_opener(),
+    };
+
+    let status = process
+        .arg(&path)
+        .status()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to open {:?}: {}", path, e)))?;
+
+    if !status.success() {
+        return Err(error::AlxError::ConfigError(format!(
+            "Opening {:?} exited with {}",
+            path, status
+        )));
     }
 
     Ok(())
 }
 
-pub fn migrate(from: Option<String>) -> Result<()> {
+fn system_opener() -> Command {
+    if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        Command::new("explorer")
+    } else {
+        Command::new("xdg-open")
+    }
+}
+
+// Add aliases that don't exist yet, validating each one and collecting the
+// outcome for the summary table
+fn add_new_aliases(
+    config_manager: &ConfigManager,
+    store: &mut AliasStore,
+    aliases: Vec<Alias>,
+    message: &str,
+) -> Result<ImportSummary> {
+    let settings = config_manager.load_config()?.settings;
+    let validation = settings.validation();
+    let mut summary = ImportSummary::new();
+    let progress = progress_bar(aliases.len(), message);
+
+    for mut alias in aliases {
+        progress.inc(1);
+        alias.group = alias.group.map(|g| settings.group_name(&g));
+
+        if let Some(existing) = store.get(&alias.name) {
+            if existing.command == alias.command {
+                summary.skipped.push(alias.name);
+            } else {
+                progress.suspend(|| eprintln!("  Kept existing alias: {}", alias.name));
+                summary.conflicts.push(ImportConflict {
+                    name: alias.name,
+                    existing: existing.command.clone(),
+                    incoming: alias.command,
+                });
+            }
+            continue;
+        }
+
+        let checked =
+            AliasValidator::check_in_store(store, &alias.name, &alias.command, validation)
+                .and_then(|warnings| {
+                    alias
+                        .group
+                        .as_deref()
+                        .map_or(Ok(()), AliasValidator::validate_group)?;
+                    Ok(warnings)
+                });
+        match checked {
+            Ok(warnings) => {
+                for warning in warnings {
+                    progress.suspend(|| eprintln!("  Warning: {}", warning));
+                }
+            }
+            Err(e) => {
+                summary.failed.push(ImportFailure {
+                    name: alias.name,
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        }
+
+        summary.added.push(alias.name.clone());
+        store.add(alias)?;
+    }
+
+    progress.finish_and_clear();
+
+    Ok(summary)
+}
+
+// Name a group after the file an alias came from, e.g. `.bash_aliases` ->
+// `bash_aliases`, `aliases.zsh` -> `aliases`
+fn source_group_name(path: &std::path::Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?.trim_start_matches('.');
+    if stem.is_empty() {
+        None
+    } else {
+        Some(stem.to_string())
+    }
+}
+
+pub fn migrate(
+    from: Option<String>,
+    omz: Option<Option<String>>,
+    shell: Option<String>,
+    depth: usize,
+    group: Option<String>,
+    group_by_source: bool,
+) -> Result<()> {
+    if let Some(plugin) = omz {
+        return migrate_omz(plugin, group);
+    }
+
     let config_manager = ConfigManager::new()?;
-    let mut store = AliasStore::load(config_manager.aliases_file())?;
+    let mut store = config_manager.load_aliases()?;
+    let shell = shell
+        .as_deref()
+        .map(ShellDetector::parse_shell_name)
+        .transpose()?;
 
     // Determine the config file path and shell type
     let (config_path, shell_type) = if let Some(path) = from {
         let path_buf = std::path::PathBuf::from(path);
-        let shell_type = ShellDetector::detect_from_path(&path_buf)?;
+        let shell_type = match shell {
+            Some(shell_type) => shell_type,
+            None => ShellDetector::detect_from_path(&path_buf).map_err(|_| {
+                error::AlxError::ConfigError(format!(
+                    "Could not tell which shell {:?} is for; pass --shell bash, zsh or fish",
+                    path_buf
+                ))
+            })?,
+        };
         (path_buf, shell_type)
     } else {
-        let shell_type = ShellDetector::detect()?;
-        let handler: Box<dyn ShellHandler> = match shell_type {
-            ShellType::Bash => Box::new(BashHandler::new()),
-            ShellType::Zsh => Box::new(ZshHandler::new()),
-            ShellType::Fish => Box::new(FishHandler::new()),
+        let shell_type = match shell {
+            Some(shell_type) => shell_type,
+            None => current_shell(&config_manager)?,
         };
+        let handler = registry::handler(shell_type);
         (handler.config_file_path()?, shell_type)
     };
 
@@ -486,44 +4072,179 @@ pub fn migrate(from: Option<String>) -> Result<()> {
         )));
     }
 
-    let handler: Box<dyn ShellHandler> = match shell_type {
-        ShellType::Bash => Box::new(BashHandler::new()),
-        ShellType::Zsh => Box::new(ZshHandler::new()),
-        ShellType::Fish => Box::new(FishHandler::new()),
-    };
+    let handler = registry::handler(shell_type);
 
     println!("Migrating aliases from: {:?}", config_path);
 
-    // Parse aliases from the config file
-    let parsed_aliases = handler.parse_aliases_from_file(&config_path)?;
+    // Parse aliases from the config file and the files it sources, leaving
+    // out our own generated file
+    let files = sources::sourced_files(
+        &config_path,
+        depth,
+        &[current_shell_aliases_file(&config_manager)],
+    );
+    let mut parsed_aliases = Vec::new();
+    for file in &files {
+        if file != &config_path {
+            println!("  including: {:?}", file);
+        }
+
+        let file_group = if group_by_source {
+            source_group_name(file)
+        } else {
+            group.clone()
+        };
+        for (name, command) in handler.parse_aliases_from_file(file)? {
+            let alias = Alias::new(name, command);
+            parsed_aliases.push(match &file_group {
+                Some(grp) => alias.with_group(grp.clone()),
+                None => alias,
+            });
+        }
+    }
 
     if parsed_aliases.is_empty() {
         println!("No aliases found in the configuration file");
         return Ok(());
     }
 
-    let mut imported_count = 0;
-    let mut skipped_count = 0;
+    let selected = select_to_migrate(parsed_aliases, handler.as_ref())?;
+    let summary = add_new_aliases(&config_manager, &mut store, selected, "Migrating")?;
 
-    for (name, command) in parsed_aliases {
-        if store.exists(&name) {
-            skipped_count += 1;
-            eprintln!("  Skipped existing alias: {}", name);
-        } else {
-            let alias = Alias::new(name.clone(), command);
-            store.add(alias)?;
-            imported_count += 1;
-        }
+    save_store(&config_manager, &store)?;
+
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!(
+            "alx: migrate {} aliases from {}",
+            summary.added.len(),
+            config_path.display()
+        ),
+    )?;
+
+    println!("✓ Migrated {} aliases", summary.added.len());
+    summary.print_table();
+
+    Ok(())
+}
+
+// Let the user pick which aliases to migrate when running interactively.
+// The most used come first, counted in the shell's history.
+fn select_to_migrate(mut candidates: Vec<Alias>, handler: &dyn ShellHandler) -> Result<Vec<Alias>> {
+    if !Term::stdout().is_term() {
+        return Ok(candidates);
+    }
+
+    let mut uses = None;
+    if let Some((path, history)) = history::load(handler) {
+        let aliases: Vec<(&str, &str)> = candidates
+            .iter()
+            .map(|a| (a.name.as_str(), a.command.as_str()))
+            .collect();
+        let counts = history::count_uses(&history, &aliases);
+        let mut ranked: Vec<(Alias, usize)> = candidates.into_iter().zip(counts).collect();
+        ranked.sort_by_key(|(_, count)| Reverse(*count));
+        let (sorted, counts): (Vec<Alias>, Vec<usize>) = ranked.into_iter().unzip();
+        candidates = sorted;
+        uses = Some(counts);
+        println!("Most used first, going by {:?}", path);
     }
 
-    store.save(config_manager.aliases_file())?;
+    let labels: Vec<String> = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            let mut label = format!("{} = {}", a.name, a.command);
+            if let Some(group) = &a.group {
+                label.push_str(&format!(" ({})", group));
+            }
+            if let Some(uses) = &uses {
+                label.push_str(&format!(" - used {} times", uses[i]));
+            }
+            label
+        })
+        .collect();
+
+    let chosen = MultiSelect::new()
+        .with_prompt("Select aliases to migrate (space to toggle, enter to confirm)")
+        .items(&labels)
+        .defaults(&vec![true; labels.len()])
+        .interact()
+        .map_err(|e| error::AlxError::ConfigError(format!("Failed to select aliases: {}", e)))?;
+
+    Ok(candidates
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| chosen.contains(i))
+        .map(|(_, a)| a)
+        .collect())
+}
+
+fn migrate_omz(plugin: Option<String>, group: Option<String>) -> Result<()> {
+    let config_manager = ConfigManager::new()?;
+    let mut store = config_manager.load_aliases()?;
+    let handler = registry::handler(ShellType::Zsh);
+
+    // Without a plugin name, take every plugin enabled in .zshrc
+    let explicit = plugin.is_some();
+    let plugins = match plugin {
+        Some(plugin) => vec![plugin],
+        None => {
+            let zshrc = handler.config_file_path()?;
+            let plugins = omz::enabled_plugins(&fs::read_to_string(&zshrc)?);
+            if plugins.is_empty() {
+                return Err(error::AlxError::ConfigError(format!(
+                    "No oh-my-zsh plugins enabled in {:?}",
+                    zshrc
+                )));
+            }
+            plugins
+        }
+    };
+
+    let mut candidates = Vec::new();
+    for plugin in &plugins {
+        let file = match omz::plugin_file(plugin) {
+            Ok(file) => file,
+            Err(e) if explicit => return Err(e),
+            Err(e) => {
+                eprintln!("  Skipped plugin: {}", e);
+                continue;
+            }
+        };
 
-    sync_aliases()?;
+        for (name, command) in handler.parse_aliases_from_file(&file)? {
+            let grp = group.clone().unwrap_or_else(|| plugin.clone());
+            candidates.push(Alias::new(name, command).with_group(grp));
+        }
+    }
 
-    println!("✓ Migrated {} aliases", imported_count);
-    if skipped_count > 0 {
-        println!("  Skipped {} existing aliases", skipped_count);
+    if candidates.is_empty() {
+        println!(
+            "No aliases found in oh-my-zsh plugins: {}",
+            plugins.join(", ")
+        );
+        return Ok(());
     }
 
+    let selected = select_to_migrate(candidates, handler.as_ref())?;
+    let summary = add_new_aliases(&config_manager, &mut store, selected, "Migrating")?;
+
+    save_store(&config_manager, &store)?;
+
+    sync_after_change(&config_manager)?;
+    record_change(
+        &config_manager,
+        &format!(
+            "alx: migrate {} aliases from oh-my-zsh plugins {}",
+            summary.added.len(),
+            plugins.join(", ")
+        ),
+    )?;
+
+    println!("✓ Migrated {} aliases", summary.added.len());
+    summary.print_table();
+
     Ok(())
 }
@@ -0,0 +1,207 @@
+use crate::cli::Cli;
+use alx::shell::{self, ShellType};
+use clap::CommandFactory;
+use std::fmt::Write;
+
+// A word that can follow a command path, with its help text
+struct Entry {
+    word: String,
+    help: String,
+}
+
+// A command path such as "alx" or "alx group", with the subcommands, option
+// names and argument values that can follow it
+struct Node {
+    path: String,
+    has_subcommands: bool,
+    entries: Vec<Entry>,
+}
+
+// The completion script for `shell_type`, generated from the CLI definition
+pub fn render(shell_type: ShellType) -> String {
+    let mut cli = Cli::command();
+    cli.build();
+    let mut nodes = Vec::new();
+    collect(&cli, "alx".to_string(), &mut nodes);
+
+    match shell_type {
+        ShellType::Bash => render_bash(&nodes),
+        ShellType::Zsh => render_zsh(&nodes),
+        ShellType::Fish => render_fish(&nodes),
+    }
+}
+
+fn collect(command: &clap::Command, path: String, nodes: &mut Vec<Node>) {
+    let first_line = |text: Option<String>| {
+        text.unwrap_or_default()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let mut entries = Vec::new();
+    let mut subcommands: Vec<&clap::Command> = command
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .collect();
+    for sub in &subcommands {
+        entries.push(Entry {
+            word: sub.get_name().to_string(),
+            help: first_line(sub.get_about().map(|a| a.to_string())),
+        });
+    }
+    for arg in command.get_arguments().filter(|a| !a.is_hide_set()) {
+        let help = first_line(arg.get_help().map(|h| h.to_string()));
+        if let Some(long) = arg.get_long() {
+            entries.push(Entry {
+                word: format!("--{}", long),
+                help,
+            });
+        } else if arg.is_positional() {
+            for value in arg.get_possible_values() {
+                entries.push(Entry {
+                    word: value.get_name().to_string(),
+                    help: help.clone(),
+                });
+            }
+        }
+    }
+
+    // The generated `help` subcommands only repeat the tree, so they get
+    // offered but not descended into
+    subcommands.retain(|c| c.get_name() != "help");
+    nodes.push(Node {
+        path: path.clone(),
+        has_subcommands: !subcommands.is_empty(),
+        entries,
+    });
+    for sub in subcommands {
+        collect(sub, format!("{} {}", path, sub.get_name()), nodes);
+    }
+}
+
+// Paths that lead to more subcommands, as case patterns
+fn parent_patterns(nodes: &[Node], sep: &str) -> String {
+    nodes
+        .iter()
+        .filter(|n| n.has_subcommands && n.path != "alx")
+        .map(|n| shell::quote(&n.path))
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+fn render_bash(nodes: &[Node]) -> String {
+    let mut script =
+        String::from("# bash completion for alx, generated by `alx completions bash`\n");
+    script.push_str("_alx() {\n");
+    script.push_str("    local cur=\"${COMP_WORDS[COMP_CWORD]}\" cmdpath=\"alx\" word i words\n");
+    script.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+    script.push_str("        word=\"${COMP_WORDS[i]}\"\n");
+    let _ = writeln!(
+        script,
+        "        case \"$cmdpath $word\" in\n            {}) cmdpath=\"$cmdpath $word\" ;;\n        esac",
+        parent_patterns(nodes, "|")
+    );
+    script.push_str("    done\n    case \"$cmdpath\" in\n");
+    for node in nodes {
+        let words: Vec<&str> = node.entries.iter().map(|e| e.word.as_str()).collect();
+        let _ = writeln!(
+            script,
+            "        {}) words={} ;;",
+            shell::quote(&node.path),
+            shell::quote(&words.join(" "))
+        );
+    }
+    script.push_str("    esac\n");
+    script.push_str("    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))\n}\n");
+    script.push_str("complete -F _alx alx\n");
+    script
+}
+
+fn render_zsh(nodes: &[Node]) -> String {
+    let mut script = String::from(
+        "#compdef alx\n# zsh completion for alx, generated by `alx completions zsh`\n",
+    );
+    // `path` is tied to $PATH in zsh, hence cmdpath
+    script.push_str("_alx() {\n    local cmdpath=\"alx\" word\n    local -a entries\n");
+    script.push_str("    for word in \"${(@)words[2,CURRENT-1]}\"; do\n");
+    let _ = writeln!(
+        script,
+        "        case \"$cmdpath $word\" in\n            {}) cmdpath=\"$cmdpath $word\" ;;\n        esac",
+        parent_patterns(nodes, "|")
+    );
+    script.push_str("    done\n    case \"$cmdpath\" in\n");
+    for node in nodes {
+        let entries: Vec<String> = node
+            .entries
+            .iter()
+            .map(|e| shell::quote(&format!("{}:{}", e.word.replace(':', "\\:"), e.help)))
+            .collect();
+        let _ = writeln!(
+            script,
+            "        {}) entries=({}) ;;",
+            shell::quote(&node.path),
+            entries.join(" ")
+        );
+    }
+    script.push_str("    esac\n    _describe -t commands alx entries\n}\n\n_alx \"$@\"\n");
+    script
+}
+
+fn render_fish(nodes: &[Node]) -> String {
+    let fish_quote = |text: &str| format!("'{}'", text.replace('\\', r"\\").replace('\'', r"\'"));
+
+    let mut script =
+        String::from("# fish completion for alx, generated by `alx completions fish`\n");
+    script.push_str("function __alx_path\n    set -l cmdpath alx\n");
+    script.push_str("    for word in (commandline -opc)[2..-1]\n");
+    let parents: Vec<String> = nodes
+        .iter()
+        .filter(|n| n.has_subcommands && n.path != "alx")
+        .map(|n| fish_quote(&n.path))
+        .collect();
+    let _ = writeln!(
+        script,
+        "        switch \"$cmdpath $word\"\n            case {}\n                set cmdpath \"$cmdpath $word\"\n        end",
+        parents.join(" ")
+    );
+    script.push_str("    end\n    echo $cmdpath\nend\n\n");
+
+    for node in nodes {
+        let condition = fish_quote(&format!("test (__alx_path) = {}", fish_quote(&node.path)));
+        for entry in &node.entries {
+            let word = match entry.word.strip_prefix("--") {
+                Some(long) => format!("-l {}", long),
+                None => format!("-a {}", fish_quote(&entry.word)),
+            };
+            let _ = write!(script, "complete -c alx -n {} {}", condition, word);
+            if !entry.help.is_empty() {
+                let _ = write!(script, " -d {}", fish_quote(&entry.help));
+            }
+            script.push('\n');
+        }
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let bash = render(ShellType::Bash);
+        assert!(bash.contains("'alx group') words='create lock unlock"));
+        assert!(bash.contains("complete -F _alx alx"));
+
+        let zsh = render(ShellType::Zsh);
+        assert!(zsh.starts_with("#compdef alx\n"));
+        assert!(zsh.contains("'--group:Filter by group'"));
+
+        let fish = render(ShellType::Fish);
+        assert!(fish.contains(
+            "complete -c alx -n 'test (__alx_path) = \\'alx shell set\\'' -a 'zsh' -d 'Shell name'"
+        ));
+    }
+}
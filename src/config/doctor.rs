@@ -0,0 +1,236 @@
+use crate::config::{Config, Hooks, Settings};
+use crate::error::AlxError;
+use toml::Value;
+
+// Settings that must be present; the rest have defaults
+const REQUIRED: &[&str] = &["auto_sync", "backup_enabled"];
+const BOOL_KEYS: &[&str] = &[
+    "auto_sync",
+    "backup_enabled",
+    "log_enabled",
+    "track_usage",
+    "validate_syntax",
+    "unicode_names",
+    "emit_cleanup",
+    "infer_groups",
+    "ignore_case",
+    "update_check",
+    "auto_commit",
+    "source_in_login_shells",
+];
+const NUMBER_KEYS: &[&str] = &["backup_keep", "table_max_width"];
+
+#[derive(Debug, PartialEq)]
+pub enum Issue {
+    Syntax(String),
+    UnknownKey(String),
+    Missing(String),
+    InvalidValue { key: String, message: String },
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::Syntax(message) => write!(f, "not valid TOML: {}", message.trim()),
+            Issue::UnknownKey(key) => write!(f, "unknown key: {}", key),
+            Issue::Missing(key) => write!(f, "missing key: {} (the default will be used)", key),
+            Issue::InvalidValue { key, message } => write!(f, "{}: {}", key, message),
+        }
+    }
+}
+
+// Check config.toml and build the closest valid config: unknown keys are
+// dropped and invalid values fall back to their defaults
+pub fn check(content: &str) -> (Vec<Issue>, Config) {
+    let mut issues = Vec::new();
+    let mut config = Config::default();
+
+    let table: toml::Table = match toml::from_str(content) {
+        Ok(table) => table,
+        Err(e) => {
+            issues.push(Issue::Syntax(e.to_string()));
+            return (issues, config);
+        }
+    };
+
+    for (section, value) in &table {
+        if section == "scopes" {
+            check_scopes(&mut config, value, &mut issues);
+            continue;
+        }
+        let keys: &[&str] = match section.as_str() {
+            "settings" => Settings::KEYS,
+            "hooks" => Hooks::KEYS,
+            _ => {
+                issues.push(Issue::UnknownKey(section.clone()));
+                continue;
+            }
+        };
+        let Some(entries) = value.as_table() else {
+            issues.push(Issue::InvalidValue {
+                key: section.clone(),
+                message: "expected a table".to_string(),
+            });
+            continue;
+        };
+
+        for (name, value) in entries {
+            // Hooks are addressed as hooks.<name>, settings by their bare name
+            let key = match section.as_str() {
+                "hooks" => format!("hooks.{}", name),
+                _ => name.clone(),
+            };
+            if !keys.contains(&key.as_str()) {
+                issues.push(Issue::UnknownKey(format!("{}.{}", section, name)));
+                continue;
+            }
+
+            if let Err(message) = apply(&mut config, &key, value) {
+                issues.push(Issue::InvalidValue {
+                    key: format!("{}.{}", section, name),
+                    message,
+                });
+            }
+        }
+    }
+
+    let settings = table.get("settings").and_then(Value::as_table);
+    for key in REQUIRED {
+        if !settings.is_some_and(|s| s.contains_key(*key)) {
+            issues.push(Issue::Missing(format!("settings.{}", key)));
+        }
+    }
+
+    (issues, config)
+}
+
+// Each scope is a group name mapped to a list of directories
+fn check_scopes(config: &mut Config, value: &Value, issues: &mut Vec<Issue>) {
+    let Some(entries) = value.as_table() else {
+        issues.push(Issue::InvalidValue {
+            key: "scopes".to_string(),
+            message: "expected a table".to_string(),
+        });
+        return;
+    };
+
+    for (group, dirs) in entries {
+        let dirs: Option<Vec<String>> = dirs.as_array().and_then(|dirs| {
+            dirs.iter()
+                .map(|d| d.as_str().map(str::to_string))
+                .collect()
+        });
+        match dirs {
+            Some(dirs) => {
+                config.scopes.insert(group.clone(), dirs);
+            }
+            None => issues.push(Issue::InvalidValue {
+                key: format!("scopes.{}", group),
+                message: "expected a list of directories".to_string(),
+            }),
+        }
+    }
+}
+
+fn apply(config: &mut Config, key: &str, value: &Value) -> Result<(), String> {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Integer(n) => n.to_string(),
+        other => return Err(format!("unsupported value type: {}", other.type_str())),
+    };
+
+    config.set(key, &text).map_err(|e| match e {
+        AlxError::ConfigError(message) => message,
+        other => other.to_string(),
+    })?;
+
+    // Accepted, but stored with a type serde would reject
+    let expected = if BOOL_KEYS.contains(&key) {
+        "a boolean"
+    } else if NUMBER_KEYS.contains(&key) {
+        "a number"
+    } else {
+        "a string"
+    };
+    let type_ok = match value {
+        Value::Boolean(_) => BOOL_KEYS.contains(&key),
+        Value::Integer(_) => NUMBER_KEYS.contains(&key),
+        _ => !BOOL_KEYS.contains(&key) && !NUMBER_KEYS.contains(&key),
+    };
+    if !type_ok {
+        return Err(format!("expected {}, found {}", expected, value.type_str()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_config() {
+        let content = toml::to_string_pretty(&Config::default()).unwrap();
+        let (issues, _) = check(&content);
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_scopes() {
+        let content = "[settings]\nauto_sync = true\nbackup_enabled = true\n\n[scopes]\nk8s = [\"~/work/infra\"]\ngit = \"~/src\"\n";
+        let (issues, config) = check(content);
+        assert_eq!(
+            issues,
+            vec![Issue::InvalidValue {
+                key: "scopes.git".to_string(),
+                message: "expected a list of directories".to_string(),
+            }]
+        );
+        assert_eq!(config.scopes["k8s"], vec!["~/work/infra"]);
+    }
+
+    #[test]
+    fn test_reports_and_fixes() {
+        let content = r#"
+[settings]
+auto_sync = "false"
+backup_keep = 0
+colour = "red"
+table_style = "none"
+
+[hooks]
+post_sync = "true"
+
+[extra]
+a = 1
+"#;
+        let (issues, config) = check(content);
+
+        assert!(issues.contains(&Issue::UnknownKey("extra".to_string())));
+        assert!(issues.contains(&Issue::UnknownKey("settings.colour".to_string())));
+        assert!(issues.contains(&Issue::Missing("settings.backup_enabled".to_string())));
+        assert!(
+            issues.iter().any(
+                |i| matches!(i, Issue::InvalidValue { key, .. } if key == "settings.auto_sync")
+            )
+        );
+        assert!(issues.iter().any(
+            |i| matches!(i, Issue::InvalidValue { key, .. } if key == "settings.backup_keep")
+        ));
+        assert_eq!(issues.len(), 5);
+
+        // Wrongly typed but readable values are kept, invalid ones reset
+        assert!(!config.settings.auto_sync);
+        assert_eq!(config.settings.backup_keep, 10);
+        assert_eq!(config.settings.table_style.as_deref(), Some("none"));
+        assert_eq!(config.hooks.post_sync.as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let (issues, config) = check("[settings\nauto_sync = true");
+        assert!(matches!(issues[0], Issue::Syntax(_)));
+        assert!(config.settings.auto_sync);
+    }
+}
@@ -1,29 +1,118 @@
-use crate::config::Config;
+use crate::alias::store::AliasStore;
+use crate::config::{Config, paths};
 use crate::error::{AlxError, Result};
+use crate::shell::ShellType;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+pub const CONFIG_DIR_ENV: &str = "ALX_CONFIG_DIR";
+
+// Set once from the global --config-dir flag
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn set_config_dir(dir: PathBuf) {
+    let _ = CONFIG_DIR_OVERRIDE.set(dir);
+}
 
 pub struct ConfigManager {
     config_dir: PathBuf,
     config_file: PathBuf,
     aliases_file: PathBuf,
+    shell_outputs: Vec<(ShellType, PathBuf)>,
+    storage: Storage,
+}
+
+// Where the files managed through `read` and `write` live
+enum Storage {
+    Disk,
+    // File contents by path, for tests that shouldn't touch the real disk
+    Memory(Mutex<HashMap<PathBuf, String>>),
 }
 
 impl ConfigManager {
     pub fn new() -> Result<Self> {
-        let config_dir = dirs::home_dir()
-            .ok_or_else(|| AlxError::ConfigError("Could not find home directory".to_string()))?
-            .join(".config")
-            .join("alx");
+        Self::with_config_dir(Self::locate()?.config_dir)
+    }
+
+    // Find the config directory without reading config.toml, for repairing
+    // a config that can't be loaded
+    pub fn locate() -> Result<Self> {
+        let env_dir = std::env::var(CONFIG_DIR_ENV).ok();
+        let config_dir = resolve_config_dir(CONFIG_DIR_OVERRIDE.get(), env_dir.as_deref())?;
+        Ok(Self::unconfigured(config_dir))
+    }
 
+    fn unconfigured(config_dir: PathBuf) -> Self {
         let config_file = config_dir.join("config.toml");
         let aliases_file = config_dir.join("aliases.toml");
 
-        Ok(Self {
+        Self {
             config_dir,
             config_file,
             aliases_file,
-        })
+            shell_outputs: Vec::new(),
+            storage: Storage::Disk,
+        }
+    }
+
+    /// Use `config_dir` instead of the discovered directory.
+    ///
+    /// ```
+    /// use alx::config::manager::ConfigManager;
+    ///
+    /// let dir = std::env::temp_dir().join("alx-doctest");
+    /// let config_manager = ConfigManager::with_config_dir(dir.clone())?;
+    /// assert_eq!(config_manager.aliases_file(), &dir.join("aliases.toml"));
+    /// # Ok::<(), alx::error::AlxError>(())
+    /// ```
+    pub fn with_config_dir(config_dir: PathBuf) -> Result<Self> {
+        Self::unconfigured(config_dir).configured()
+    }
+
+    /// Keep the config, the aliases and the generated shell files in memory,
+    /// starting from `config` and an empty store.
+    ///
+    /// ```
+    /// use alx::config::Config;
+    /// use alx::config::manager::ConfigManager;
+    ///
+    /// let config_manager = ConfigManager::in_memory(Config::default())?;
+    /// assert!(config_manager.is_initialized());
+    /// assert!(config_manager.load_aliases()?.list().is_empty());
+    /// assert!(!config_manager.config_dir().exists());
+    /// # Ok::<(), alx::error::AlxError>(())
+    /// ```
+    pub fn in_memory(config: Config) -> Result<Self> {
+        let mut manager = Self::unconfigured(PathBuf::from("/alx-in-memory"));
+        manager.storage = Storage::Memory(Mutex::new(HashMap::new()));
+        manager.init()?;
+        manager.save_config(&config)?;
+        manager.configured()
+    }
+
+    fn configured(mut self) -> Result<Self> {
+        let settings = self.load_config()?.settings;
+
+        // The store may live elsewhere, e.g. inside a dotfiles repository
+        if let Some(path) = settings.aliases_path {
+            self.aliases_file = paths::expand_path(&path, &self.config_dir);
+        }
+
+        // So may the generated file for each shell
+        for (shell, path) in [
+            (ShellType::Bash, settings.bash_output_path),
+            (ShellType::Zsh, settings.zsh_output_path),
+            (ShellType::Fish, settings.fish_output_path),
+        ] {
+            if let Some(path) = path {
+                let path = paths::expand_path(&path, &self.config_dir);
+                self.shell_outputs.push((shell, path));
+            }
+        }
+
+        Ok(self)
     }
 
     pub fn config_dir(&self) -> &PathBuf {
@@ -46,45 +135,186 @@ impl ConfigManager {
         self.shell_dir().join("aliases.sh")
     }
 
+    // Where aliases for `shell` are generated, honoring the *_output_path settings
+    pub fn shell_aliases_file_for(&self, shell: ShellType) -> PathBuf {
+        self.shell_outputs
+            .iter()
+            .find(|(s, _)| *s == shell)
+            .map(|(_, path)| path.clone())
+            .unwrap_or_else(|| self.shell_aliases_file())
+    }
+
+    // Shells with an output path of their own
+    pub fn shell_outputs(&self) -> &[(ShellType, PathBuf)] {
+        &self.shell_outputs
+    }
+
     pub fn backup_dir(&self) -> PathBuf {
         self.config_dir.join("backups")
     }
 
+    pub fn snapshot_dir(&self) -> PathBuf {
+        self.config_dir.join("snapshots")
+    }
+
+    pub fn log_dir(&self) -> PathBuf {
+        self.config_dir.join("logs")
+    }
+
+    // Appended to by the usage hook in the generated shell files
+    pub fn usage_log(&self) -> PathBuf {
+        self.config_dir.join("usage.log")
+    }
+
+    // Daily use counts rolled up from the usage log
+    pub fn usage_stats(&self) -> PathBuf {
+        self.config_dir.join("usage.toml")
+    }
+
+    // The fingerprint of the store as last saved or synced, for the prompt
+    // check from `alx shell-init` to read without running alx
+    pub fn store_fingerprint_file(&self) -> PathBuf {
+        self.shell_dir().join("fingerprint")
+    }
+
     pub fn init(&self) -> Result<()> {
         // Create config directory
-        fs::create_dir_all(&self.config_dir)?;
-        fs::create_dir_all(self.shell_dir())?;
-        fs::create_dir_all(self.backup_dir())?;
+        if let Storage::Disk = self.storage {
+            fs::create_dir_all(&self.config_dir)?;
+            fs::create_dir_all(self.shell_dir())?;
+            fs::create_dir_all(self.backup_dir())?;
+        }
 
         // Create default config if not exists
-        if !self.config_file.exists() {
+        if self.read(&self.config_file)?.is_none() {
             let config = Config::default();
             self.save_config(&config)?;
         }
 
         // Create empty aliases file if not exists
-        if !self.aliases_file.exists() {
-            fs::write(&self.aliases_file, "aliases = []\n")?;
+        if self.read(&self.aliases_file)?.is_none() {
+            self.write(&self.aliases_file, "aliases = []\n")?;
         }
 
         Ok(())
     }
 
+    // Contents of a managed file, or None when it doesn't exist
+    pub fn read(&self, path: &Path) -> Result<Option<String>> {
+        match &self.storage {
+            Storage::Disk => match fs::read_to_string(path) {
+                Ok(content) => Ok(Some(content)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+            Storage::Memory(files) => Ok(lock(files).get(path).cloned()),
+        }
+    }
+
+    // Write a managed file, creating its directory first
+    pub fn write(&self, path: &Path, content: &str) -> Result<()> {
+        match &self.storage {
+            Storage::Disk => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, content)?;
+            }
+            Storage::Memory(files) => {
+                lock(files).insert(path.to_path_buf(), content.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load_aliases(&self) -> Result<AliasStore> {
+        let store = match self.read(&self.aliases_file)? {
+            Some(content) => AliasStore::parse(&content)?,
+            None => AliasStore::new(),
+        };
+        let ignore_case = self
+            .load_config()
+            .is_ok_and(|config| config.settings.ignore_case);
+        Ok(store.with_ignore_case(ignore_case))
+    }
+
+    pub fn save_aliases(&self, store: &AliasStore) -> Result<()> {
+        let content = store.to_toml()?;
+        self.write(&self.aliases_file, &content)?;
+        self.write(&self.store_fingerprint_file(), &fingerprint(&content))
+    }
+
+    pub fn load_config(&self) -> Result<Config> {
+        let Some(content) = self.read(&self.config_file)? else {
+            return Ok(Config::default());
+        };
+
+        toml::from_str(&content).map_err(|e| {
+            AlxError::ConfigError(format!(
+                "{:?} is invalid: {} (run 'alx config doctor' to check it)",
+                self.config_file,
+                e.message()
+            ))
+        })
+    }
+
     pub fn save_config(&self, config: &Config) -> Result<()> {
         let content =
             toml::to_string_pretty(config).map_err(|e| AlxError::ConfigError(e.to_string()))?;
-        fs::write(&self.config_file, content)?;
-        Ok(())
+        self.write(&self.config_file, &content)
     }
 
     pub fn is_initialized(&self) -> bool {
-        self.config_dir.exists() && self.config_file.exists()
+        matches!(self.read(&self.config_file), Ok(Some(_)))
+    }
+}
+
+// A short hash of the store file's content (64-bit FNV-1a), stable across
+// builds so files written by one alx version still compare with the next
+pub fn fingerprint(content: &str) -> String {
+    let hash = content.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+// A poisoned lock only means another thread panicked mid-write of a whole
+// string, so the map itself is still usable
+fn lock(
+    files: &Mutex<HashMap<PathBuf, String>>,
+) -> std::sync::MutexGuard<'_, HashMap<PathBuf, String>> {
+    files.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Discovery order: the --config-dir flag, then ALX_CONFIG_DIR, then the
+// XDG directory, then ~/.alx
+fn resolve_config_dir(flag: Option<&PathBuf>, env: Option<&str>) -> Result<PathBuf> {
+    let cwd = std::env::current_dir()?;
+    if let Some(dir) = flag {
+        return Ok(paths::expand_path(&dir.to_string_lossy(), &cwd));
     }
+    if let Some(dir) = env.filter(|d| !d.is_empty()) {
+        return Ok(paths::expand_path(dir, &cwd));
+    }
+
+    let home = dirs::home_dir()
+        .ok_or_else(|| AlxError::ConfigError("Could not find home directory".to_string()))?;
+    let xdg_config_home = std::env::var("XDG_CONFIG_HOME").ok();
+    Ok(default_config_dir(xdg_config_home.as_deref(), &home))
 }
 
-impl Default for ConfigManager {
-    fn default() -> Self {
-        Self::new().expect("Failed to create ConfigManager")
+// An existing XDG directory wins over an existing ~/.alx; new installs use XDG
+fn default_config_dir(xdg_config_home: Option<&str>, home: &Path) -> PathBuf {
+    let xdg_dir = match xdg_config_home.map(Path::new) {
+        Some(dir) if dir.is_absolute() => dir.join("alx"),
+        _ => home.join(".config").join("alx"),
+    };
+    let dot_dir = home.join(".alx");
+
+    if !xdg_dir.exists() && dot_dir.is_dir() {
+        dot_dir
+    } else {
+        xdg_dir
     }
 }
 
@@ -95,15 +325,7 @@ mod tests {
 
     fn create_test_manager() -> (ConfigManager, TempDir) {
         let temp_dir = TempDir::new().unwrap();
-        let config_dir = temp_dir.path().join("alx");
-        let config_file = config_dir.join("config.toml");
-        let aliases_file = config_dir.join("aliases.toml");
-
-        let manager = ConfigManager {
-            config_dir,
-            config_file,
-            aliases_file,
-        };
+        let manager = ConfigManager::unconfigured(temp_dir.path().join("alx"));
 
         (manager, temp_dir)
     }
@@ -117,12 +339,104 @@ mod tests {
         assert!(manager.aliases_file().exists());
     }
 
+    #[test]
+    fn test_resolve_config_dir() {
+        let home = dirs::home_dir().unwrap();
+        let flag = PathBuf::from("/tmp/alx-flag");
+
+        assert_eq!(
+            resolve_config_dir(Some(&flag), Some("/tmp/alx-env")).unwrap(),
+            flag
+        );
+        assert_eq!(
+            resolve_config_dir(None, Some("~/alx")).unwrap(),
+            home.join("alx")
+        );
+        assert_eq!(
+            resolve_config_dir(None, Some("")).unwrap(),
+            resolve_config_dir(None, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_default_config_dir() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        assert_eq!(default_config_dir(None, home), home.join(".config/alx"));
+        assert_eq!(
+            default_config_dir(Some("/xdg"), home),
+            PathBuf::from("/xdg/alx")
+        );
+        assert_eq!(
+            default_config_dir(Some("relative"), home),
+            home.join(".config/alx")
+        );
+
+        fs::create_dir(home.join(".alx")).unwrap();
+        assert_eq!(default_config_dir(None, home), home.join(".alx"));
+
+        fs::create_dir_all(home.join(".config/alx")).unwrap();
+        assert_eq!(default_config_dir(None, home), home.join(".config/alx"));
+    }
+
+    #[test]
+    fn test_with_config_dir() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("sandbox");
+        let manager = ConfigManager::with_config_dir(dir.clone()).unwrap();
+        manager.init().unwrap();
+
+        assert_eq!(manager.aliases_file(), &dir.join("aliases.toml"));
+        assert!(dir.join("config.toml").exists());
+
+        let mut config = manager.load_config().unwrap();
+        config.settings.zsh_output_path = Some("zsh/aliases.zsh".to_string());
+        manager.save_config(&config).unwrap();
+
+        let manager = ConfigManager::with_config_dir(dir.clone()).unwrap();
+        assert_eq!(
+            manager.shell_aliases_file_for(ShellType::Zsh),
+            dir.join("zsh/aliases.zsh")
+        );
+        assert_eq!(
+            manager.shell_aliases_file_for(ShellType::Bash),
+            dir.join("shell/aliases.sh")
+        );
+    }
+
+    #[test]
+    fn test_in_memory() {
+        let mut config = Config::default();
+        config.settings.fish_output_path = Some("fish/aliases.fish".to_string());
+        let manager = ConfigManager::in_memory(config).unwrap();
+        assert_eq!(
+            manager.shell_aliases_file_for(ShellType::Fish),
+            PathBuf::from("/alx-in-memory/fish/aliases.fish")
+        );
+
+        let store = AliasStore::from_aliases(vec![crate::alias::Alias::new(
+            "gs".to_string(),
+            "git status".to_string(),
+        )]);
+        manager.save_aliases(&store).unwrap();
+        assert_eq!(manager.load_aliases().unwrap().list().len(), 1);
+        assert!(!manager.aliases_file().exists());
+    }
+
     #[test]
     fn test_save_and_load_config() {
         let (manager, _temp) = create_test_manager();
         manager.init().unwrap();
 
-        let config = Config::default();
+        let mut config = Config::default();
+        config.settings.aliases_path = Some("~/dotfiles/aliases.toml".to_string());
         assert!(manager.save_config(&config).is_ok());
+
+        let loaded = manager.load_config().unwrap();
+        assert_eq!(
+            loaded.settings.aliases_path.as_deref(),
+            Some("~/dotfiles/aliases.toml")
+        );
     }
 }
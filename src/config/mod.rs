@@ -1,12 +1,88 @@
+pub mod doctor;
 pub mod manager;
+pub mod paths;
 
+use crate::alias::Alias;
+use crate::alias::filter::Filter;
+use crate::alias::validator::{Validation, ValidationProfile};
+use crate::editor;
+use crate::error::{AlxError, Result};
+use crate::export;
+use crate::hooks::AliasEvent;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Settings {
     pub default_shell: Option<String>,
     pub auto_sync: bool,
     pub backup_enabled: bool,
+    #[serde(default = "default_backup_keep")]
+    pub backup_keep: usize,
+    pub aliases_path: Option<String>,
+    pub pack_index_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_style: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_max_width: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_overflow: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bash_output_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zsh_output_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fish_output_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<String>,
+    #[serde(default)]
+    pub log_enabled: bool,
+    #[serde(default)]
+    pub track_usage: bool,
+    #[serde(default)]
+    pub validate_syntax: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_case: Option<String>,
+    #[serde(default)]
+    pub unicode_names: bool,
+    #[serde(default)]
+    pub emit_cleanup: bool,
+    #[serde(default)]
+    pub infer_groups: bool,
+    #[serde(default)]
+    pub ignore_case: bool,
+    #[serde(default = "default_update_check")]
+    pub update_check: bool,
+    #[serde(default = "default_auto_commit")]
+    pub auto_commit: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rc_file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rc_startup: Option<String>,
+    #[serde(default)]
+    pub source_in_login_shells: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_exclude_groups: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_exclude_tags: Option<String>,
+}
+
+fn default_backup_keep() -> usize {
+    10
+}
+
+fn default_update_check() -> bool {
+    true
+}
+
+fn default_auto_commit() -> bool {
+    true
 }
 
 impl Default for Settings {
@@ -15,6 +91,400 @@ impl Default for Settings {
             default_shell: None,
             auto_sync: true,
             backup_enabled: true,
+            backup_keep: default_backup_keep(),
+            aliases_path: None,
+            pack_index_url: None,
+            export_format: None,
+            export_path: None,
+            editor: None,
+            table_style: None,
+            table_max_width: None,
+            table_overflow: None,
+            bash_output_path: None,
+            zsh_output_path: None,
+            fish_output_path: None,
+            validation: None,
+            log_enabled: false,
+            track_usage: false,
+            validate_syntax: false,
+            group_case: None,
+            unicode_names: false,
+            emit_cleanup: false,
+            infer_groups: false,
+            ignore_case: false,
+            update_check: true,
+            auto_commit: true,
+            rc_file: None,
+            rc_startup: None,
+            source_in_login_shells: false,
+            sync_exclude_groups: None,
+            sync_exclude_tags: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn validation_profile(&self) -> ValidationProfile {
+        self.validation
+            .as_deref()
+            .and_then(|v| ValidationProfile::parse(v).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn validation(&self) -> Validation {
+        Validation {
+            profile: self.validation_profile(),
+            unicode_names: self.unicode_names,
+        }
+    }
+
+    // A group name as given by the user, trimmed and cased per group_case
+    pub fn group_name(&self, group: &str) -> String {
+        let group = group.trim();
+        match self.group_case.as_deref() {
+            Some("lower") => group.to_lowercase(),
+            _ => group.to_string(),
+        }
+    }
+
+    // The aliases that go into the generated shell files: all but those in
+    // the comma-separated sync_exclude_groups and sync_exclude_tags
+    pub fn sync_filter(&self) -> Filter {
+        let names = |list: &Option<String>| -> Vec<String> {
+            list.iter()
+                .flat_map(|l| l.split(','))
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let filter = names(&self.sync_exclude_groups)
+            .iter()
+            .fold(Filter::new(), |f, group| f.without_group(group));
+        names(&self.sync_exclude_tags)
+            .iter()
+            .fold(filter, |f, tag| f.without_tag(tag))
+    }
+
+    pub const KEYS: &[&str] = &[
+        "default_shell",
+        "auto_sync",
+        "backup_enabled",
+        "backup_keep",
+        "aliases_path",
+        "pack_index_url",
+        "export_format",
+        "export_path",
+        "editor",
+        "table_style",
+        "table_max_width",
+        "table_overflow",
+        "bash_output_path",
+        "zsh_output_path",
+        "fish_output_path",
+        "validation",
+        "log_enabled",
+        "track_usage",
+        "validate_syntax",
+        "group_case",
+        "unicode_names",
+        "emit_cleanup",
+        "infer_groups",
+        "ignore_case",
+        "update_check",
+        "auto_commit",
+        "rc_file",
+        "rc_startup",
+        "source_in_login_shells",
+        "sync_exclude_groups",
+        "sync_exclude_tags",
+    ];
+
+    // Current value as shown to the user; unset optional values are empty
+    pub fn get(&self, key: &str) -> Result<String> {
+        let value = match key {
+            "default_shell" => self.default_shell.clone().unwrap_or_default(),
+            "auto_sync" => self.auto_sync.to_string(),
+            "backup_enabled" => self.backup_enabled.to_string(),
+            "backup_keep" => self.backup_keep.to_string(),
+            "aliases_path" => self.aliases_path.clone().unwrap_or_default(),
+            "pack_index_url" => self.pack_index_url.clone().unwrap_or_default(),
+            "export_format" => self.export_format.clone().unwrap_or_default(),
+            "export_path" => self.export_path.clone().unwrap_or_default(),
+            "editor" => self.editor.clone().unwrap_or_default(),
+            "table_style" => self.table_style.clone().unwrap_or_default(),
+            "table_max_width" => self
+                .table_max_width
+                .map(|w| w.to_string())
+                .unwrap_or_default(),
+            "table_overflow" => self.table_overflow.clone().unwrap_or_default(),
+            "bash_output_path" => self.bash_output_path.clone().unwrap_or_default(),
+            "zsh_output_path" => self.zsh_output_path.clone().unwrap_or_default(),
+            "fish_output_path" => self.fish_output_path.clone().unwrap_or_default(),
+            "validation" => self.validation.clone().unwrap_or_default(),
+            "log_enabled" => self.log_enabled.to_string(),
+            "track_usage" => self.track_usage.to_string(),
+            "validate_syntax" => self.validate_syntax.to_string(),
+            "group_case" => self.group_case.clone().unwrap_or_default(),
+            "unicode_names" => self.unicode_names.to_string(),
+            "emit_cleanup" => self.emit_cleanup.to_string(),
+            "infer_groups" => self.infer_groups.to_string(),
+            "ignore_case" => self.ignore_case.to_string(),
+            "update_check" => self.update_check.to_string(),
+            "auto_commit" => self.auto_commit.to_string(),
+            "rc_file" => self.rc_file.clone().unwrap_or_default(),
+            "rc_startup" => self.rc_startup.clone().unwrap_or_default(),
+            "source_in_login_shells" => self.source_in_login_shells.to_string(),
+            "sync_exclude_groups" => self.sync_exclude_groups.clone().unwrap_or_default(),
+            "sync_exclude_tags" => self.sync_exclude_tags.clone().unwrap_or_default(),
+            _ => return Err(unknown_key(key)),
+        };
+        Ok(value)
+    }
+
+    // Parse and validate `value` for `key`
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "default_shell" => {
+                if !["bash", "zsh", "fish"].contains(&value) {
+                    return Err(invalid_value(key, value, "expected bash, zsh or fish"));
+                }
+                self.default_shell = Some(value.to_string());
+            }
+            "auto_sync" => self.auto_sync = parse_bool(key, value)?,
+            "backup_enabled" => self.backup_enabled = parse_bool(key, value)?,
+            "log_enabled" => self.log_enabled = parse_bool(key, value)?,
+            "track_usage" => self.track_usage = parse_bool(key, value)?,
+            "validate_syntax" => self.validate_syntax = parse_bool(key, value)?,
+            "unicode_names" => self.unicode_names = parse_bool(key, value)?,
+            "emit_cleanup" => self.emit_cleanup = parse_bool(key, value)?,
+            "infer_groups" => self.infer_groups = parse_bool(key, value)?,
+            "ignore_case" => self.ignore_case = parse_bool(key, value)?,
+            "update_check" => self.update_check = parse_bool(key, value)?,
+            "auto_commit" => self.auto_commit = parse_bool(key, value)?,
+            "source_in_login_shells" => self.source_in_login_shells = parse_bool(key, value)?,
+            "rc_file" => {
+                if value.trim().is_empty() {
+                    return Err(invalid_value(key, value, "expected a path"));
+                }
+                self.rc_file = Some(value.to_string());
+            }
+            "backup_keep" => {
+                self.backup_keep = match value.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(invalid_value(key, value, "expected a positive number")),
+                };
+            }
+            "aliases_path" => {
+                if value.trim().is_empty() {
+                    return Err(invalid_value(key, value, "expected a path"));
+                }
+                self.aliases_path = Some(value.to_string());
+            }
+            "pack_index_url" => {
+                if !value.starts_with("http://") && !value.starts_with("https://") {
+                    return Err(invalid_value(key, value, "expected an http(s) URL"));
+                }
+                self.pack_index_url = Some(value.to_string());
+            }
+            "export_format" => {
+                if !export::FORMATS.contains(&value) {
+                    return Err(invalid_value(
+                        key,
+                        value,
+                        "expected json, jsonl, toml, markdown, bash, zsh or fish",
+                    ));
+                }
+                self.export_format = Some(value.to_string());
+            }
+            "export_path" => {
+                if value.trim().is_empty() {
+                    return Err(invalid_value(key, value, "expected a path"));
+                }
+                self.export_path = Some(value.to_string());
+            }
+            "editor" => {
+                let program = value.split_whitespace().next().unwrap_or_default();
+                if editor::find_program(program).is_none() {
+                    return Err(invalid_value(key, value, "command not found"));
+                }
+                self.editor = Some(value.to_string());
+            }
+            "table_style" => {
+                if !["borders", "none"].contains(&value) {
+                    return Err(invalid_value(key, value, "expected borders or none"));
+                }
+                self.table_style = Some(value.to_string());
+            }
+            "table_max_width" => {
+                self.table_max_width = match value.parse() {
+                    Ok(n) if n >= 8 => Some(n),
+                    _ => return Err(invalid_value(key, value, "expected a number of at least 8")),
+                };
+            }
+            "rc_startup" => {
+                if !["interactive", "login"].contains(&value) {
+                    return Err(invalid_value(key, value, "expected interactive or login"));
+                }
+                self.rc_startup = Some(value.to_string());
+            }
+            "table_overflow" => {
+                if !["wrap", "truncate"].contains(&value) {
+                    return Err(invalid_value(key, value, "expected wrap or truncate"));
+                }
+                self.table_overflow = Some(value.to_string());
+            }
+            "group_case" => {
+                if !["preserve", "lower"].contains(&value) {
+                    return Err(invalid_value(key, value, "expected preserve or lower"));
+                }
+                self.group_case = Some(value.to_string());
+            }
+            "validation" => {
+                ValidationProfile::parse(value).map_err(|_| {
+                    invalid_value(key, value, "expected standard, strict or lenient")
+                })?;
+                self.validation = Some(value.to_string());
+            }
+            "sync_exclude_groups" | "sync_exclude_tags" => {
+                if value.split(',').all(|n| n.trim().is_empty()) {
+                    return Err(invalid_value(key, value, "expected comma-separated names"));
+                }
+                let names = Some(value.to_string());
+                match key {
+                    "sync_exclude_groups" => self.sync_exclude_groups = names,
+                    _ => self.sync_exclude_tags = names,
+                }
+            }
+            "bash_output_path" | "zsh_output_path" | "fish_output_path" => {
+                if value.trim().is_empty() {
+                    return Err(invalid_value(key, value, "expected a path"));
+                }
+                let path = Some(value.to_string());
+                match key {
+                    "bash_output_path" => self.bash_output_path = path,
+                    "zsh_output_path" => self.zsh_output_path = path,
+                    _ => self.fish_output_path = path,
+                }
+            }
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    // Reset `key` to its default value
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        let defaults = Settings::default();
+        match key {
+            "default_shell" => self.default_shell = defaults.default_shell,
+            "auto_sync" => self.auto_sync = defaults.auto_sync,
+            "backup_enabled" => self.backup_enabled = defaults.backup_enabled,
+            "backup_keep" => self.backup_keep = defaults.backup_keep,
+            "aliases_path" => self.aliases_path = defaults.aliases_path,
+            "pack_index_url" => self.pack_index_url = defaults.pack_index_url,
+            "export_format" => self.export_format = defaults.export_format,
+            "export_path" => self.export_path = defaults.export_path,
+            "editor" => self.editor = defaults.editor,
+            "table_style" => self.table_style = defaults.table_style,
+            "table_max_width" => self.table_max_width = defaults.table_max_width,
+            "table_overflow" => self.table_overflow = defaults.table_overflow,
+            "bash_output_path" => self.bash_output_path = defaults.bash_output_path,
+            "zsh_output_path" => self.zsh_output_path = defaults.zsh_output_path,
+            "fish_output_path" => self.fish_output_path = defaults.fish_output_path,
+            "validation" => self.validation = defaults.validation,
+            "log_enabled" => self.log_enabled = defaults.log_enabled,
+            "track_usage" => self.track_usage = defaults.track_usage,
+            "validate_syntax" => self.validate_syntax = defaults.validate_syntax,
+            "group_case" => self.group_case = defaults.group_case,
+            "unicode_names" => self.unicode_names = defaults.unicode_names,
+            "emit_cleanup" => self.emit_cleanup = defaults.emit_cleanup,
+            "infer_groups" => self.infer_groups = defaults.infer_groups,
+            "ignore_case" => self.ignore_case = defaults.ignore_case,
+            "update_check" => self.update_check = defaults.update_check,
+            "auto_commit" => self.auto_commit = defaults.auto_commit,
+            "rc_file" => self.rc_file = defaults.rc_file,
+            "rc_startup" => self.rc_startup = defaults.rc_startup,
+            "source_in_login_shells" => {
+                self.source_in_login_shells = defaults.source_in_login_shells
+            }
+            "sync_exclude_groups" => self.sync_exclude_groups = defaults.sync_exclude_groups,
+            "sync_exclude_tags" => self.sync_exclude_tags = defaults.sync_exclude_tags,
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value {
+        "true" | "yes" | "on" => Ok(true),
+        "false" | "no" | "off" => Ok(false),
+        _ => Err(invalid_value(key, value, "expected true or false")),
+    }
+}
+
+fn unknown_key(key: &str) -> AlxError {
+    AlxError::ConfigError(format!(
+        "Unknown setting: {} (expected one of {}, {})",
+        key,
+        Settings::KEYS.join(", "),
+        Hooks::KEYS.join(", ")
+    ))
+}
+
+fn invalid_value(key: &str, value: &str, hint: &str) -> AlxError {
+    AlxError::ConfigError(format!("Invalid value for {}: '{}' ({})", key, value, hint))
+}
+
+// Shell commands run around regenerating the shell aliases file, and after
+// an alias is added, removed or edited
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Hooks {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_sync: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_sync: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_add: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_remove: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_edit: Option<String>,
+}
+
+impl Hooks {
+    pub const KEYS: &[&str] = &[
+        "hooks.pre_sync",
+        "hooks.post_sync",
+        "hooks.post_add",
+        "hooks.post_remove",
+        "hooks.post_edit",
+    ];
+
+    pub fn is_empty(&self) -> bool {
+        self.pre_sync.is_none()
+            && self.post_sync.is_none()
+            && self.post_add.is_none()
+            && self.post_remove.is_none()
+            && self.post_edit.is_none()
+    }
+
+    pub fn for_event(&self, event: AliasEvent) -> Option<&String> {
+        match event {
+            AliasEvent::Add => self.post_add.as_ref(),
+            AliasEvent::Remove => self.post_remove.as_ref(),
+            AliasEvent::Edit => self.post_edit.as_ref(),
+        }
+    }
+
+    fn slot(&mut self, key: &str) -> Result<&mut Option<String>> {
+        match key {
+            "hooks.pre_sync" => Ok(&mut self.pre_sync),
+            "hooks.post_sync" => Ok(&mut self.post_sync),
+            "hooks.post_add" => Ok(&mut self.post_add),
+            "hooks.post_remove" => Ok(&mut self.post_remove),
+            "hooks.post_edit" => Ok(&mut self.post_edit),
+            _ => Err(unknown_key(key)),
         }
     }
 }
@@ -22,4 +492,183 @@ impl Default for Settings {
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
     pub settings: Settings,
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+    // Groups only active inside these directories, by group name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scopes: BTreeMap<String, Vec<String>>,
+    // Groups whose aliases need --force to edit or remove
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub locked_groups: BTreeSet<String>,
+    // Colors for group names in listings, overriding the picked ones
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub group_colors: BTreeMap<String, String>,
+}
+
+impl Config {
+    // Refuse to change `alias` when its group is locked, unless forced
+    pub fn check_unlocked(&self, alias: &Alias, force: bool) -> Result<()> {
+        match &alias.group {
+            Some(group) if !force && self.locked_groups.contains(group) => {
+                Err(AlxError::GroupLocked(group.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn keys() -> impl Iterator<Item = &'static str> {
+        Settings::KEYS.iter().chain(Hooks::KEYS).copied()
+    }
+
+    pub fn get(&self, key: &str) -> Result<String> {
+        if key.starts_with("hooks.") {
+            return Ok(self.hooks.clone().slot(key)?.clone().unwrap_or_default());
+        }
+        self.settings.get(key)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        if key.starts_with("hooks.") {
+            if value.trim().is_empty() {
+                return Err(invalid_value(key, value, "expected a command"));
+            }
+            *self.hooks.slot(key)? = Some(value.to_string());
+            return Ok(());
+        }
+        self.settings.set(key, value)
+    }
+
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        if key.starts_with("hooks.") {
+            *self.hooks.slot(key)? = None;
+            return Ok(());
+        }
+        self.settings.unset(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_and_set() {
+        let mut settings = Settings::default();
+        for key in Settings::KEYS {
+            assert!(settings.get(key).is_ok());
+        }
+
+        settings.set("auto_sync", "false").unwrap();
+        settings.set("default_shell", "fish").unwrap();
+        settings.set("backup_keep", "3").unwrap();
+        settings.set("log_enabled", "true").unwrap();
+        assert!(settings.log_enabled);
+        settings.set("validate_syntax", "yes").unwrap();
+        assert!(settings.validate_syntax);
+        assert_eq!(settings.group_name(" Git "), "Git");
+        settings.set("group_case", "lower").unwrap();
+        assert_eq!(settings.group_name("Git"), "git");
+        assert!(settings.set("group_case", "upper").is_err());
+        settings.set("rc_startup", "login").unwrap();
+        assert!(settings.set("rc_startup", "both").is_err());
+        settings
+            .set("zsh_output_path", "~/.zsh/aliases.zsh")
+            .unwrap();
+        assert_eq!(
+            settings.zsh_output_path.as_deref(),
+            Some("~/.zsh/aliases.zsh")
+        );
+        assert_eq!(settings.get("auto_sync").unwrap(), "false");
+        assert_eq!(settings.get("default_shell").unwrap(), "fish");
+        assert_eq!(settings.backup_keep, 3);
+
+        settings.unset("default_shell").unwrap();
+        assert_eq!(settings.get("default_shell").unwrap(), "");
+    }
+
+    #[test]
+    fn test_check_unlocked() {
+        let mut config = Config::default();
+        config.locked_groups.insert("shared".to_string());
+        let shared =
+            Alias::new("gs".to_string(), "git status".to_string()).with_group("shared".to_string());
+        let mine = Alias::new("ll".to_string(), "ls -la".to_string());
+
+        assert!(matches!(
+            config.check_unlocked(&shared, false),
+            Err(AlxError::GroupLocked(_))
+        ));
+        assert!(config.check_unlocked(&shared, true).is_ok());
+        assert!(config.check_unlocked(&mine, false).is_ok());
+    }
+
+    #[test]
+    fn test_hooks() {
+        let mut config = Config::default();
+        config
+            .set("hooks.post_sync", "tmux source-file ~/.tmux.conf")
+            .unwrap();
+        assert_eq!(
+            config.get("hooks.post_sync").unwrap(),
+            "tmux source-file ~/.tmux.conf"
+        );
+        assert_eq!(config.get("hooks.pre_sync").unwrap(), "");
+        assert!(config.set("hooks.on_add", "true").is_err());
+
+        config.set("hooks.post_remove", "logger removed").unwrap();
+        assert_eq!(
+            config
+                .hooks
+                .for_event(AliasEvent::Remove)
+                .map(String::as_str),
+            Some("logger removed")
+        );
+        assert!(config.hooks.for_event(AliasEvent::Add).is_none());
+
+        config.unset("hooks.post_sync").unwrap();
+        assert!(config.hooks.post_sync.is_none());
+        assert_eq!(Config::keys().count(), Settings::KEYS.len() + 5);
+    }
+
+    #[test]
+    fn test_set_validation() {
+        let mut settings = Settings::default();
+        assert!(settings.set("auto_sync", "maybe").is_err());
+        assert!(settings.set("default_shell", "tcsh").is_err());
+        assert!(settings.set("backup_keep", "0").is_err());
+        assert!(settings.set("pack_index_url", "ftp://example.com").is_err());
+        assert!(settings.set("export_format", "yaml").is_err());
+        assert!(settings.set("export_format", "markdown").is_ok());
+        assert!(settings.set("editor", "alx-no-such-editor --wait").is_err());
+        assert!(settings.set("editor", "sh -c").is_ok());
+        assert!(settings.set("table_style", "ascii").is_err());
+        assert!(settings.set("table_max_width", "4").is_err());
+        assert!(settings.set("table_overflow", "truncate").is_ok());
+        assert!(settings.set("validation", "loose").is_err());
+        assert!(settings.set("validation", "lenient").is_ok());
+        assert_eq!(settings.validation_profile(), ValidationProfile::Lenient);
+        assert!(settings.set("colour", "red").is_err());
+        assert!(settings.get("colour").is_err());
+    }
+
+    #[test]
+    fn test_sync_filter() {
+        let mut settings = Settings::default();
+        let work =
+            Alias::new("vpn".to_string(), "vpn connect".to_string()).with_group("work".to_string());
+        let client = Alias::new("deploy".to_string(), "make deploy".to_string())
+            .with_tags(vec!["client".to_string()]);
+        let mine = Alias::new("ll".to_string(), "ls -la".to_string());
+        assert!(settings.sync_filter().matches(&work));
+
+        assert!(settings.set("sync_exclude_groups", " , ").is_err());
+        settings
+            .set("sync_exclude_groups", "work, scratch")
+            .unwrap();
+        settings.set("sync_exclude_tags", "client").unwrap();
+        let filter = settings.sync_filter();
+        assert!(!filter.matches(&work));
+        assert!(!filter.matches(&client));
+        assert!(filter.matches(&mine));
+    }
 }
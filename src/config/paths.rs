@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+// Expand a leading `~` or `$HOME` and resolve relative paths against `base`
+pub fn expand_path(path: &str, base: &Path) -> PathBuf {
+    let home = dirs::home_dir();
+
+    let expanded = match (home, path) {
+        (Some(home), "~" | "$HOME") => home,
+        (Some(home), p) if p.starts_with("~/") => home.join(&p[2..]),
+        (Some(home), p) if p.starts_with("$HOME/") => home.join(&p[6..]),
+        (_, p) => PathBuf::from(p),
+    };
+
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        base.join(expanded)
+    }
+}
+
+// Render a path for shell config files, keeping it relative to $HOME so the
+// same line works on every machine a dotfiles repo is checked out on
+pub fn portable_path(path: &Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(relative) if relative.as_os_str().is_empty() => "$HOME".to_string(),
+        Some(relative) => format!("$HOME/{}", relative.display()),
+        None => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_path() {
+        let home = dirs::home_dir().unwrap();
+        let base = Path::new("/etc/alx");
+
+        assert_eq!(
+            expand_path("~/dotfiles/aliases.toml", base),
+            home.join("dotfiles/aliases.toml")
+        );
+        assert_eq!(
+            expand_path("$HOME/dotfiles/aliases.toml", base),
+            home.join("dotfiles/aliases.toml")
+        );
+        assert_eq!(
+            expand_path("/opt/aliases.toml", base),
+            PathBuf::from("/opt/aliases.toml")
+        );
+        assert_eq!(
+            expand_path("aliases.toml", base),
+            PathBuf::from("/etc/alx/aliases.toml")
+        );
+    }
+
+    #[test]
+    fn test_portable_path() {
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(
+            portable_path(&home.join(".config/alx/shell/aliases.sh")),
+            "$HOME/.config/alx/shell/aliases.sh"
+        );
+        assert_eq!(
+            portable_path(Path::new("/opt/alx/aliases.sh")),
+            "/opt/alx/aliases.sh"
+        );
+    }
+}
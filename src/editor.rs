@@ -0,0 +1,134 @@
+use crate::alias::Alias;
+use crate::config::Settings;
+use crate::error::{AlxError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// The fields of an alias that can be changed in the editor
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct AliasEdit {
+    pub command: String,
+    pub description: Option<String>,
+    pub group: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl AliasEdit {
+    pub fn from_alias(alias: &Alias) -> Self {
+        Self {
+            command: alias.command.clone(),
+            description: alias.description.clone(),
+            group: alias.group.clone(),
+            tags: alias.tags.clone(),
+        }
+    }
+}
+
+// The editor setting, then $VISUAL, then $EDITOR
+pub fn configured(settings: &Settings) -> Option<String> {
+    settings
+        .editor
+        .clone()
+        .or_else(|| std::env::var("VISUAL").ok())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|e| !e.trim().is_empty())
+}
+
+// Build a command for an editor string such as `code --wait`
+pub fn command(editor: &str) -> Result<Command> {
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| AlxError::ConfigError("Editor command is empty".to_string()))?;
+
+    if find_program(program).is_none() {
+        return Err(AlxError::ConfigError(format!(
+            "Editor not found: {} (check the editor setting, $VISUAL or $EDITOR)",
+            program
+        )));
+    }
+
+    let mut command = Command::new(program);
+    command.args(parts);
+    Ok(command)
+}
+
+pub fn find_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+pub fn open_file(editor: &str, path: &Path) -> Result<()> {
+    let status = command(editor)?
+        .arg(path)
+        .status()
+        .map_err(|e| AlxError::ConfigError(format!("Failed to open {:?}: {}", path, e)))?;
+
+    if !status.success() {
+        return Err(AlxError::ConfigError(format!(
+            "Editor exited with {}",
+            status
+        )));
+    }
+    Ok(())
+}
+
+// Open the alias as TOML in the editor and read back the result. Returns
+// None when nothing was changed.
+pub fn edit_alias(editor: &str, alias: &Alias) -> Result<Option<AliasEdit>> {
+    let original = AliasEdit::from_alias(alias);
+    let content = format!(
+        "# Editing alias '{}'. Save and close the editor to apply.\n# Fields: command, description, group, tags\n{}",
+        alias.name,
+        toml::to_string_pretty(&original).map_err(|e| AlxError::ConfigError(e.to_string()))?
+    );
+
+    let path = std::env::temp_dir().join(format!("alx-edit-{}.toml", alias.name));
+    fs::write(&path, content)?;
+    let result = open_file(editor, &path).and_then(|_| parse(&fs::read_to_string(&path)?));
+    let _ = fs::remove_file(&path);
+
+    let edited = result?;
+    Ok((edited != original).then_some(edited))
+}
+
+fn parse(content: &str) -> Result<AliasEdit> {
+    let mut edited: AliasEdit = toml::from_str(content)?;
+    // An emptied field means "unset"
+    edited.description = edited.description.filter(|d| !d.trim().is_empty());
+    edited.group = edited.group.filter(|g| !g.trim().is_empty());
+    Ok(edited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let edited =
+            parse("command = \"ls -lah\"\ndescription = \"\"\ngroup = \"utils\"\n").unwrap();
+        assert_eq!(edited.command, "ls -lah");
+        assert!(edited.description.is_none());
+        assert_eq!(edited.group.as_deref(), Some("utils"));
+        assert!(edited.tags.is_empty());
+
+        assert!(parse("description = \"no command\"\n").is_err());
+    }
+
+    #[test]
+    fn test_find_program() {
+        assert!(find_program("sh").is_some());
+        assert!(find_program("/bin/sh").is_some());
+        assert!(find_program("alx-no-such-editor").is_none());
+    }
+}
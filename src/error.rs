@@ -5,12 +5,21 @@ pub enum AlxError {
     #[error("Alias '{0}' already exists")]
     AliasExists(String),
 
+    #[error("Alias '{0}' already exists; free names include {1}")]
+    AliasTaken(String, String),
+
     #[error("Alias '{0}' not found")]
     AliasNotFound(String),
 
+    #[error("Alias '{0}' not found; did you mean {1}?")]
+    AliasMisspelled(String, String),
+
     #[error("Invalid alias name: {0}")]
     InvalidAliasName(String),
 
+    #[error("Invalid group name: {0}")]
+    InvalidGroupName(String),
+
     #[error("Invalid command: {0}")]
     InvalidCommand(String),
 
@@ -23,6 +32,30 @@ pub enum AlxError {
     #[error("Shell not supported: {0}")]
     UnsupportedShell(String),
 
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
+    #[error("Clipboard error: {0}")]
+    ClipboardError(String),
+
+    #[error("Hook failed: {0}")]
+    HookFailed(String),
+
+    #[error("Secret lookup failed: {0}")]
+    SecretLookupFailed(String),
+
+    #[error("Group '{0}' is locked; pass --force to change its aliases")]
+    GroupLocked(String),
+
+    #[error("Alias '{0}' is protected; pass --force to remove it or change its command")]
+    AliasProtected(String),
+
+    #[error("Unknown command '{0}' (no alx-{0} plugin on PATH)")]
+    UnknownCommand(String),
+
+    #[error("Git error: {0}")]
+    GitError(#[from] git2::Error),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -36,4 +69,47 @@ pub enum AlxError {
     JsonError(#[from] serde_json::Error),
 }
 
+impl AlxError {
+    // Process exit status for this error, so scripts can tell failures apart
+    // without parsing stderr. 2 is shared with clap for usage errors. Keep the
+    // table in the README in sync.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AlxError::AliasNotFound(_) | AlxError::AliasMisspelled(..) => 3,
+            AlxError::AliasExists(_) | AlxError::AliasTaken(..) => 4,
+            AlxError::InvalidAliasName(_)
+            | AlxError::InvalidGroupName(_)
+            | AlxError::InvalidCommand(_) => 5,
+            AlxError::ShellDetectionFailed | AlxError::UnsupportedShell(_) => 6,
+            AlxError::IoError(_) => 7,
+            AlxError::SerializationError(_)
+            | AlxError::DeserializationError(_)
+            | AlxError::JsonError(_) => 8,
+            AlxError::NetworkError(_) => 9,
+            AlxError::GitError(_) => 10,
+            AlxError::HookFailed(_) => 11,
+            AlxError::ClipboardError(_) => 12,
+            AlxError::SecretLookupFailed(_) => 13,
+            AlxError::GroupLocked(_) => 14,
+            AlxError::AliasProtected(_) => 15,
+            AlxError::UnknownCommand(_) => 2,
+            AlxError::ConfigError(_) => 1,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, AlxError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(AlxError::AliasNotFound("gs".to_string()).exit_code(), 3);
+        assert_eq!(AlxError::InvalidCommand(String::new()).exit_code(), 5);
+        assert_eq!(AlxError::ShellDetectionFailed.exit_code(), 6);
+        assert_eq!(AlxError::AliasProtected("gs".to_string()).exit_code(), 15);
+        assert_eq!(AlxError::ConfigError(String::new()).exit_code(), 1);
+    }
+}
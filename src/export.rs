@@ -0,0 +1,238 @@
+use crate::alias::Alias;
+use crate::alias::store::AliasStore;
+use crate::error::{AlxError, Result};
+use crate::shell::registry;
+use crate::shell::{ShellHandler, ShellType};
+use chrono::Utc;
+use std::collections::BTreeMap;
+
+pub const FORMATS: &[&str] = &[
+    "json", "jsonl", "toml", "markdown", "md", "bash", "zsh", "fish",
+];
+
+// File extension used when exporting into a directory
+pub fn extension(format: &str) -> &str {
+    match format {
+        "markdown" | "md" => "md",
+        "bash" => "sh",
+        other => other,
+    }
+}
+
+// File name for one group's aliases when exporting a file per group. Path
+// separators in a hierarchical name like `dev/git` become `-`, so every file
+// lands directly in the export directory.
+pub fn group_file_name(group: &str, format: &str) -> String {
+    let name: String = group
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\') { '-' } else { c })
+        .collect();
+    format!("{}.{}", name, extension(format))
+}
+
+// `aliases` in `format`. Links stay links in the formats alx reads back, and
+// run their target's command, looked up in `store`, everywhere else.
+pub fn render(store: &AliasStore, aliases: &[&Alias], format: &str) -> Result<String> {
+    let shell = |shell_type| -> Result<String> {
+        let resolved = resolve(store, aliases)?;
+        let resolved: Vec<&Alias> = resolved.iter().collect();
+        Ok(render_shell(&resolved, &*registry::handler(shell_type)))
+    };
+    match format {
+        "json" => Ok(serde_json::to_string_pretty(&to_store(aliases))?),
+        "jsonl" => render_jsonl(aliases),
+        "toml" => toml::to_string_pretty(&to_store(aliases))
+            .map_err(|e| AlxError::ConfigError(e.to_string())),
+        "markdown" | "md" => {
+            let resolved = resolve(store, aliases)?;
+            Ok(render_markdown(&resolved.iter().collect::<Vec<_>>()))
+        }
+        "bash" => shell(ShellType::Bash),
+        "zsh" => shell(ShellType::Zsh),
+        "fish" => shell(ShellType::Fish),
+        _ => Err(AlxError::ConfigError(format!(
+            "Unsupported format: {}",
+            format
+        ))),
+    }
+}
+
+// Copies of `aliases` with each link's command replaced by its target's
+fn resolve(store: &AliasStore, aliases: &[&Alias]) -> Result<Vec<Alias>> {
+    aliases
+        .iter()
+        .map(|alias| {
+            let mut alias = (*alias).clone();
+            if alias.is_link() {
+                alias.command = store.resolve_link(&alias.name)?.command.clone();
+            }
+            Ok(alias)
+        })
+        .collect()
+}
+
+fn to_store(aliases: &[&Alias]) -> AliasStore {
+    AliasStore::from_aliases(aliases.iter().map(|a| (*a).clone()).collect())
+}
+
+// One compact alias object per line, for jq, grep and log pipelines
+fn render_jsonl(aliases: &[&Alias]) -> Result<String> {
+    let lines = aliases
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(lines.join("\n"))
+}
+
+fn render_markdown(aliases: &[&Alias]) -> String {
+    let mut content = String::new();
+    content.push_str("# Aliases\n\n");
+    content.push_str(&format!(
+        "_Generated by alx on {}_\n",
+        Utc::now().format("%Y-%m-%d %H:%M UTC")
+    ));
+
+    if aliases.is_empty() {
+        content.push_str("\nNo aliases configured.\n");
+        return content;
+    }
+
+    // Group by category, sorted by group name
+    let mut grouped: BTreeMap<&str, Vec<&Alias>> = BTreeMap::new();
+    for alias in aliases {
+        let group = alias.group.as_deref().unwrap_or("general");
+        grouped.entry(group).or_default().push(alias);
+    }
+
+    for (group, group_aliases) in grouped {
+        content.push_str(&format!("\n## {}\n\n", group));
+        content.push_str("| Name | Command | Description |\n");
+        content.push_str("| --- | --- | --- |\n");
+
+        for alias in group_aliases {
+            content.push_str(&format!(
+                "| {} | {} | {} |\n",
+                markdown_code(&alias.name),
+                markdown_code(&alias.command),
+                alias
+                    .description
+                    .as_deref()
+                    .map(|d| d.replace('|', "\\|"))
+                    .unwrap_or_default()
+            ));
+        }
+    }
+
+    content
+}
+
+// A plain sourceable script, usable without alx installed
+fn render_shell(aliases: &[&Alias], handler: &dyn ShellHandler) -> String {
+    let mut content = String::new();
+    content.push_str(&format!(
+        "# Aliases exported by alx on {}\n\n",
+        Utc::now().format("%Y-%m-%d %H:%M UTC")
+    ));
+
+    for alias in aliases {
+        if let Some(desc) = &alias.description {
+            content.push_str(&format!("# {}\n", desc));
+        }
+        content.push_str(&handler.generate_alias_line(alias));
+        content.push('\n');
+    }
+
+    content
+}
+
+// Wrap text in a code span that survives backticks and table pipes
+fn markdown_code(text: &str) -> String {
+    let text = text.replace('|', "\\|");
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_markdown() {
+        let alias1 = Alias::new("gs".to_string(), "git status".to_string())
+            .with_description("Show git status".to_string())
+            .with_group("git".to_string());
+        let alias2 = Alias::new("lc".to_string(), "ls | wc -l".to_string());
+
+        let content = render(&AliasStore::new(), &[&alias1, &alias2], "markdown").unwrap();
+
+        assert!(content.starts_with("# Aliases\n"));
+        assert!(content.contains("_Generated by alx on "));
+        assert!(content.contains("## general"));
+        assert!(content.contains("## git"));
+        assert!(content.contains("| `gs` | `git status` | Show git status |"));
+        assert!(content.contains("| `lc` | `ls \\| wc -l` |  |"));
+        assert!(content.find("## general").unwrap() < content.find("## git").unwrap());
+    }
+
+    #[test]
+    fn test_markdown_code_with_backticks() {
+        assert_eq!(markdown_code("echo `date`"), "`` echo `date` ``");
+    }
+
+    #[test]
+    fn test_render_shell_script() {
+        let alias = Alias::new("gs".to_string(), "git status".to_string())
+            .with_description("Show git status".to_string());
+
+        let bash = render(&AliasStore::new(), &[&alias], "bash").unwrap();
+        assert!(bash.starts_with("# Aliases exported by alx on "));
+        assert!(bash.contains("# Show git status\nalias gs='git status'\n"));
+
+        let fish = render(&AliasStore::new(), &[&alias], "fish").unwrap();
+        assert!(fish.contains("alias gs 'git status'\n"));
+    }
+
+    #[test]
+    fn test_render_jsonl() {
+        let alias1 = Alias::new("gs".to_string(), "git status".to_string());
+        let alias2 = Alias::new("ll".to_string(), "ls -la\nls".to_string());
+
+        let content = render(&AliasStore::new(), &[&alias1, &alias2], "jsonl").unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let second: Alias = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second.command, "ls -la\nls");
+        assert_eq!(render(&AliasStore::new(), &[], "jsonl").unwrap(), "");
+    }
+
+    #[test]
+    fn test_render_links() {
+        let store = AliasStore::from_aliases(vec![
+            Alias::new("gs".to_string(), "git status".to_string()),
+            Alias::link("st".to_string(), "gs".to_string()),
+        ]);
+        let link = store.get("st").unwrap();
+
+        let bash = render(&store, &[link], "bash").unwrap();
+        assert!(bash.contains("alias st='git status'\n"));
+        let markdown = render(&store, &[link], "markdown").unwrap();
+        assert!(markdown.contains("| `st` | `git status` |"));
+        let jsonl = render(&store, &[link], "jsonl").unwrap();
+        assert!(jsonl.contains(r#""command":"gs""#));
+    }
+
+    #[test]
+    fn test_group_file_name() {
+        assert_eq!(group_file_name("git", "bash"), "git.sh");
+        assert_eq!(group_file_name("dev/git", "md"), "dev-git.md");
+        assert_eq!(group_file_name("..\\etc", "json"), "..-etc.json");
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        assert!(render(&AliasStore::new(), &[], "yaml").is_err());
+    }
+}
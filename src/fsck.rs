@@ -0,0 +1,315 @@
+use crate::alias::Alias;
+use crate::alias::store::AliasStore;
+use crate::error::{AlxError, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+// Fields an alias entry may have; anything else was probably written by a
+// newer version of alx
+const ALIAS_FIELDS: &[&str] = &[
+    "name",
+    "command",
+    "description",
+    "group",
+    "tags",
+    "enabled",
+    "pack",
+    "kind",
+    "protected",
+    "created_at",
+    "updated_at",
+];
+const STORE_FIELDS: &[&str] = &["aliases", "packs", "groups"];
+
+// How an issue is repaired, on the parsed aliases.toml
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fix {
+    DropEntry(usize),
+    RemoveField {
+        entry: Option<usize>,
+        key: String,
+    },
+    SetTimestamp {
+        entry: usize,
+        key: String,
+        value: String,
+    },
+}
+
+impl std::fmt::Display for Fix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fix::DropEntry(_) => write!(f, "remove the entry"),
+            Fix::RemoveField { key, .. } => write!(f, "remove the {} field", key),
+            Fix::SetTimestamp { key, value, .. } => write!(f, "set {} to {}", key, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    // The alias name, or its position when the entry has no usable name
+    pub alias: String,
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+// Check the raw aliases.toml without deserializing it, so problems that would
+// stop the store from loading can be reported one by one
+pub fn check(content: &str, now: DateTime<Utc>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut issue = |alias: String, message: String, fix| {
+        issues.push(Issue {
+            alias,
+            message,
+            fix,
+        })
+    };
+
+    let table: toml::Table = match toml::from_str(content) {
+        Ok(table) => table,
+        Err(e) => {
+            issue(
+                "-".to_string(),
+                format!("not valid TOML: {}", e.message().trim()),
+                None,
+            );
+            return issues;
+        }
+    };
+
+    for key in table.keys().filter(|k| !STORE_FIELDS.contains(&k.as_str())) {
+        let fix = Fix::RemoveField {
+            entry: None,
+            key: key.clone(),
+        };
+        issue(
+            "-".to_string(),
+            format!("unknown field '{}'", key),
+            Some(fix),
+        );
+    }
+
+    let entries = match table.get("aliases") {
+        None => return issues,
+        Some(toml::Value::Array(entries)) => entries,
+        Some(_) => {
+            issue("-".to_string(), "aliases is not a list".to_string(), None);
+            return issues;
+        }
+    };
+
+    let mut names = HashSet::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let position = format!("#{}", index + 1);
+        let drop = Some(Fix::DropEntry(index));
+        let Some(entry) = entry.as_table() else {
+            issue(position, "entry is not a table".to_string(), drop);
+            continue;
+        };
+
+        let name = match entry.get("name").and_then(|n| n.as_str()) {
+            Some(name) if !name.trim().is_empty() => name.to_string(),
+            _ => {
+                issue(position, "entry has no name".to_string(), drop);
+                continue;
+            }
+        };
+        if !names.insert(name.clone()) {
+            let message = "duplicate name; an earlier entry has it".to_string();
+            issue(name, message, drop);
+            continue;
+        }
+        if entry
+            .get("command")
+            .and_then(|c| c.as_str())
+            .is_none_or(|c| c.trim().is_empty())
+        {
+            issue(name, "empty command".to_string(), drop);
+            continue;
+        }
+
+        // Check the rest against a copy with the field fixes applied, so an
+        // entry is only dropped when those aren't enough
+        let mut fixed = entry.clone();
+        for key in entry.keys().filter(|k| !ALIAS_FIELDS.contains(&k.as_str())) {
+            fixed.remove(key);
+            let fix = Fix::RemoveField {
+                entry: Some(index),
+                key: key.clone(),
+            };
+            let message = format!("unknown field '{}' (from a newer alx?)", key);
+            issue(name.clone(), message, Some(fix));
+        }
+
+        let timestamp = |key: &str| {
+            entry
+                .get(key)
+                .and_then(|v| v.as_str())
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                .map(|t| t.with_timezone(&Utc))
+        };
+        let created = timestamp("created_at");
+        let updated = timestamp("updated_at");
+        let mut set_timestamp = |key: &str, value: DateTime<Utc>, message: String| {
+            let value = value.to_rfc3339();
+            fixed.insert(key.to_string(), toml::Value::String(value.clone()));
+            let fix = Fix::SetTimestamp {
+                entry: index,
+                key: key.to_string(),
+                value,
+            };
+            issue(name.clone(), message, Some(fix));
+        };
+        let invalid = |key: &str| match entry.get(key) {
+            Some(value) => format!("invalid {}: {}", key, value),
+            None => format!("missing {}", key),
+        };
+        match (created, updated) {
+            (None, _) => {
+                set_timestamp("created_at", updated.unwrap_or(now), invalid("created_at"));
+                if updated.is_none() {
+                    set_timestamp("updated_at", now, invalid("updated_at"));
+                }
+            }
+            (Some(created), None) => set_timestamp("updated_at", created, invalid("updated_at")),
+            (Some(created), Some(updated)) if updated < created => {
+                let message = "updated_at is before created_at".to_string();
+                set_timestamp("updated_at", created, message);
+            }
+            _ => {}
+        }
+
+        if let Err(e) = Alias::deserialize(toml::Value::Table(fixed)) {
+            let message = format!("invalid entry: {}", e.message().trim());
+            issue(name, message, drop);
+        }
+    }
+
+    issues
+}
+
+// Apply the fixes of `issues` to the raw aliases.toml and load the result.
+// Fails if an issue that wasn't fixed still keeps the store from loading.
+pub fn repair(content: &str, issues: &[&Issue]) -> Result<AliasStore> {
+    let mut table: toml::Table = toml::from_str(content)?;
+    let mut dropped = Vec::new();
+
+    for fix in issues.iter().filter_map(|i| i.fix.as_ref()) {
+        match fix {
+            Fix::DropEntry(entry) => dropped.push(*entry),
+            Fix::RemoveField { entry: None, key } => {
+                table.remove(key);
+            }
+            Fix::RemoveField {
+                entry: Some(entry),
+                key,
+            } => {
+                if let Some(entry) = entry_mut(&mut table, *entry) {
+                    entry.remove(key);
+                }
+            }
+            Fix::SetTimestamp { entry, key, value } => {
+                if let Some(entry) = entry_mut(&mut table, *entry) {
+                    entry.insert(key.clone(), toml::Value::String(value.clone()));
+                }
+            }
+        }
+    }
+
+    // Drop from the end so earlier indices stay valid
+    dropped.sort_unstable();
+    dropped.dedup();
+    if let Some(toml::Value::Array(entries)) = table.get_mut("aliases") {
+        for entry in dropped.into_iter().rev() {
+            if entry < entries.len() {
+                entries.remove(entry);
+            }
+        }
+    }
+
+    AliasStore::deserialize(toml::Value::Table(table)).map_err(|e| {
+        AlxError::ConfigError(format!(
+            "aliases.toml still can't be loaded: {}",
+            e.message().trim()
+        ))
+    })
+}
+
+fn entry_mut(table: &mut toml::Table, entry: usize) -> Option<&mut toml::Table> {
+    table
+        .get_mut("aliases")?
+        .as_array_mut()?
+        .get_mut(entry)?
+        .as_table_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BROKEN: &str = r#"
+future = 1
+
+[[aliases]]
+name = "gs"
+command = "git status"
+color = "red"
+created_at = "2024-01-01T00:00:00Z"
+updated_at = "2024-01-02T00:00:00Z"
+
+[[aliases]]
+name = "gs"
+command = "git stash"
+created_at = "2024-01-01T00:00:00Z"
+updated_at = "2024-01-01T00:00:00Z"
+
+[[aliases]]
+name = "x"
+command = " "
+created_at = "2024-01-01T00:00:00Z"
+updated_at = "2024-01-01T00:00:00Z"
+
+[[aliases]]
+name = "ll"
+command = "ls -la"
+created_at = "yesterday"
+updated_at = "2024-01-01T00:00:00Z"
+"#;
+
+    #[test]
+    fn test_check_and_repair() {
+        let now = Utc::now();
+        let issues = check(BROKEN, now);
+        let found: Vec<(&str, &str)> = issues
+            .iter()
+            .map(|i| (i.alias.as_str(), i.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("-", "unknown field 'future'"),
+                ("gs", "unknown field 'color' (from a newer alx?)"),
+                ("gs", "duplicate name; an earlier entry has it"),
+                ("x", "empty command"),
+                ("ll", "invalid created_at: \"yesterday\""),
+            ]
+        );
+        assert!(AliasStore::parse(BROKEN).is_err());
+
+        let all: Vec<&Issue> = issues.iter().collect();
+        let store = repair(BROKEN, &all).unwrap();
+        let names: Vec<&str> = store.list().iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["gs", "ll"]);
+        assert_eq!(store.get("gs").unwrap().command, "git status");
+        assert_eq!(
+            store.get("ll").unwrap().created_at,
+            store.get("ll").unwrap().updated_at
+        );
+
+        // Without the timestamp fix the store still can't load
+        assert!(repair(BROKEN, &all[..4]).is_err());
+        assert!(check(&store.to_toml().unwrap(), now).is_empty());
+    }
+}
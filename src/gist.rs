@@ -0,0 +1,97 @@
+use crate::error::{AlxError, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+const API_URL: &str = "https://api.github.com/gists";
+const USER_AGENT: &str = concat!("alx/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Deserialize)]
+struct GistResponse {
+    html_url: String,
+    files: BTreeMap<String, GistFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GistFile {
+    content: Option<String>,
+    truncated: Option<bool>,
+    raw_url: Option<String>,
+}
+
+// Read the token from the environment so it never lands in shell history
+pub fn token_from_env() -> Option<String> {
+    std::env::var("ALX_GITHUB_TOKEN")
+        .or_else(|_| std::env::var("GITHUB_TOKEN"))
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+}
+
+// Create a gist holding a single file and return its URL
+pub fn publish(
+    filename: &str,
+    content: &str,
+    description: &str,
+    public: bool,
+    token: &str,
+) -> Result<String> {
+    let body = json!({
+        "description": description,
+        "public": public,
+        "files": { filename: { "content": content } },
+    });
+
+    let gist: GistResponse = ureq::post(API_URL)
+        .header("Authorization", &format!("Bearer {}", token))
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", USER_AGENT)
+        .send_json(&body)
+        .and_then(|mut r| r.body_mut().read_json())
+        .map_err(|e| AlxError::NetworkError(format!("Failed to create gist: {}", e)))?;
+
+    Ok(gist.html_url)
+}
+
+// Download the first alias file (.json or .toml) from a gist, returning its
+// filename and content
+pub fn fetch(id: &str, token: Option<&str>) -> Result<(String, String)> {
+    let mut request = ureq::get(&format!("{}/{}", API_URL, id))
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", USER_AGENT);
+    if let Some(token) = token {
+        request = request.header("Authorization", &format!("Bearer {}", token));
+    }
+
+    let gist: GistResponse = request
+        .call()
+        .and_then(|mut r| r.body_mut().read_json())
+        .map_err(|e| AlxError::NetworkError(format!("Failed to fetch gist '{}': {}", id, e)))?;
+
+    let (filename, file) = gist
+        .files
+        .into_iter()
+        .find(|(name, _)| name.ends_with(".json") || name.ends_with(".toml"))
+        .ok_or_else(|| {
+            AlxError::ConfigError(format!("Gist '{}' has no .json or .toml file", id))
+        })?;
+
+    // Large files are truncated in the API response and must be read raw
+    let content = match (file.truncated, file.content, file.raw_url) {
+        (Some(false) | None, Some(content), _) => content,
+        (_, _, Some(raw_url)) => ureq::get(&raw_url)
+            .header("User-Agent", USER_AGENT)
+            .call()
+            .and_then(|mut r| r.body_mut().read_to_string())
+            .map_err(|e| {
+                AlxError::NetworkError(format!("Failed to download {}: {}", filename, e))
+            })?,
+        _ => {
+            return Err(AlxError::NetworkError(format!(
+                "Gist file {} has no content",
+                filename
+            )));
+        }
+    };
+
+    Ok((filename, content))
+}
@@ -0,0 +1,474 @@
+use crate::alias::merge::{MergeReport, MergeStrategy, merge_stores};
+use crate::alias::store::AliasStore;
+use crate::error::{AlxError, Result};
+use git2::build::CheckoutBuilder;
+use git2::{
+    Commit, Cred, CredentialType, FetchOptions, IndexAddOption, PushOptions, RemoteCallbacks,
+    Repository, Signature,
+};
+use std::fs;
+use std::path::Path;
+
+const REMOTE: &str = "origin";
+const ALIASES_FILE: &str = "aliases.toml";
+// Generated files and backups are machine-specific
+const GITIGNORE: &str = "shell/\nbackups/\n";
+
+pub enum PullOutcome {
+    UpToDate,
+    FastForward,
+    Merged(MergeReport),
+}
+
+pub struct GitStatus {
+    pub branch: String,
+    pub remote_url: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub changed_files: usize,
+}
+
+pub struct GitSync {
+    repo: Repository,
+}
+
+impl GitSync {
+    pub fn init(dir: &Path) -> Result<Self> {
+        let repo = Repository::init(dir)?;
+
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(&gitignore, GITIGNORE)?;
+        }
+
+        let sync = Self { repo };
+        sync.commit_all("alx: initial commit")?;
+        Ok(sync)
+    }
+
+    pub fn open(dir: &Path) -> Result<Self> {
+        if !Self::is_repo(dir) {
+            return Err(AlxError::ConfigError(format!(
+                "{:?} is not a git repository, run 'alx git init' first",
+                dir
+            )));
+        }
+
+        Ok(Self {
+            repo: Repository::open(dir)?,
+        })
+    }
+
+    pub fn is_repo(dir: &Path) -> bool {
+        dir.join(".git").exists()
+    }
+
+    // The repository `path` is in, looking through its parent directories
+    pub fn discover(path: &Path) -> Option<Self> {
+        Repository::discover(path)
+            .ok()
+            .filter(|repo| !repo.is_bare())
+            .map(|repo| Self { repo })
+    }
+
+    pub fn workdir(&self) -> Option<&Path> {
+        self.repo.workdir()
+    }
+
+    pub fn set_remote(&self, url: &str) -> Result<()> {
+        if self.repo.find_remote(REMOTE).is_ok() {
+            self.repo.remote_set_url(REMOTE, url)?;
+        } else {
+            self.repo.remote(REMOTE, url)?;
+        }
+        Ok(())
+    }
+
+    // Stage everything and commit it, returning false when nothing changed
+    pub fn commit_all(&self, message: &str) -> Result<bool> {
+        let mut index = self.repo.index()?;
+        index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
+        index.update_all(["*"].iter(), None)?;
+        index.write()?;
+
+        let tree = self.repo.find_tree(index.write_tree()?)?;
+        let parent = self.head_commit();
+        if parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+            return Ok(false);
+        }
+
+        let signature = self.signature()?;
+        let parents: Vec<&Commit> = parent.iter().collect();
+        self.repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )?;
+        Ok(true)
+    }
+
+    // Commit the current contents of those `files` the repository already
+    // tracks, leaving everything else in it alone, including changes the user
+    // has staged. Returns false when none of them changed.
+    pub fn commit_tracked(&self, files: &[&Path], message: &str) -> Result<bool> {
+        let (Some(workdir), Some(head)) = (self.repo.workdir(), self.head_commit()) else {
+            return Ok(false);
+        };
+        let workdir = fs::canonicalize(workdir)?;
+
+        let mut tree_index = git2::Index::new()?;
+        tree_index.read_tree(&head.tree()?)?;
+        let mut index = self.repo.index()?;
+        for file in files {
+            let Ok(file) = fs::canonicalize(file) else {
+                continue;
+            };
+            let Ok(relative) = file.strip_prefix(&workdir) else {
+                continue;
+            };
+            let Some(mut entry) = tree_index.get_path(relative, 0) else {
+                continue;
+            };
+            entry.id = self.repo.blob_path(&file)?;
+            entry.file_size = fs::metadata(&file)?.len() as u32;
+            tree_index.add(&entry)?;
+            index.add_path(relative)?;
+        }
+
+        let tree = self.repo.find_tree(tree_index.write_tree_to(&self.repo)?)?;
+        if tree.id() == head.tree_id() {
+            return Ok(false);
+        }
+        let signature = self.signature()?;
+        self.repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &[&head],
+        )?;
+        // So `git status` doesn't show the committed files as changed
+        index.write()?;
+        Ok(true)
+    }
+
+    pub fn push(&self) -> Result<()> {
+        let branch = self.branch()?;
+        let mut remote = self.find_remote()?;
+
+        let mut rejection = None;
+        let config = self.repo.config()?;
+        {
+            let mut callbacks = remote_callbacks(&config);
+            callbacks.push_update_reference(|_, status| {
+                rejection = status.map(|s| s.to_string());
+                Ok(())
+            });
+
+            let mut options = PushOptions::new();
+            options.remote_callbacks(callbacks);
+            let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+            remote.push(&[refspec.as_str()], Some(&mut options))?;
+        }
+
+        if let Some(reason) = rejection {
+            return Err(AlxError::ConfigError(format!(
+                "Push was rejected ({}), run 'alx git pull' first",
+                reason
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn pull(&self, strategy: MergeStrategy) -> Result<PullOutcome> {
+        self.commit_all("alx: save local changes before pull")?;
+
+        let branch = self.branch()?;
+        self.fetch(&branch)?;
+
+        let remote_ref = format!("refs/remotes/{}/{}", REMOTE, branch);
+        let Ok(remote_oid) = self.repo.refname_to_id(&remote_ref) else {
+            return Ok(PullOutcome::UpToDate);
+        };
+        let remote_commit = self.repo.find_commit(remote_oid)?;
+
+        let annotated = self.repo.find_annotated_commit(remote_oid)?;
+        let (analysis, _) = self.repo.merge_analysis(&[&annotated])?;
+
+        if analysis.is_up_to_date() {
+            return Ok(PullOutcome::UpToDate);
+        }
+
+        if analysis.is_fast_forward() || analysis.is_unborn() {
+            let head_ref = format!("refs/heads/{}", branch);
+            self.repo
+                .reference(&head_ref, remote_oid, true, "alx: fast-forward")?;
+            self.repo.set_head(&head_ref)?;
+            self.repo
+                .checkout_head(Some(CheckoutBuilder::new().force()))?;
+            return Ok(PullOutcome::FastForward);
+        }
+
+        let local_commit = self.head_commit().ok_or_else(|| {
+            AlxError::ConfigError("Repository has no commits to merge into".to_string())
+        })?;
+        // Merge the alias stores ourselves so conflicting edits are resolved
+        // per alias instead of leaving conflict markers in the TOML. Machines
+        // that ran `alx git init` separately share no history at all.
+        let base = match self.repo.merge_base(local_commit.id(), remote_oid) {
+            Ok(oid) => self.read_store(&self.repo.find_commit(oid)?)?,
+            Err(_) => AliasStore::new(),
+        };
+        let ours = self.read_store(&local_commit)?;
+        let theirs = self.read_store(&remote_commit)?;
+        let (merged, report) = merge_stores(&base, &ours, &theirs, strategy);
+
+        let content =
+            toml::to_string_pretty(&merged).map_err(|e| AlxError::ConfigError(e.to_string()))?;
+        let blob = self.repo.blob(content.as_bytes())?;
+
+        // Anything besides the store keeps our version when git can't merge it
+        let merged_tree = match self.repo.merge_commits(&local_commit, &remote_commit, None) {
+            Ok(mut index) if !index.has_conflicts() => {
+                self.repo.find_tree(index.write_tree_to(&self.repo)?)?
+            }
+            _ => local_commit.tree()?,
+        };
+        let mut builder = self.repo.treebuilder(Some(&merged_tree))?;
+        builder.insert(ALIASES_FILE, blob, 0o100644)?;
+        let tree = self.repo.find_tree(builder.write()?)?;
+        let signature = self.signature()?;
+        let message = format!("alx: merge {}/{}", REMOTE, branch);
+        self.repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &[&local_commit, &remote_commit],
+        )?;
+        self.repo
+            .checkout_head(Some(CheckoutBuilder::new().force()))?;
+
+        Ok(PullOutcome::Merged(report))
+    }
+
+    pub fn status(&self) -> Result<GitStatus> {
+        let branch = self.branch()?;
+        let remote_url = self
+            .repo
+            .find_remote(REMOTE)
+            .ok()
+            .and_then(|r| r.url().map(|u| u.to_string()));
+
+        let remote_ref = format!("refs/remotes/{}/{}", REMOTE, branch);
+        let (ahead, behind) = match (self.head_commit(), self.repo.refname_to_id(&remote_ref)) {
+            (Some(local), Ok(remote)) => self.repo.graph_ahead_behind(local.id(), remote)?,
+            _ => (0, 0),
+        };
+
+        let changed_files = self.repo.statuses(None)?.len();
+
+        Ok(GitStatus {
+            branch,
+            remote_url,
+            ahead,
+            behind,
+            changed_files,
+        })
+    }
+
+    fn fetch(&self, branch: &str) -> Result<()> {
+        let mut remote = self.find_remote()?;
+        let config = self.repo.config()?;
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(remote_callbacks(&config));
+        remote.fetch(&[branch], Some(&mut options), None)?;
+        Ok(())
+    }
+
+    fn find_remote(&self) -> Result<git2::Remote<'_>> {
+        self.repo.find_remote(REMOTE).map_err(|_| {
+            AlxError::ConfigError("No remote configured, run 'alx git remote <url>'".to_string())
+        })
+    }
+
+    fn read_store(&self, commit: &Commit) -> Result<AliasStore> {
+        let entry = match commit.tree()?.get_path(Path::new(ALIASES_FILE)) {
+            Ok(entry) => entry,
+            Err(_) => return Ok(AliasStore::new()),
+        };
+        let blob = entry.to_object(&self.repo)?.peel_to_blob()?;
+        let content = String::from_utf8_lossy(blob.content());
+        Ok(toml::from_str(&content)?)
+    }
+
+    fn head_commit(&self) -> Option<Commit<'_>> {
+        self.repo.head().ok().and_then(|h| h.peel_to_commit().ok())
+    }
+
+    fn branch(&self) -> Result<String> {
+        let head = self.repo.find_reference("HEAD")?;
+        let target = head
+            .symbolic_target()
+            .ok_or_else(|| AlxError::ConfigError("HEAD is detached".to_string()))?;
+        Ok(target.trim_start_matches("refs/heads/").to_string())
+    }
+
+    fn signature(&self) -> Result<Signature<'static>> {
+        Ok(self
+            .repo
+            .signature()
+            .or_else(|_| Signature::now("alx", "alx@localhost"))?)
+    }
+}
+
+fn remote_callbacks(config: &git2::Config) -> RemoteCallbacks<'_> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut attempts = 0;
+    callbacks.credentials(move |url, username, allowed| {
+        // libgit2 keeps asking while credentials are rejected
+        attempts += 1;
+        if attempts > 3 {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            Cred::ssh_key_from_agent(username.unwrap_or("git"))
+        } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            Cred::credential_helper(config, url, username)
+        } else {
+            Cred::default()
+        }
+    });
+    callbacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use tempfile::TempDir;
+
+    fn write_store(dir: &Path, aliases: &[Alias]) {
+        let store = AliasStore::from_aliases(aliases.to_vec());
+        store.save(&dir.join(ALIASES_FILE)).unwrap();
+    }
+
+    fn clone_pair() -> (TempDir, GitSync, GitSync) {
+        let temp = TempDir::new().unwrap();
+        let remote = temp.path().join("remote.git");
+        Repository::init_bare(&remote).unwrap();
+        let url = remote.to_str().unwrap();
+
+        let first_dir = temp.path().join("first");
+        fs::create_dir_all(&first_dir).unwrap();
+        write_store(
+            &first_dir,
+            &[Alias::new("gs".to_string(), "git status".to_string())],
+        );
+        let first = GitSync::init(&first_dir).unwrap();
+        first.set_remote(url).unwrap();
+        first.push().unwrap();
+
+        let second_dir = temp.path().join("second");
+        fs::create_dir_all(&second_dir).unwrap();
+        let second = GitSync::init(&second_dir).unwrap();
+        second.set_remote(url).unwrap();
+
+        (temp, first, second)
+    }
+
+    fn load(sync: &GitSync) -> AliasStore {
+        AliasStore::load(&sync.repo.workdir().unwrap().join(ALIASES_FILE)).unwrap()
+    }
+
+    #[test]
+    fn test_commit_all_skips_unchanged_tree() {
+        let temp = TempDir::new().unwrap();
+        write_store(temp.path(), &[]);
+        let sync = GitSync::init(temp.path()).unwrap();
+
+        assert!(!sync.commit_all("nothing").unwrap());
+
+        write_store(
+            temp.path(),
+            &[Alias::new("ll".to_string(), "ls -la".to_string())],
+        );
+        assert!(sync.commit_all("add ll").unwrap());
+        assert_eq!(sync.status().unwrap().changed_files, 0);
+    }
+
+    #[test]
+    fn test_commit_tracked() {
+        let temp = TempDir::new().unwrap();
+        let alx_dir = temp.path().join("alx");
+        fs::create_dir_all(&alx_dir).unwrap();
+        write_store(&alx_dir, &[]);
+        fs::write(temp.path().join("notes.txt"), "draft").unwrap();
+        let dotfiles = GitSync::init(temp.path()).unwrap();
+
+        let aliases = alx_dir.join(ALIASES_FILE);
+        let untracked = alx_dir.join("config.toml");
+        fs::write(&untracked, "[settings]\n").unwrap();
+        fs::write(temp.path().join("notes.txt"), "edited").unwrap();
+        write_store(
+            &alx_dir,
+            &[Alias::new("ll".to_string(), "ls -la".to_string())],
+        );
+
+        let sync = GitSync::discover(&alx_dir).unwrap();
+        assert!(
+            sync.commit_tracked(&[&aliases, &untracked], "add ll")
+                .unwrap()
+        );
+        assert!(!sync.commit_tracked(&[&aliases], "again").unwrap());
+
+        let head = dotfiles.head_commit().unwrap();
+        assert_eq!(head.message(), Some("add ll"));
+        let tree = head.tree().unwrap();
+        assert!(tree.get_path(Path::new("alx/config.toml")).is_err());
+        let notes = tree.get_path(Path::new("notes.txt")).unwrap();
+        let notes = notes
+            .to_object(&dotfiles.repo)
+            .unwrap()
+            .peel_to_blob()
+            .unwrap();
+        assert_eq!(notes.content(), b"draft");
+        // The edited notes and the untracked config are all that's left
+        assert_eq!(dotfiles.status().unwrap().changed_files, 2);
+    }
+
+    #[test]
+    fn test_pull_merges_diverged_stores() {
+        let (_temp, first, second) = clone_pair();
+
+        // The second machine has its own unrelated history
+        let second_dir = second.repo.workdir().unwrap().to_path_buf();
+        write_store(
+            &second_dir,
+            &[Alias::new("ll".to_string(), "ls -la".to_string())],
+        );
+        second.commit_all("add ll").unwrap();
+
+        assert!(matches!(
+            second.pull(MergeStrategy::Newest).unwrap(),
+            PullOutcome::Merged(_)
+        ));
+        let mut names: Vec<String> = load(&second).aliases.into_iter().map(|a| a.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["gs", "ll"]);
+
+        second.push().unwrap();
+        assert!(matches!(
+            first.pull(MergeStrategy::Newest).unwrap(),
+            PullOutcome::FastForward
+        ));
+        assert_eq!(load(&first).aliases.len(), 2);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::alias::Alias;
+use crate::error::{AlxError, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// A change to a single alias that can have a hook of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasEvent {
+    Add,
+    Remove,
+    Edit,
+}
+
+impl AliasEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AliasEvent::Add => "add",
+            AliasEvent::Remove => "remove",
+            AliasEvent::Edit => "edit",
+        }
+    }
+
+    pub fn hook_name(&self) -> &'static str {
+        match self {
+            AliasEvent::Add => "post_add",
+            AliasEvent::Remove => "post_remove",
+            AliasEvent::Edit => "post_edit",
+        }
+    }
+}
+
+// Run a hook command through the shell. Its output is captured and echoed
+// indented; a non-zero exit becomes an error carrying what it printed.
+pub fn run(name: &str, command: &str, aliases_file: &Path) -> Result<()> {
+    let mut process = shell_command(command);
+    process
+        .env("ALX_HOOK", name)
+        .env("ALX_SHELL_ALIASES_FILE", aliases_file);
+    execute(name, command, process, None)
+}
+
+// Run the hook for `event` with a JSON document on stdin holding the event,
+// the alias as it is now (as it was, for a removal) and, for an edit, the
+// alias before the change
+pub fn run_alias_event(
+    event: AliasEvent,
+    command: &str,
+    alias: &Alias,
+    previous: Option<&Alias>,
+) -> Result<()> {
+    let mut document = serde_json::json!({ "event": event.as_str(), "alias": alias });
+    if let Some(previous) = previous {
+        document["previous"] = serde_json::to_value(previous)?;
+    }
+
+    let mut process = shell_command(command);
+    process
+        .env("ALX_HOOK", event.hook_name())
+        .env("ALX_EVENT", event.as_str())
+        .env("ALX_ALIAS", &alias.name);
+    // One line per event, so `cat >> log.jsonl` makes a JSON Lines file
+    let input = format!("{}\n", document);
+    execute(event.hook_name(), command, process, Some(input))
+}
+
+fn execute(name: &str, command: &str, mut process: Command, input: Option<String>) -> Result<()> {
+    let spawn_error = |e: std::io::Error| {
+        AlxError::HookFailed(format!("{}: could not run '{}': {}", name, command, e))
+    };
+    let mut child = process
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // A hook that doesn't read its input closes the pipe early; that's fine
+        let _ = stdin.write_all(input.as_bytes());
+    }
+    let output = child.wait_with_output().map_err(spawn_error)?;
+
+    let mut captured = String::from_utf8_lossy(&output.stdout).into_owned();
+    captured.push_str(&String::from_utf8_lossy(&output.stderr));
+    let captured = captured.trim_end();
+
+    if !output.status.success() {
+        let mut message = format!("{} ('{}') exited with {}", name, command, output.status);
+        if !captured.is_empty() {
+            message.push_str(":\n");
+            message.push_str(&indent(captured));
+        }
+        return Err(AlxError::HookFailed(message));
+    }
+
+    if !captured.is_empty() {
+        eprintln!("{}", indent(captured));
+    }
+    Ok(())
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.args(["/C", command]);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.args(["-c", command]);
+        process
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("  {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let file = Path::new("/tmp/aliases.sh");
+        assert!(
+            run(
+                "post_sync",
+                "test \"$ALX_SHELL_ALIASES_FILE\" = /tmp/aliases.sh",
+                file
+            )
+            .is_ok()
+        );
+
+        let err = run("pre_sync", "echo broken >&2; exit 3", file).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("pre_sync"));
+        assert!(message.contains("  broken"));
+    }
+
+    #[test]
+    fn test_run_alias_event() {
+        let old = Alias::new("gs".to_string(), "git status".to_string());
+        let mut new = old.clone();
+        new.command = "git status -sb".to_string();
+
+        let check = r#"input=$(cat); test "$ALX_EVENT/$ALX_ALIAS" = edit/gs && \
+            case "$input" in *'"command":"git status -sb"'*'"previous":'*) ;; *) exit 1 ;; esac"#;
+        assert!(run_alias_event(AliasEvent::Edit, check, &new, Some(&old)).is_ok());
+
+        let err = run_alias_event(AliasEvent::Remove, "exit 1", &old, None).unwrap_err();
+        assert!(err.to_string().contains("post_remove"));
+        // Hooks don't have to read their input
+        assert!(run_alias_event(AliasEvent::Add, "true", &new, None).is_ok());
+    }
+}
@@ -0,0 +1,588 @@
+use crate::alias::Alias;
+use crate::error::{AlxError, Result};
+use serde::Deserialize;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{BufRead, Read};
+
+// Convert another tool's alias format into aliases
+pub fn parse(tool: &str, content: &str) -> Result<Vec<Alias>> {
+    match tool {
+        "aka" => parse_aka(content),
+        "pet" => parse_pet(content),
+        "shell" => Ok(parse_shell(content)),
+        "doskey" => Ok(parse_doskey(content)),
+        _ => Err(AlxError::ConfigError(format!(
+            "Unknown import source: {} (expected aka, pet, shell or doskey)",
+            tool
+        ))),
+    }
+}
+
+// Group renames applied to incoming aliases, from `--map-group old=new` and
+// `--into-group name`
+#[derive(Debug, Default)]
+pub struct GroupMap {
+    // An empty new name drops the group
+    renames: Vec<(String, Option<String>)>,
+    into: Option<String>,
+}
+
+impl GroupMap {
+    pub fn parse(specs: &[String], into: Option<String>) -> Result<Self> {
+        let renames = specs
+            .iter()
+            .map(|spec| match spec.split_once('=') {
+                Some((old, new)) if !old.trim().is_empty() => {
+                    let new = Some(new.trim()).filter(|n| !n.is_empty());
+                    Ok((old.trim().to_string(), new.map(str::to_string)))
+                }
+                _ => Err(AlxError::ConfigError(format!(
+                    "Invalid group mapping '{}': expected old=new",
+                    spec
+                ))),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { renames, into })
+    }
+
+    // Rewrite the groups of `aliases`, returning the mapped groups no alias
+    // was in so typos can be reported
+    pub fn apply(&self, aliases: &mut [Alias]) -> Vec<&str> {
+        if let Some(into) = &self.into {
+            for alias in aliases.iter_mut() {
+                alias.group = Some(into.clone());
+            }
+            return Vec::new();
+        }
+
+        let mut unused = Vec::new();
+        for (old, new) in &self.renames {
+            let mut used = false;
+            for alias in aliases
+                .iter_mut()
+                .filter(|a| a.group.as_deref() == Some(old))
+            {
+                alias.group = new.clone();
+                used = true;
+            }
+            if !used {
+                unused.push(old.as_str());
+            }
+        }
+        unused
+    }
+}
+
+// Read an exported JSON store as a stream, so only one entry is held as
+// JSON at a time. `progress` is called with the number of aliases read so
+// far. A malformed entry stops the import with its name and position.
+pub fn read_json<R: Read>(reader: R, mut progress: impl FnMut(usize)) -> Result<Vec<Alias>> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let aliases = StoreVisitor {
+        progress: &mut progress,
+    }
+    .deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(aliases)
+}
+
+// Read a JSON Lines export, one alias object per line. Blank lines are
+// skipped; a malformed line stops the import with its line number.
+pub fn read_jsonl<R: BufRead>(reader: R, mut progress: impl FnMut(usize)) -> Result<Vec<Alias>> {
+    let mut aliases = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let alias: Alias = serde_json::from_str(&line).map_err(|e| {
+            AlxError::ConfigError(format!("invalid alias on line {}: {}", index + 1, e))
+        })?;
+        aliases.push(alias);
+        progress(aliases.len());
+    }
+    Ok(aliases)
+}
+
+struct StoreVisitor<'a> {
+    progress: &'a mut dyn FnMut(usize),
+}
+
+impl<'de> DeserializeSeed<'de> for StoreVisitor<'_> {
+    type Value = Vec<Alias>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for StoreVisitor<'_> {
+    type Value = Vec<Alias>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an alias store with an \"aliases\" list")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<Self::Value, A::Error> {
+        let mut aliases = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "aliases" {
+                aliases = map.next_value_seed(AliasesVisitor {
+                    progress: &mut *self.progress,
+                })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(aliases)
+    }
+}
+
+struct AliasesVisitor<'a> {
+    progress: &'a mut dyn FnMut(usize),
+}
+
+impl<'de> DeserializeSeed<'de> for AliasesVisitor<'_> {
+    type Value = Vec<Alias>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for AliasesVisitor<'_> {
+    type Value = Vec<Alias>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of aliases")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> std::result::Result<Self::Value, A::Error> {
+        let mut aliases = Vec::new();
+        while let Some(entry) = seq.next_element::<serde_json::Value>()? {
+            let position = aliases.len() + 1;
+            let name = entry
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(str::to_string);
+            let alias: Alias = serde_json::from_value(entry).map_err(|e| match &name {
+                Some(name) => de::Error::custom(format!(
+                    "invalid alias '{}' (entry {}): {}",
+                    name, position, e
+                )),
+                None => de::Error::custom(format!("invalid alias at entry {}: {}", position, e)),
+            })?;
+            aliases.push(alias);
+            (self.progress)(aliases.len());
+        }
+        Ok(aliases)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AkaEntry {
+    Command(String),
+    Detailed {
+        command: String,
+        description: Option<String>,
+    },
+}
+
+// aka keeps a YAML mapping of name to either a command or a detailed entry
+fn parse_aka(content: &str) -> Result<Vec<Alias>> {
+    let entries: BTreeMap<String, AkaEntry> = serde_yaml::from_str(content)
+        .map_err(|e| AlxError::ConfigError(format!("Invalid aka YAML: {}", e)))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|(name, entry)| match entry {
+            AkaEntry::Command(command) => Alias::new(name, command),
+            AkaEntry::Detailed {
+                command,
+                description: Some(desc),
+            } => Alias::new(name, command).with_description(desc),
+            AkaEntry::Detailed { command, .. } => Alias::new(name, command),
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct PetSnippets {
+    #[serde(default)]
+    snippets: Vec<PetSnippet>,
+}
+
+#[derive(Deserialize)]
+struct PetSnippet {
+    command: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    tag: Vec<String>,
+}
+
+// pet snippets have no names, so one is derived from the description
+fn parse_pet(content: &str) -> Result<Vec<Alias>> {
+    let pet: PetSnippets = toml::from_str(content)?;
+
+    Ok(pet
+        .snippets
+        .into_iter()
+        .enumerate()
+        .map(|(i, snippet)| {
+            let name =
+                slugify(&snippet.description).unwrap_or_else(|| format!("snippet-{}", i + 1));
+            let mut alias = Alias::new(name, snippet.command).with_tags(snippet.tag);
+            if !snippet.description.is_empty() {
+                alias = alias.with_description(snippet.description);
+            }
+            alias
+        })
+        .collect())
+}
+
+// Output of `alias -p`, one `alias name='command'` per line
+fn parse_shell(content: &str) -> Vec<Alias> {
+    content
+        .lines()
+        .filter_map(parse_alias_output)
+        .map(|(name, command)| Alias::new(name, command))
+        .collect()
+}
+
+// One line of a shell listing its live aliases: `alias -p` in bash, `alias`
+// or `alias -L` in zsh (`name=value`, `alias name=value`) and `alias` in fish
+// (`alias name value`). Values are unquoted the way the shell printed them.
+fn parse_alias_output(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let prefixed = line.starts_with("alias ");
+    let def = line.strip_prefix("alias ").unwrap_or(line).trim_start();
+    let def = def.strip_prefix("-- ").unwrap_or(def);
+
+    let (name, value, fish) = match def.find(['=', ' ']) {
+        Some(i) if def[i..].starts_with('=') => (&def[..i], &def[i + 1..], false),
+        Some(i) if prefixed => (&def[..i], def[i + 1..].trim_start(), true),
+        _ => return None,
+    };
+    let name = unquote(name, fish)?;
+    if name.is_empty() {
+        return None;
+    }
+    Some((name, unquote(value, fish)?))
+}
+
+// Undo shell quoting: '...', "...", backslashes and bash's $'...'. Fish also
+// allows \' and \\ inside single quotes. None when a quote is left open.
+fn unquote(word: &str, fish: bool) -> Option<String> {
+    let mut out = String::with_capacity(word.len());
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => loop {
+                match chars.next()? {
+                    '\'' => break,
+                    '\\' if fish && matches!(chars.peek(), Some('\'' | '\\')) => {
+                        out.push(chars.next()?)
+                    }
+                    c => out.push(c),
+                }
+            },
+            '"' => loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' if matches!(chars.peek(), Some('"' | '\\' | '$' | '`')) => {
+                        out.push(chars.next()?)
+                    }
+                    c => out.push(c),
+                }
+            },
+            '$' if !fish && chars.peek() == Some(&'\'') => {
+                chars.next();
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        '\\' => out.push(match chars.next()? {
+                            'n' => '\n',
+                            't' => '\t',
+                            'e' | 'E' => '\x1b',
+                            c => c,
+                        }),
+                        c => out.push(c),
+                    }
+                }
+            }
+            '\\' => out.push(chars.next()?),
+            c => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+// A doskey macrofile (`doskey /macrofile=...`), one `name=text` per line.
+// `[exe]` headings from `doskey /macros:all` and `;` comments are skipped.
+fn parse_doskey(content: &str) -> Vec<Alias> {
+    content
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .filter(|line| !line.starts_with(';') && !line.starts_with('['))
+        .filter_map(|line| line.split_once('='))
+        .filter(|(name, text)| !name.trim().is_empty() && !text.trim().is_empty())
+        .map(|(name, text)| Alias::new(name.trim().to_string(), doskey_command(name.trim(), text)))
+        .collect()
+}
+
+// Translate a doskey macro's text into a shell command. A trailing `$*` is
+// what an alias does anyway; other parameters need them passed in, so the
+// macro becomes `sh -c '...' name` with `$1`..`$9` and `$*` as "$1" and "$@".
+fn doskey_command(name: &str, text: &str) -> String {
+    let text = text.trim();
+    let text = text.strip_suffix("$*").map_or(text, str::trim_end);
+
+    let mut command = String::with_capacity(text.len());
+    let mut parameters = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            command.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(d @ '1'..='9') => {
+                command.push_str(&format!("\"${}\"", d));
+                parameters = true;
+            }
+            Some('*') => {
+                command.push_str("\"$@\"");
+                parameters = true;
+            }
+            Some('t' | 'T') => command.push(';'),
+            Some('b' | 'B') => command.push('|'),
+            Some('g' | 'G') => command.push('>'),
+            Some('l' | 'L') => command.push('<'),
+            Some('$') => command.push('$'),
+            Some(other) => {
+                command.push('$');
+                command.push(other);
+            }
+            None => command.push('$'),
+        }
+    }
+
+    if parameters {
+        format!("sh -c {} {}", crate::shell::quote(&command), name)
+    } else {
+        command
+    }
+}
+
+fn slugify(text: &str) -> Option<String> {
+    let slug = text
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    // Alias names must start with a letter
+    let slug = slug.trim_start_matches(|c: char| !c.is_ascii_alphabetic());
+    if slug.is_empty() {
+        None
+    } else {
+        Some(slug.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aka() {
+        let content = r#"
+gs: git status
+ll:
+  command: ls -la
+  description: List all files
+"#;
+        let aliases = parse("aka", content).unwrap();
+
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[0].name, "gs");
+        assert_eq!(aliases[0].command, "git status");
+        assert_eq!(aliases[1].description, Some("List all files".to_string()));
+    }
+
+    #[test]
+    fn test_parse_pet() {
+        let content = r#"
+[[snippets]]
+  description = "Ping Google DNS"
+  command = "ping 8.8.8.8"
+  tag = ["network"]
+  output = ""
+
+[[snippets]]
+  description = ""
+  command = "uptime"
+"#;
+        let aliases = parse("pet", content).unwrap();
+
+        assert_eq!(aliases[0].name, "ping-google-dns");
+        assert!(aliases[0].has_tag("network"));
+        assert_eq!(aliases[1].name, "snippet-2");
+        assert!(aliases[1].description.is_none());
+    }
+
+    #[test]
+    fn test_parse_shell() {
+        let content = "alias gs='git status'\nalias ll='ls -la'\n";
+        let aliases = parse("shell", content).unwrap();
+
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[1].command, "ls -la");
+    }
+
+    #[test]
+    fn test_parse_doskey() {
+        let content = "\u{feff};= Macros for cmd.exe\n[cmd.exe]\nll=dir /w $*\n\
+                       gco=git checkout $1 $T git log -1\nlog=git log $G$G log.txt\nbad\n";
+        let commands: Vec<(String, String)> = parse("doskey", content)
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.name, a.command))
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                ("ll".to_string(), "dir /w".to_string()),
+                (
+                    "gco".to_string(),
+                    r#"sh -c 'git checkout "$1" ; git log -1' gco"#.to_string()
+                ),
+                ("log".to_string(), "git log >> log.txt".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_alias_output() {
+        let parsed = |line: &str| parse_alias_output(line).map(|(n, c)| format!("{}|{}", n, c));
+
+        // bash alias -p
+        assert_eq!(
+            parsed(r#"alias say='echo '\''hi'\'' "x"'"#).as_deref(),
+            Some(r#"say|echo 'hi' "x""#)
+        );
+        assert_eq!(
+            parsed("alias nl=$'printf \\'a\\nb\\''").as_deref(),
+            Some("nl|printf 'a\nb'")
+        );
+        // zsh alias and alias -L
+        assert_eq!(parsed("ll='ls -la'").as_deref(), Some("ll|ls -la"));
+        assert_eq!(parsed("g=git").as_deref(), Some("g|git"));
+        assert_eq!(parsed("alias -- -='cd -'").as_deref(), Some("-|cd -"));
+        // fish alias
+        assert_eq!(parsed("alias ll 'ls -la'").as_deref(), Some("ll|ls -la"));
+        assert_eq!(
+            parsed(r"alias it 'echo it\'s'").as_deref(),
+            Some("it|echo it's")
+        );
+        assert_eq!(parsed(r"alias gl git\ log").as_deref(), Some("gl|git log"));
+
+        assert_eq!(parsed("alias broken='echo"), None);
+        assert_eq!(parsed("# comment"), None);
+        assert_eq!(parsed("echo hi"), None);
+    }
+
+    #[test]
+    fn test_read_json() {
+        let content = r#"{"packs": [], "aliases": [
+            {"name": "gs", "command": "git status", "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z"},
+            {"name": "ll", "command": "ls -la", "group": "files", "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z"}
+        ]}"#;
+        let mut read = 0;
+        let aliases = read_json(content.as_bytes(), |n| read = n).unwrap();
+
+        assert_eq!(read, 2);
+        assert_eq!(aliases[1].name, "ll");
+        assert_eq!(aliases[1].group.as_deref(), Some("files"));
+
+        let err = read_json(r#"{"aliases": [{"name": "gs"}]}"#.as_bytes(), |_| {}).unwrap_err();
+        assert!(err.to_string().contains("invalid alias 'gs' (entry 1)"));
+        assert!(read_json(r#"{"aliases": []} trailing"#.as_bytes(), |_| {}).is_err());
+    }
+
+    #[test]
+    fn test_read_jsonl() {
+        let content = concat!(
+            r#"{"name": "gs", "command": "git status", "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z"}"#,
+            "\n\n",
+            r#"{"name": "ll", "command": "ls -la", "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z"}"#,
+            "\n",
+        );
+        let mut read = 0;
+        let aliases = read_jsonl(content.as_bytes(), |n| read = n).unwrap();
+        assert_eq!(read, 2);
+        assert_eq!(aliases[1].name, "ll");
+
+        let err = read_jsonl("\n{\"name\": \"gs\"}\n".as_bytes(), |_| {}).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_group_map() {
+        let alias = |name: &str, group: Option<&str>| Alias {
+            group: group.map(str::to_string),
+            ..Alias::new(name.to_string(), "true".to_string())
+        };
+        let mut aliases = vec![
+            alias("gs", Some("vcs")),
+            alias("ll", Some("files")),
+            alias("x", None),
+        ];
+
+        let specs = [
+            "vcs=git".to_string(),
+            "files=".to_string(),
+            "typo=x".to_string(),
+        ];
+        let map = GroupMap::parse(&specs, None).unwrap();
+        assert_eq!(map.apply(&mut aliases), vec!["typo"]);
+        assert_eq!(aliases[0].group.as_deref(), Some("git"));
+        assert_eq!(aliases[1].group, None);
+        assert_eq!(aliases[2].group, None);
+
+        let map = GroupMap::parse(&[], Some("shared".to_string())).unwrap();
+        assert!(map.apply(&mut aliases).is_empty());
+        assert!(aliases.iter().all(|a| a.group.as_deref() == Some("shared")));
+
+        assert!(GroupMap::parse(&["git".to_string()], None).is_err());
+        assert!(GroupMap::parse(&["=git".to_string()], None).is_err());
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Show Git log!"), Some("show-git-log".to_string()));
+        assert_eq!(slugify("2fa code"), Some("fa-code".to_string()));
+        assert_eq!(slugify("!!!"), None);
+    }
+}
@@ -0,0 +1,45 @@
+//! Library behind the `alx` alias manager.
+//!
+//! The CLI is a thin layer over these modules, so other programs such as
+//! editor plugins or GUI frontends can read and change the same alias store
+//! and regenerate shell files the same way `alx` does.
+//!
+//! ```
+//! use alx::alias::Alias;
+//! use alx::alias::store::AliasStore;
+//! use alx::shell::ShellType;
+//!
+//! let mut store = AliasStore::new();
+//! store.add(Alias::new("gs".to_string(), "git status".to_string())).unwrap();
+//!
+//! let script = alx::sync::render_shell_aliases(&store, ShellType::Bash).unwrap();
+//! assert!(script.contains("alias gs='git status'"));
+//! ```
+
+pub mod alias;
+pub mod backup;
+pub mod clipboard;
+pub mod config;
+pub mod editor;
+pub mod error;
+pub mod export;
+pub mod fsck;
+pub mod gist;
+pub mod git;
+pub mod hooks;
+pub mod importer;
+pub mod lint;
+pub mod logging;
+pub mod pack;
+pub mod plugin;
+pub mod preset;
+pub mod report;
+pub mod secrets;
+pub mod shadow;
+pub mod shell;
+pub mod snapshot;
+pub mod suggest;
+pub mod sync;
+pub mod update;
+pub mod usage;
+pub mod watch;
@@ -0,0 +1,260 @@
+use crate::alias::store::AliasStore;
+use crate::alias::validator::AliasValidator;
+use crate::shadow;
+use crate::shell::ShellType;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+
+// Disabled aliases untouched for this long are reported as forgotten
+const STALE_DISABLED_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+}
+
+// What --fix does for an issue that can be fixed without asking
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fix {
+    TrimCommand,
+    NormalizeGroups,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub alias: String,
+    pub check: &'static str,
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+// Run every check over the store, most severe issues first
+pub fn lint(store: &AliasStore, shell_type: ShellType, now: DateTime<Utc>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut issue = |severity, alias: &str, check, message: String, fix| {
+        issues.push(Issue {
+            severity,
+            alias: alias.to_string(),
+            check,
+            message,
+            fix,
+        })
+    };
+
+    // With ignore_case only one of them can ever be looked up
+    if store.ignore_case {
+        for (first, second) in store.case_conflicts() {
+            let message = format!("same name as '{}' ignoring case", first);
+            issue(Severity::Error, second, "name-case", message, None);
+        }
+    }
+
+    let mut commands: HashMap<&str, &str> = HashMap::new();
+    let mut groups: HashMap<String, &str> = HashMap::new();
+    let no_aliases = HashMap::new();
+
+    for alias in store.list() {
+        let name = alias.name.as_str();
+        let command = alias.command.trim();
+
+        if let Some(quote) = unbalanced_quote(command) {
+            let message = format!("unbalanced {} quote", quote);
+            issue(Severity::Error, name, "quoting", message, None);
+        }
+
+        if command != alias.command {
+            let message = "command has leading or trailing whitespace".to_string();
+            issue(
+                Severity::Warning,
+                name,
+                "whitespace",
+                message,
+                Some(Fix::TrimCommand),
+            );
+        }
+
+        if let Some(path) = AliasValidator::find_loop(store, name, command) {
+            let message = format!("runs itself ({})", path.join(" -> "));
+            issue(Severity::Warning, name, "recursion", message, None);
+        }
+
+        if let Some(target) = first_word(command).filter(|w| is_dead(store, w, shell_type)) {
+            let message = format!("'{}' is not an alias, builtin or program", target);
+            issue(Severity::Warning, name, "dead-target", message, None);
+        }
+
+        match commands.get(command) {
+            Some(first) => {
+                let message = format!("same command as '{}'", first);
+                issue(Severity::Warning, name, "duplicate", message, None);
+            }
+            None => {
+                commands.insert(command, name);
+            }
+        }
+
+        if let Some(group) = &alias.group {
+            match groups.get(&group.to_lowercase()) {
+                Some(other) if *other != group => {
+                    let message = format!("group '{}' also exists as '{}'", group, other);
+                    let fix = Some(Fix::NormalizeGroups);
+                    issue(Severity::Warning, name, "group-case", message, fix);
+                }
+                Some(_) => {}
+                None => {
+                    groups.insert(group.to_lowercase(), group);
+                }
+            }
+        }
+
+        if let Some(before) = shadow::shadowed(name, shell_type, &no_aliases) {
+            issue(
+                Severity::Note,
+                name,
+                "shadowing",
+                format!("hides {}", before),
+                None,
+            );
+        }
+
+        if alias.description.is_none() {
+            let message = "no description".to_string();
+            issue(Severity::Note, name, "description", message, None);
+        }
+
+        if !alias.enabled && now - alias.updated_at > Duration::days(STALE_DISABLED_DAYS) {
+            let message = format!(
+                "disabled and unchanged since {}",
+                alias.updated_at.format("%Y-%m-%d")
+            );
+            issue(Severity::Note, name, "stale-disabled", message, None);
+        }
+    }
+
+    issues.sort_by_key(|i| i.severity);
+    issues
+}
+
+// Apply the fixes of `issues` to the store, returning how many issues were
+// fixed
+pub fn fix(store: &mut AliasStore, issues: &[Issue]) -> usize {
+    let mut fixed = 0;
+    for issue in issues {
+        match issue.fix {
+            Some(Fix::TrimCommand) => {
+                if let Some(alias) = store.get_mut(&issue.alias) {
+                    alias.update_command(alias.command.trim().to_string());
+                    fixed += 1;
+                }
+            }
+            Some(Fix::NormalizeGroups) => fixed += 1,
+            None => {}
+        }
+    }
+    if issues.iter().any(|i| i.fix == Some(Fix::NormalizeGroups)) {
+        store.normalize_groups(false);
+    }
+    fixed
+}
+
+// The quote character left open at the end of `command`, if any
+fn unbalanced_quote(command: &str) -> Option<char> {
+    let mut open: Option<char> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (open, c) {
+            (Some('\''), '\'') => open = None,
+            (Some('\''), _) => {}
+            (_, '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => open = None,
+            (None, '\'' | '"' | '`') => open = Some(c),
+            _ => {}
+        }
+    }
+    open
+}
+
+// The program the command runs, skipping leading variable assignments
+fn first_word(command: &str) -> Option<&str> {
+    command
+        .split_whitespace()
+        .find(|w| !w.contains('=') || w.starts_with('='))
+}
+
+fn is_dead(store: &AliasStore, word: &str, shell_type: ShellType) -> bool {
+    // Anything expanded or quoted can't be checked statically
+    if word.contains(['$', '`', '(', '{', '\'', '"', '~', '\\']) {
+        return false;
+    }
+    if word.contains('/') {
+        return !Path::new(word).exists();
+    }
+    store.get(word).is_none() && shadow::shadowed(word, shell_type, &HashMap::new()).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+
+    #[test]
+    fn test_unbalanced_quote() {
+        assert_eq!(unbalanced_quote("echo 'it''s'"), None);
+        assert_eq!(unbalanced_quote(r#"echo "a \" b""#), None);
+        assert_eq!(unbalanced_quote("echo 'don\\'t'"), Some('\''));
+        assert_eq!(unbalanced_quote("echo \"open"), Some('"'));
+    }
+
+    #[test]
+    fn test_lint() {
+        let mut disabled = Alias::new("xold".to_string(), "sh -c true".to_string())
+            .with_description("Old".to_string());
+        disabled.enabled = false;
+        disabled.updated_at = Utc::now() - Duration::days(200);
+
+        let described = |name: &str, command: &str| {
+            Alias::new(name.to_string(), command.to_string()).with_description("x".to_string())
+        };
+        let mut store = AliasStore::from_aliases(vec![
+            described("xq", "echo 'oops").with_group("Git".to_string()),
+            described("xw", "sh -c true ").with_group("git".to_string()),
+            described("xd", "alx-no-such-program --flag"),
+            described("xe", "FOO=1 sh -c 'echo $FOO'"),
+            disabled,
+        ]);
+
+        let issues = lint(&store, ShellType::Bash, Utc::now());
+        let found: Vec<(&str, &str)> = issues.iter().map(|i| (i.alias.as_str(), i.check)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("xq", "quoting"),
+                ("xw", "whitespace"),
+                ("xw", "group-case"),
+                ("xd", "dead-target"),
+                ("xold", "duplicate"),
+                ("xold", "stale-disabled"),
+            ]
+        );
+
+        assert_eq!(fix(&mut store, &issues), 2);
+        assert_eq!(store.get("xw").unwrap().command, "sh -c true");
+        assert_eq!(store.groups(), vec!["Git"]);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::error::{AlxError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+const PREFIX: &str = "alx";
+const SUFFIX: &str = "log";
+// One file per day, so this is about a week of history
+const KEEP_FILES: usize = 7;
+
+// Send tracing events to a daily log file in `dir`
+pub fn init(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(PREFIX)
+        .filename_suffix(SUFFIX)
+        .max_log_files(KEEP_FILES)
+        .build(dir)
+        .map_err(|e| AlxError::ConfigError(format!("Failed to open log in {:?}: {}", dir, e)))?;
+
+    tracing_subscriber::fmt()
+        .with_writer(appender)
+        .with_ansi(false)
+        .with_target(false)
+        .try_init()
+        .map_err(|e| AlxError::ConfigError(format!("Failed to start logging: {}", e)))
+}
+
+// Log files in `dir`, oldest first. Their names end in the date, so sorting
+// by name sorts by age.
+pub fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.starts_with(PREFIX) && name.ends_with(SUFFIX) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// The last `count` lines across all log files
+pub fn tail(dir: &Path, count: usize) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+    for file in files(dir)?.iter().rev() {
+        let content = fs::read_to_string(file)?;
+        let mut newest: Vec<String> = content
+            .lines()
+            .rev()
+            .take(count.saturating_sub(lines.len()))
+            .map(str::to_string)
+            .collect();
+        newest.reverse();
+        newest.append(&mut lines);
+        lines = newest;
+        if lines.len() >= count {
+            break;
+        }
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tail() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        assert!(tail(&dir.join("missing"), 5).unwrap().is_empty());
+
+        fs::write(dir.join("alx.2026-01-01.log"), "a\nb\nc\n").unwrap();
+        fs::write(dir.join("alx.2026-01-02.log"), "d\ne\n").unwrap();
+        fs::write(dir.join("other.txt"), "x\n").unwrap();
+
+        assert_eq!(tail(dir, 3).unwrap(), vec!["c", "d", "e"]);
+        assert_eq!(tail(dir, 1).unwrap(), vec!["e"]);
+        assert_eq!(tail(dir, 10).unwrap().len(), 5);
+    }
+}
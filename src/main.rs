@@ -1,45 +1,323 @@
-mod alias;
 mod cli;
 mod command;
-mod config;
-mod error;
-mod shell;
+mod completions;
+mod server;
 
+use alx::config;
+use alx::config::manager::ConfigManager;
+use alx::error::Result;
+use alx::logging;
 use clap::Parser;
-use cli::{Cli, Commands};
-use error::Result;
+use cli::{
+    BackupCommands, Cli, Commands, ConfigCommands, GitCommands, GroupCommands, LogCommands,
+    PackCommands, PresetCommands, ScopeCommands, ShareCommands, ShellCommands, SnapshotCommands,
+};
 
 fn main() {
     if let Err(e) = run() {
+        tracing::error!("{}", e);
         eprintln!("Error: {}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
 
 fn run() -> Result<()> {
     let cli = Cli::parse();
+    if let Some(dir) = cli.config_dir {
+        config::manager::set_config_dir(dir);
+    }
+    if cli.sync || cli.no_sync {
+        command::set_sync_override(cli.sync);
+    }
+    start_logging();
+
+    // Output meant for scripts and long-running commands get no notice
+    let notify = !matches!(
+        cli.command,
+        Commands::SelfUpdate { .. }
+            | Commands::Env { .. }
+            | Commands::Try { .. }
+            | Commands::ShellInit { .. }
+            | Commands::Count { .. }
+            | Commands::Completions { install: false, .. }
+            | Commands::Info { json: true }
+            | Commands::Serve { .. }
+            | Commands::Watch
+            | Commands::External(_)
+    );
 
-    match cli.command {
-        Commands::Init => command::init(),
+    let result = match cli.command {
+        Commands::Init { repair, rc_file } => command::init(repair, rc_file),
         Commands::Add {
             name,
             command,
             description,
             group,
-        } => command::add(name, command, description, group),
-        Commands::Remove { names } => command::remove(names),
-        Commands::List { group } => command::list(group),
-        Commands::Search { keyword } => command::search(keyword),
+            tags,
+        } => command::add(name, command, description, group, tags),
+        Commands::Link {
+            name,
+            target,
+            description,
+            group,
+        } => command::link(name, target, description, group),
+        Commands::Rename { old, new } => command::rename(old, new),
+        Commands::Remove {
+            names,
+            conditions,
+            force,
+        } => command::remove(names, conditions, force),
+        Commands::List {
+            group,
+            conditions,
+            exclude_group,
+            exclude_tag,
+            show_dates,
+            last_used,
+            unused_since,
+            sort,
+            format,
+        } => command::list(
+            group,
+            with_exclusions(conditions, exclude_group, exclude_tag),
+            show_dates,
+            last_used,
+            unused_since,
+            sort,
+            format,
+        ),
+        Commands::Show { name } => command::show(name),
+        Commands::Protect { names } => command::protect(names, true),
+        Commands::Unprotect { names } => command::protect(names, false),
+        Commands::Search { keyword, format } => command::search(keyword, format),
+        Commands::Grep {
+            pattern,
+            ignore_case,
+            context,
+        } => command::grep(pattern, ignore_case, context),
         Commands::Edit {
             name,
             command,
             description,
             group,
-        } => command::edit(name, command, description, group),
-        Commands::Export { output, format } => command::export(output, format),
-        Commands::Import { file } => command::import(file),
+            tags,
+            force,
+            batch,
+        } => match name {
+            Some(name) if !batch => command::edit(name, command, description, group, tags, force),
+            _ => command::edit_batch(force),
+        },
+        Commands::Export {
+            output,
+            format,
+            group,
+            tag,
+            enabled_only,
+            conditions,
+            exclude_group,
+            exclude_tag,
+            since,
+            until,
+            by,
+            split_by_group,
+        } => {
+            let conditions = with_exclusions(conditions, exclude_group, exclude_tag);
+            command::export(
+                output,
+                format,
+                group,
+                tag,
+                enabled_only,
+                with_date_range(conditions, &by, since, until),
+                split_by_group,
+            )
+        }
+        Commands::Import {
+            file,
+            from,
+            report,
+            map_group,
+            into_group,
+        } => command::import(file, from, report, map_group, into_group),
+        Commands::Merge {
+            file,
+            base,
+            strategy,
+            dry_run,
+        } => command::merge(file, base, strategy, dry_run),
+        Commands::Share {
+            name,
+            command: share_command,
+        } => match share_command {
+            None => command::share(name),
+            Some(ShareCommands::Gist {
+                format,
+                group,
+                tag,
+                enabled_only,
+                public,
+                description,
+            }) => command::share_gist(format, group, tag, enabled_only, public, description),
+        },
+        Commands::Git {
+            command: git_command,
+        } => match git_command {
+            GitCommands::Init => command::git_init(),
+            GitCommands::Remote { url } => command::git_remote(url),
+            GitCommands::Push => command::git_push(),
+            GitCommands::Pull { strategy } => command::git_pull(strategy),
+            GitCommands::Status => command::git_status(),
+        },
+        Commands::Preset {
+            command: preset_command,
+        } => match preset_command {
+            PresetCommands::List => command::preset_list(),
+            PresetCommands::Apply {
+                name,
+                group,
+                overwrite,
+            } => command::preset_apply(name, group, overwrite),
+        },
+        Commands::Pack {
+            command: pack_command,
+        } => match pack_command {
+            PackCommands::Search { query } => command::pack_search(query),
+            PackCommands::Install { name, group } => command::pack_install(name, group),
+            PackCommands::Update { name } => command::pack_update(name),
+            PackCommands::Remove { name } => command::pack_remove(name),
+            PackCommands::List => command::pack_list(),
+        },
+        Commands::Backup {
+            command: backup_command,
+        } => match backup_command {
+            BackupCommands::List => command::backup_list(),
+            BackupCommands::Restore { id } => command::backup_restore(id),
+        },
+        Commands::Snapshot {
+            command: snapshot_command,
+        } => match snapshot_command {
+            SnapshotCommands::Create { name, force } => command::snapshot_create(name, force),
+            SnapshotCommands::Restore { name } => command::snapshot_restore(name),
+            SnapshotCommands::Diff { name } => command::snapshot_diff(name),
+            SnapshotCommands::List => command::snapshot_list(),
+        },
+        Commands::Log {
+            command: log_command,
+        } => match log_command {
+            LogCommands::Tail { lines } => command::log_tail(lines),
+        },
+        Commands::Config {
+            command: config_command,
+        } => match config_command {
+            ConfigCommands::Get { key } => command::config_get(key),
+            ConfigCommands::Set { key, value } => command::config_set(key, value),
+            ConfigCommands::Unset { key } => command::config_unset(key),
+            ConfigCommands::List => command::config_list(),
+            ConfigCommands::Doctor { fix } => command::config_doctor(fix),
+        },
+        Commands::Shell {
+            command: shell_command,
+        } => match shell_command {
+            ShellCommands::Set { shell } => command::shell_set(shell),
+            ShellCommands::Show => command::shell_show(),
+        },
+        Commands::Sync => command::sync(),
+        Commands::Env { shell, group, tag } => command::env(shell, group, tag),
+        Commands::Try {
+            name,
+            command,
+            shell,
+        } => command::try_alias(name, command, shell),
+        Commands::Top { since, limit } => command::top(since, limit),
+        Commands::Suggest { min_count, limit } => command::suggest(min_count, limit),
+        Commands::Scope {
+            command: scope_command,
+        } => match scope_command {
+            ScopeCommands::Add { group, dir } => command::scope_add(group, dir),
+            ScopeCommands::Remove { group, dir } => command::scope_remove(group, dir),
+            ScopeCommands::List => command::scope_list(),
+        },
+        Commands::Enable { names } => command::set_enabled(names, true),
+        Commands::Disable { names } => command::set_enabled(names, false),
+        Commands::Toggle { group } => command::toggle(group),
+        Commands::Copy { name } => command::copy(name),
+        Commands::Watch => command::watch(),
+        Commands::Serve { listen } => server::serve(&listen),
+        Commands::Lint { fix } => command::lint(fix),
+        Commands::Fsck { auto } => command::fsck(auto),
+        Commands::CheckShadowing { all } => command::check_shadowing(all),
+        Commands::Group {
+            command: group_command,
+        } => match group_command {
+            GroupCommands::Create { group, description } => {
+                command::group_create(group, description)
+            }
+            GroupCommands::Lock { group } => command::group_lock(group, true),
+            GroupCommands::Unlock { group } => command::group_lock(group, false),
+            GroupCommands::Normalize { lower, dry_run } => command::group_normalize(lower, dry_run),
+            GroupCommands::Infer { apply } => command::group_infer(apply),
+            GroupCommands::Color { group, color } => command::group_color(group, color),
+        },
         Commands::Groups => command::groups(),
-        Commands::Info => command::info(),
-        Commands::Migrate { from } => command::migrate(from),
+        Commands::Count { what, json } => command::count(what, json),
+        Commands::Info { json } => command::info(json),
+        Commands::Open { target } => command::open(target),
+        Commands::Migrate {
+            from,
+            omz,
+            shell,
+            depth,
+            group,
+            group_by_source,
+        } => command::migrate(from, omz, shell, depth, group, group_by_source),
+        Commands::SelfUpdate { check } => command::self_update(check),
+        Commands::Completions { shell, install } => command::completions(shell, install),
+        Commands::ShellInit { shell } => command::shell_init(shell),
+        Commands::External(args) => command::plugin(args),
+    };
+
+    if result.is_ok() {
+        command::roll_up_large_usage_log();
+        command::reload_hint();
     }
+    if result.is_ok() && notify {
+        command::notify_update();
+    }
+    result
+}
+
+// Logging is opt-in and must never stop a command from running
+fn start_logging() {
+    let Ok(config_manager) = ConfigManager::new() else {
+        return;
+    };
+    let enabled = config_manager
+        .load_config()
+        .is_ok_and(|config| config.settings.log_enabled);
+    if enabled && let Err(e) = logging::init(&config_manager.log_dir()) {
+        eprintln!("Warning: {}", e);
+    }
+}
+
+// --exclude-group and --exclude-tag as the --where conditions they stand for
+fn with_exclusions(
+    mut conditions: Vec<String>,
+    groups: Vec<String>,
+    tags: Vec<String>,
+) -> Vec<String> {
+    conditions.extend(groups.iter().map(|g| format!("group!={}", g)));
+    conditions.extend(tags.iter().map(|t| format!("tag!={}", t)));
+    conditions
+}
+
+// --since and --until as conditions on the `by` date, both days included
+fn with_date_range(
+    mut conditions: Vec<String>,
+    by: &str,
+    since: Option<String>,
+    until: Option<String>,
+) -> Vec<String> {
+    conditions.extend(since.map(|date| format!("{}>={}", by, date)));
+    conditions.extend(until.map(|date| format!("{}<={}", by, date)));
+    conditions
 }
@@ -0,0 +1,111 @@
+use crate::error::{AlxError, Result};
+use crate::preset::Preset;
+use serde::Deserialize;
+
+const USER_AGENT: &str = concat!("alx/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Deserialize)]
+pub struct PackIndex {
+    pub packs: Vec<PackEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackEntry {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    // Absolute, or relative to the index URL
+    pub url: String,
+}
+
+impl PackIndex {
+    pub fn fetch(index_url: &str) -> Result<Self> {
+        let content = download(index_url)?;
+        serde_json::from_str(&content)
+            .map_err(|e| AlxError::ConfigError(format!("Invalid pack index: {}", e)))
+    }
+
+    pub fn search(&self, query: &str) -> Vec<&PackEntry> {
+        let query = query.to_lowercase();
+        self.packs
+            .iter()
+            .filter(|p| {
+                p.name.to_lowercase().contains(&query)
+                    || p.description.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    pub fn find(&self, name: &str) -> Result<&PackEntry> {
+        self.packs
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| AlxError::ConfigError(format!("Pack '{}' not found in index", name)))
+    }
+}
+
+impl PackEntry {
+    // Packs use the same TOML format as the bundled presets
+    pub fn fetch(&self, index_url: &str) -> Result<Preset> {
+        let content = download(&resolve_url(index_url, &self.url))?;
+        Preset::parse(&self.name, &content)
+    }
+}
+
+fn resolve_url(index_url: &str, url: &str) -> String {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return url.to_string();
+    }
+
+    let base = index_url
+        .rsplit_once('/')
+        .map(|(base, _)| base)
+        .unwrap_or(index_url);
+    format!("{}/{}", base, url.trim_start_matches("./"))
+}
+
+fn download(url: &str) -> Result<String> {
+    ureq::get(url)
+        .header("User-Agent", USER_AGENT)
+        .call()
+        .and_then(|mut r| r.body_mut().read_to_string())
+        .map_err(|e| AlxError::NetworkError(format!("Failed to download {}: {}", url, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_url() {
+        let index = "https://example.com/packs/index.json";
+        assert_eq!(
+            resolve_url(index, "git-extras.toml"),
+            "https://example.com/packs/git-extras.toml"
+        );
+        assert_eq!(
+            resolve_url(index, "./k8s/pack.toml"),
+            "https://example.com/packs/k8s/pack.toml"
+        );
+        assert_eq!(
+            resolve_url(index, "https://cdn.example.com/a.toml"),
+            "https://cdn.example.com/a.toml"
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let index: PackIndex = serde_json::from_str(
+            r#"{"packs": [
+                {"name": "git-extras", "version": "1.0.0", "description": "More git", "url": "g.toml"},
+                {"name": "terraform", "version": "0.2.0", "description": "IaC helpers", "url": "t.toml"}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(index.search("GIT").len(), 1);
+        assert_eq!(index.search("iac")[0].name, "terraform");
+        assert!(index.find("missing").is_err());
+    }
+}
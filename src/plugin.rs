@@ -0,0 +1,82 @@
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+// `alx foo` runs the first `alx-foo` on PATH when foo isn't a built-in command
+pub const PREFIX: &str = "alx-";
+
+// The executable for plugin `name` on PATH
+pub fn find(name: &str) -> Option<PathBuf> {
+    find_in(name, env::var_os("PATH")?)
+}
+
+// Names of the plugins on PATH, without the prefix
+pub fn list() -> Vec<String> {
+    env::var_os("PATH").map(list_in).unwrap_or_default()
+}
+
+fn find_in(name: &str, path: OsString) -> Option<PathBuf> {
+    let file = format!("{}{}{}", PREFIX, name, env::consts::EXE_SUFFIX);
+    env::split_paths(&path)
+        .map(|dir| dir.join(&file))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn list_in(path: OsString) -> Vec<String> {
+    let mut names: Vec<String> = env::split_paths(&path)
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| is_executable(&entry.path()))
+        .filter_map(|entry| {
+            let file = entry.file_name().into_string().ok()?;
+            let name = file
+                .strip_prefix(PREFIX)?
+                .strip_suffix(env::consts::EXE_SUFFIX)?;
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_and_list() {
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let write = |dir: &TempDir, name: &str, mode: u32| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+            path
+        };
+        let hello = write(&first, "alx-hello", 0o755);
+        write(&second, "alx-hello", 0o755);
+        write(&second, "alx-notes", 0o644);
+        write(&second, "alx-sync-gist", 0o755);
+        let path = env::join_paths([first.path(), second.path()]).unwrap();
+
+        assert_eq!(find_in("hello", path.clone()), Some(hello));
+        assert_eq!(find_in("notes", path.clone()), None);
+        assert_eq!(list_in(path), vec!["hello", "sync-gist"]);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::alias::Alias;
+use crate::error::{AlxError, Result};
+use serde::Deserialize;
+
+// Presets are compiled into the binary so they work offline
+const PRESETS: &[(&str, &str)] = &[
+    ("docker", include_str!("../presets/docker.toml")),
+    ("git", include_str!("../presets/git.toml")),
+    ("kubectl", include_str!("../presets/kubectl.toml")),
+    ("systemd", include_str!("../presets/systemd.toml")),
+];
+
+#[derive(Debug, Deserialize)]
+struct PresetFile {
+    description: String,
+    aliases: Vec<PresetAlias>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PresetAlias {
+    name: String,
+    command: String,
+    description: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Preset {
+    pub name: String,
+    pub description: String,
+    aliases: Vec<PresetAlias>,
+}
+
+impl Preset {
+    pub fn all() -> Result<Vec<Preset>> {
+        PRESETS
+            .iter()
+            .map(|(name, content)| Self::parse(name, content))
+            .collect()
+    }
+
+    pub fn find(name: &str) -> Result<Preset> {
+        let (name, content) = PRESETS.iter().find(|(n, _)| *n == name).ok_or_else(|| {
+            AlxError::ConfigError(format!(
+                "Unknown preset: {} (run 'alx preset list' to see available presets)",
+                name
+            ))
+        })?;
+        Self::parse(name, content)
+    }
+
+    pub fn parse(name: &str, content: &str) -> Result<Preset> {
+        let file: PresetFile = toml::from_str(content)?;
+        Ok(Preset {
+            name: name.to_string(),
+            description: file.description,
+            aliases: file.aliases,
+        })
+    }
+
+    pub fn alias_count(&self) -> usize {
+        self.aliases.len()
+    }
+
+    // Build the preset's aliases, placed in `group`
+    pub fn to_aliases(&self, group: &str) -> Vec<Alias> {
+        self.aliases
+            .iter()
+            .map(|a| {
+                let alias =
+                    Alias::new(a.name.clone(), a.command.clone()).with_group(group.to_string());
+                match &a.description {
+                    Some(desc) => alias.with_description(desc.clone()),
+                    None => alias,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::validator::AliasValidator;
+
+    #[test]
+    fn test_all_presets_parse_and_validate() {
+        for preset in Preset::all().unwrap() {
+            assert!(preset.alias_count() > 0, "{} is empty", preset.name);
+            for alias in preset.to_aliases(&preset.name) {
+                assert!(AliasValidator::validate_name(&alias.name).is_ok());
+                assert!(AliasValidator::validate_command(&alias.command).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        let preset = Preset::find("git").unwrap();
+        let aliases = preset.to_aliases("vcs");
+        assert!(aliases.iter().any(|a| a.name == "gs"));
+        assert!(aliases.iter().all(|a| a.group.as_deref() == Some("vcs")));
+
+        assert!(Preset::find("emacs").is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use crate::alias::Alias;
+use crate::config::Settings;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use comfy_table::{
+    Cell, Color, ContentArrangement, Table,
+    modifiers::UTF8_ROUND_CORNERS,
+    presets::{NOTHING, UTF8_BORDERS_ONLY},
+};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+// Progress bars are only worth drawing for large batches
+const PROGRESS_THRESHOLD: usize = 100;
+
+pub fn progress_bar(len: usize, message: &str) -> ProgressBar {
+    if len < PROGRESS_THRESHOLD {
+        return ProgressBar::hidden();
+    }
+    styled_bar(len, message)
+}
+
+// A bar ticking off each shell's file as a sync writes it, drawn only when
+// there is more than one
+pub fn sync_progress(targets: usize) -> ProgressBar {
+    if targets < 2 {
+        return ProgressBar::hidden();
+    }
+    styled_bar(targets, "Syncing")
+}
+
+fn styled_bar(len: usize, message: &str) -> ProgressBar {
+    let bar = ProgressBar::new(len as u64);
+    if let Ok(style) = ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len}") {
+        bar.set_style(style.progress_chars("=> "));
+    }
+    bar.set_message(message.to_string());
+    bar
+}
+
+// A counter for work of unknown length, such as reading a streamed import.
+// It stays hidden until `advance` passes the progress threshold.
+pub fn spinner(message: &str) -> ProgressBar {
+    let bar = ProgressBar::with_draw_target(None, ProgressDrawTarget::hidden());
+    if let Ok(style) = ProgressStyle::with_template("{spinner} {msg} {pos}") {
+        bar.set_style(style);
+    }
+    bar.set_message(message.to_string());
+    bar
+}
+
+pub fn advance(bar: &ProgressBar, pos: usize) {
+    if pos == PROGRESS_THRESHOLD {
+        bar.set_draw_target(ProgressDrawTarget::stderr());
+    }
+    bar.set_position(pos as u64);
+}
+
+// An incoming alias whose name is taken by a different existing alias
+#[derive(Debug, Serialize)]
+pub struct ImportConflict {
+    pub name: String,
+    pub existing: String,
+    pub incoming: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportFailure {
+    pub name: String,
+    pub reason: String,
+}
+
+// Colors a group can be shown in, besides "none"
+pub const GROUP_COLORS: &[&str] = &["red", "green", "yellow", "blue", "magenta", "cyan"];
+
+// The color `group` is shown in: its group_colors entry, else one picked from
+// the name so a group keeps its color between runs
+pub fn group_color<'a>(group: &str, colors: &'a BTreeMap<String, String>) -> &'a str {
+    if let Some(color) = colors.get(group) {
+        return color;
+    }
+    let hash = group.bytes().fold(0usize, |hash, b| {
+        hash.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    GROUP_COLORS[hash % GROUP_COLORS.len()]
+}
+
+// `group` styled in its color for plain output. console leaves it unstyled
+// for NO_COLOR and when stdout isn't a terminal.
+pub fn paint_group(group: &str, colors: &BTreeMap<String, String>) -> String {
+    let color = match group_color(group, colors) {
+        "red" => console::Color::Red,
+        "green" => console::Color::Green,
+        "yellow" => console::Color::Yellow,
+        "blue" => console::Color::Blue,
+        "magenta" => console::Color::Magenta,
+        "cyan" => console::Color::Cyan,
+        _ => return group.to_string(),
+    };
+    console::style(group).fg(color).to_string()
+}
+
+fn table_color(name: &str) -> Option<Color> {
+    match name {
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" => Some(Color::Magenta),
+        "cyan" => Some(Color::Cyan),
+        _ => None,
+    }
+}
+
+// Width used when truncating without a table_max_width setting
+const DEFAULT_TRUNCATE_WIDTH: usize = 40;
+
+// How alias tables are drawn, from the table_* settings
+#[derive(Debug, Default)]
+pub struct TableStyle {
+    borders_hidden: bool,
+    max_width: Option<usize>,
+    truncate: bool,
+    dates: bool,
+    // Per-group color overrides, None when output isn't colored
+    group_colors: Option<BTreeMap<String, String>>,
+    // Last use per alias, for a Last used column
+    last_used: Option<HashMap<String, DateTime<Utc>>>,
+}
+
+impl TableStyle {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            borders_hidden: settings.table_style.as_deref() == Some("none"),
+            max_width: settings.table_max_width,
+            truncate: settings.table_overflow.as_deref() == Some("truncate"),
+            dates: false,
+            group_colors: None,
+            last_used: None,
+        }
+    }
+
+    // Color the Group column, unless NO_COLOR is set or stdout isn't a
+    // terminal
+    pub fn with_group_colors(mut self, colors: &BTreeMap<String, String>) -> Self {
+        self.group_colors = console::colors_enabled().then(|| colors.clone());
+        self
+    }
+
+    // Add Created/Updated columns to alias tables
+    pub fn with_dates(mut self, dates: bool) -> Self {
+        self.dates = dates;
+        self
+    }
+
+    // Add a Last used column to alias tables
+    pub fn with_last_used(mut self, last_used: HashMap<String, DateTime<Utc>>) -> Self {
+        self.last_used = Some(last_used);
+        self
+    }
+
+    pub fn table(&self) -> Table {
+        let mut table = Table::new();
+        if self.borders_hidden {
+            table.load_preset(NOTHING);
+        } else {
+            table.load_preset(UTF8_BORDERS_ONLY);
+            table.apply_modifier(UTF8_ROUND_CORNERS);
+        }
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table
+    }
+
+    // Fit long text such as commands into the configured width
+    pub fn fit(&self, text: &str) -> String {
+        match (self.max_width, self.truncate) {
+            (Some(width), false) => wrap(text, width),
+            (width, true) => truncate(text, width.unwrap_or(DEFAULT_TRUNCATE_WIDTH)),
+            (None, false) => text.to_string(),
+        }
+    }
+}
+
+// The name/command/description/group table used by list and search
+pub fn alias_table(aliases: &[&Alias], style: &TableStyle) -> Table {
+    let mut table = style.table();
+    let mut header = vec!["Name", "Command", "Description", "Group"];
+    if style.dates {
+        header.extend(["Created", "Updated"]);
+    }
+    if style.last_used.is_some() {
+        header.push("Last used");
+    }
+    table.set_header(header);
+
+    let now = Utc::now();
+    for alias in aliases {
+        let description = alias.description.as_deref().unwrap_or("-");
+        let group = alias.group.as_deref().unwrap_or("-");
+
+        let command = if alias.is_link() {
+            format!("→ {}", alias.command)
+        } else {
+            alias.command.clone()
+        };
+        let mut group_cell = Cell::new(group);
+        if let (Some(colors), Some(name)) = (&style.group_colors, &alias.group)
+            && let Some(color) = table_color(group_color(name, colors))
+        {
+            group_cell = group_cell.fg(color);
+        }
+        let mut row = vec![
+            Cell::new(&alias.name),
+            Cell::new(style.fit(&command)),
+            Cell::new(style.fit(description)),
+            group_cell,
+        ];
+        if style.dates {
+            row.push(Cell::new(relative_time(alias.created_at, now)));
+            row.push(Cell::new(relative_time(alias.updated_at, now)));
+        }
+        if let Some(last_used) = &style.last_used {
+            let used = last_used.get(&alias.name);
+            row.push(Cell::new(
+                used.map_or("never".to_string(), |t| relative_time(*t, now)),
+            ));
+        }
+        table.add_row(row);
+    }
+
+    table
+}
+
+// "just now", "5m ago", "3d ago"; dates older than a year are shown in full
+pub fn relative_time(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - then;
+    if elapsed.num_days() >= 365 {
+        return then.format("%Y-%m-%d").to_string();
+    }
+    let (amount, unit) = if elapsed.num_days() >= 30 {
+        (elapsed.num_days() / 30, "mo")
+    } else if elapsed.num_days() >= 1 {
+        (elapsed.num_days(), "d")
+    } else if elapsed.num_hours() >= 1 {
+        (elapsed.num_hours(), "h")
+    } else if elapsed.num_minutes() >= 1 {
+        (elapsed.num_minutes(), "m")
+    } else {
+        return "just now".to_string();
+    };
+    format!("{}{} ago", amount, unit)
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
+// Greedy word wrap; words longer than `width` are split
+fn wrap(text: &str, width: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+
+        if line.is_empty() {
+            line = word;
+        } else if line.chars().count() + 1 + word.chars().count() <= width {
+            line.push(' ');
+            line.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut line, word));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub added: Vec<String>,
+    // Already present with the same command
+    pub skipped: Vec<String>,
+    pub conflicts: Vec<ImportConflict>,
+    pub failed: Vec<ImportFailure>,
+}
+
+impl ImportSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn print_table(&self) {
+        let mut table = Table::new();
+        table.load_preset(UTF8_BORDERS_ONLY);
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec!["Result", "Count"]);
+
+        table.add_row(vec![Cell::new("Added"), Cell::new(self.added.len())]);
+        table.add_row(vec![Cell::new("Skipped"), Cell::new(self.skipped.len())]);
+        table.add_row(vec![
+            Cell::new("Conflicts"),
+            Cell::new(self.conflicts.len()),
+        ]);
+        table.add_row(vec![Cell::new("Failed"), Cell::new(self.failed.len())]);
+
+        println!("{table}");
+
+        if !self.failed.is_empty() {
+            eprintln!("\nFailed:");
+            for failure in &self.failed {
+                eprintln!("  {}: {}", failure.name, failure.reason);
+            }
+        }
+    }
+
+    // Write the summary as JSON for scripts to post-process
+    pub fn write_report(&self, path: &Path, source: &str) -> Result<()> {
+        let report = serde_json::json!({
+            "source": source,
+            "added": self.added,
+            "skipped": self.skipped,
+            "conflicts": self.conflicts,
+            "failed": self.failed,
+        });
+        fs::write(path, serde_json::to_string_pretty(&report)? + "\n")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_batches_are_hidden() {
+        assert!(progress_bar(10, "Importing").is_hidden());
+    }
+
+    #[test]
+    fn test_fit() {
+        let wrapped = TableStyle {
+            max_width: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(wrapped.fit("git log --oneline"), "git log\n--oneline");
+        assert_eq!(wrapped.fit("abcdefghijklm x"), "abcdefghij\nklm x");
+
+        let truncated = TableStyle {
+            max_width: Some(10),
+            truncate: true,
+            ..Default::default()
+        };
+        assert_eq!(truncated.fit("git log --oneline"), "git log -…");
+        assert_eq!(truncated.fit("git log"), "git log");
+
+        assert_eq!(
+            TableStyle::default().fit("git log --oneline"),
+            "git log --oneline"
+        );
+    }
+
+    #[test]
+    fn test_relative_time() {
+        let now = Utc::now();
+        let ago = |d: chrono::Duration| relative_time(now - d, now);
+        assert_eq!(ago(chrono::Duration::seconds(20)), "just now");
+        assert_eq!(ago(chrono::Duration::minutes(5)), "5m ago");
+        assert_eq!(ago(chrono::Duration::hours(26)), "1d ago");
+        assert_eq!(ago(chrono::Duration::days(3)), "3d ago");
+        assert_eq!(ago(chrono::Duration::days(65)), "2mo ago");
+        let old = now - chrono::Duration::days(400);
+        assert_eq!(relative_time(old, now), old.format("%Y-%m-%d").to_string());
+        // Clock skew between machines can put timestamps in the future
+        assert_eq!(ago(chrono::Duration::minutes(-3)), "just now");
+    }
+
+    #[test]
+    fn test_group_color() {
+        let mut colors = BTreeMap::new();
+        let picked = group_color("git", &colors);
+        assert!(GROUP_COLORS.contains(&picked));
+        assert_eq!(group_color("git", &colors), picked);
+
+        colors.insert("git".to_string(), "none".to_string());
+        assert_eq!(group_color("git", &colors), "none");
+        assert_eq!(paint_group("git", &colors), "git");
+    }
+
+    #[test]
+    fn test_write_report() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("report.json");
+
+        let mut summary = ImportSummary::new();
+        summary.added.push("gs".to_string());
+        summary.conflicts.push(ImportConflict {
+            name: "ll".to_string(),
+            existing: "ls -la".to_string(),
+            incoming: "ls -lah".to_string(),
+        });
+        summary.write_report(&path, "aliases.json").unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(report["source"], "aliases.json");
+        assert_eq!(report["added"][0], "gs");
+        assert_eq!(report["conflicts"][0]["incoming"], "ls -lah");
+        assert!(report["failed"].as_array().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::alias::store::AliasStore;
+use crate::error::{AlxError, Result};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::process::Command;
+
+// A reference to a secret in an alias command, written as
+// `{{op://vault/item/field}}` (1Password CLI) or `{{pass:entry}}` (pass).
+// Placeholders are resolved when the shell files are generated, so the store
+// itself never holds the secret.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Secret<'a> {
+    OnePassword(&'a str),
+    Pass(&'a str),
+}
+
+impl Secret<'_> {
+    // Fetch the secret with the matching password manager CLI
+    pub fn lookup(&self) -> Result<String> {
+        let (program, output) = match self {
+            Secret::OnePassword(reference) => {
+                ("op", Command::new("op").args(["read", reference]).output())
+            }
+            Secret::Pass(entry) => ("pass", Command::new("pass").args(["show", entry]).output()),
+        };
+        let output = output.map_err(|e| {
+            AlxError::SecretLookupFailed(format!("could not run '{}': {}", program, e))
+        })?;
+
+        if !output.status.success() {
+            return Err(AlxError::SecretLookupFailed(format!(
+                "{} exited with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            )));
+        }
+
+        // pass keeps the password on the first line and metadata after it
+        let value = String::from_utf8_lossy(&output.stdout);
+        Ok(value.lines().next().unwrap_or_default().to_string())
+    }
+}
+
+// The secret placeholders in `command` with their byte ranges. Other `{{...}}`
+// text, such as docker format strings, is left alone.
+pub fn placeholders(command: &str) -> Vec<(Range<usize>, Secret<'_>)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = command[offset..].find("{{").map(|i| offset + i) {
+        let Some(end) = command[start..].find("}}").map(|i| start + i + 2) else {
+            break;
+        };
+        let inner = command[start + 2..end - 2].trim();
+        let secret = if inner.starts_with("op://") {
+            Some(Secret::OnePassword(inner))
+        } else {
+            inner.strip_prefix("pass:").map(|e| Secret::Pass(e.trim()))
+        };
+        match secret {
+            Some(secret) => {
+                found.push((start..end, secret));
+                offset = end;
+            }
+            None => offset = start + 2,
+        }
+    }
+    found
+}
+
+pub fn has_placeholders(command: &str) -> bool {
+    !placeholders(command).is_empty()
+}
+
+// Replace every placeholder in `command` with what `lookup` returns for it
+pub fn resolve<'c>(
+    command: &'c str,
+    mut lookup: impl FnMut(&Secret<'c>) -> Result<String>,
+) -> Result<String> {
+    let mut resolved = String::with_capacity(command.len());
+    let mut last = 0;
+    for (range, secret) in placeholders(command) {
+        resolved.push_str(&command[last..range.start]);
+        resolved.push_str(&lookup(&secret)?);
+        last = range.end;
+    }
+    resolved.push_str(&command[last..]);
+    Ok(resolved)
+}
+
+// The store with placeholders in enabled aliases resolved, looking each
+// secret up once. Borrowed when there is nothing to resolve.
+pub fn resolve_store<'a>(
+    store: &'a AliasStore,
+    mut lookup: impl FnMut(&Secret<'a>) -> Result<String>,
+) -> Result<Cow<'a, AliasStore>> {
+    if !store
+        .list()
+        .iter()
+        .any(|a| a.enabled && has_placeholders(&a.command))
+    {
+        return Ok(Cow::Borrowed(store));
+    }
+
+    let mut cache: HashMap<Secret, String> = HashMap::new();
+    let mut resolved = store.clone();
+    for (alias, original) in resolved.aliases.iter_mut().zip(store.list()) {
+        if !alias.enabled {
+            continue;
+        }
+        alias.command = resolve(&original.command, |secret| {
+            if let Some(value) = cache.get(secret) {
+                return Ok(value.clone());
+            }
+            let value = lookup(secret).map_err(|e| match e {
+                AlxError::SecretLookupFailed(message) => {
+                    AlxError::SecretLookupFailed(format!("alias '{}': {}", alias.name, message))
+                }
+                e => e,
+            })?;
+            cache.insert(secret.clone(), value.clone());
+            Ok(value)
+        })?;
+    }
+
+    Ok(Cow::Owned(resolved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+
+    #[test]
+    fn test_placeholders() {
+        let command = "curl -H 'Token: {{ op://dev/api/token }}' -u {{pass:work/ci}} {{ .Names }}";
+        let found: Vec<Secret> = placeholders(command).into_iter().map(|(_, s)| s).collect();
+        assert_eq!(
+            found,
+            vec![
+                Secret::OnePassword("op://dev/api/token"),
+                Secret::Pass("work/ci")
+            ]
+        );
+        assert!(!has_placeholders("docker ps --format '{{.Names}}'"));
+        assert!(!has_placeholders("echo {{pass:unterminated"));
+    }
+
+    #[test]
+    fn test_resolve_store() {
+        let store = AliasStore::from_aliases(vec![
+            Alias::new("a".to_string(), "api {{pass:t}} {{pass:t}}".to_string()),
+            Alias::new("b".to_string(), "ls".to_string()),
+        ]);
+        let mut lookups = 0;
+        let resolved = resolve_store(&store, |secret| {
+            lookups += 1;
+            assert_eq!(*secret, Secret::Pass("t"));
+            Ok("s3cret".to_string())
+        })
+        .unwrap();
+        assert_eq!(resolved.get("a").unwrap().command, "api s3cret s3cret");
+        assert_eq!(lookups, 1);
+        assert_eq!(store.get("a").unwrap().command, "api {{pass:t}} {{pass:t}}");
+
+        let plain = AliasStore::from_aliases(vec![Alias::new("b".to_string(), "ls".to_string())]);
+        assert!(matches!(
+            resolve_store(&plain, |_| unreachable!()).unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -0,0 +1,396 @@
+use crate::command::{
+    check_syntax, group_name, record_change, run_alias_hooks, save_store, sync_alias_after_change,
+};
+use alx::alias::validator::AliasValidator;
+use alx::alias::{Alias, AliasKind};
+use alx::config::manager::ConfigManager;
+use alx::error::{AlxError, Result};
+use alx::hooks::AliasEvent;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::net::SocketAddr;
+use tiny_http::{Header, Method, Response, Server};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewAlias {
+    name: String,
+    command: String,
+    description: Option<String>,
+    group: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AliasChanges {
+    command: Option<String>,
+    description: Option<String>,
+    group: Option<String>,
+    tags: Option<Vec<String>>,
+    enabled: Option<bool>,
+}
+
+pub fn serve(listen: &str) -> Result<()> {
+    let addr: SocketAddr = listen
+        .parse()
+        .map_err(|_| AlxError::ConfigError(format!("Invalid listen address: {}", listen)))?;
+    if !addr.ip().is_loopback() {
+        eprintln!(
+            "Warning: anyone who can reach {} and sees the token can change your aliases",
+            addr
+        );
+    }
+
+    let server = Server::http(addr)
+        .map_err(|e| AlxError::NetworkError(format!("Failed to listen on {}: {}", addr, e)))?;
+    let token = new_token();
+    println!("Listening on http://{}", addr);
+    println!("Send 'Authorization: Bearer {}' with every request", token);
+    tracing::info!("serving the API on {}", addr);
+
+    for mut request in server.incoming_requests() {
+        let headers: Vec<(String, String)> = request
+            .headers()
+            .iter()
+            .map(|h| (h.field.to_string(), h.value.to_string()))
+            .collect();
+        let mut body = String::new();
+        let (status, value) =
+            if let Some(rejection) = reject(request.method(), &headers, addr, &token) {
+                rejection
+            } else {
+                match request.as_reader().read_to_string(&mut body) {
+                    Ok(_) => handle(request.method(), request.url(), &body),
+                    Err(e) => (400, json!({ "error": e.to_string() })),
+                }
+            };
+        tracing::info!("{} {} -> {}", request.method(), request.url(), status);
+
+        let response = Response::from_string(value.to_string())
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+        if let Err(e) = request.respond(response) {
+            tracing::error!("failed to send response: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+// 128 random bits, from the randomly keyed hashers std seeds from the OS
+fn new_token() -> String {
+    (0..2)
+        .map(|i: u8| format!("{:016x}", RandomState::new().hash_one(i)))
+        .collect()
+}
+
+// Why a request may not reach the store, if it may not. Browsers send
+// requests to loopback addresses from any page, so refuse anything with an
+// Origin, a Host other than the listen address (DNS rebinding) or a body a
+// form could send without a preflight, and require the token on top.
+fn reject(
+    method: &Method,
+    headers: &[(String, String)],
+    addr: SocketAddr,
+    token: &str,
+) -> Option<(u16, Value)> {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    };
+    let error = |status: u16, message: &str| Some((status, json!({ "error": message })));
+
+    let hosts = [addr.to_string(), format!("localhost:{}", addr.port())];
+    let host_ok = header("Host")
+        .is_some_and(|host| hosts[0] == host || (addr.ip().is_loopback() && hosts[1] == host));
+    if !host_ok {
+        return error(403, "Host must be the address alx serve listens on");
+    }
+    if header("Origin").is_some() {
+        return error(403, "Requests from web pages are not allowed");
+    }
+    if header("Authorization") != Some(format!("Bearer {}", token).as_str()) {
+        return error(401, "Missing or wrong token; use the one alx serve printed");
+    }
+    let json_body = header("Content-Type").is_some_and(|t| {
+        t.split(';')
+            .next()
+            .is_some_and(|t| t.trim().eq_ignore_ascii_case("application/json"))
+    });
+    if matches!(method, Method::Post | Method::Patch | Method::Put) && !json_body {
+        return error(415, "Content-Type must be application/json");
+    }
+    None
+}
+
+// Each request reloads the config and store, so changes made with the CLI
+// while the server runs are picked up
+fn handle(method: &Method, url: &str, body: &str) -> (u16, Value) {
+    match ConfigManager::new().and_then(|cm| route(&cm, method, url, body)) {
+        Ok(response) => response,
+        Err(e) => (status_for(&e), json!({ "error": e.to_string() })),
+    }
+}
+
+fn status_for(error: &AlxError) -> u16 {
+    match error {
+        AlxError::AliasNotFound(_) => 404,
+        AlxError::AliasExists(_) => 409,
+        AlxError::InvalidAliasName(_) | AlxError::InvalidCommand(_) | AlxError::JsonError(_) => 400,
+        AlxError::GroupLocked(_) | AlxError::AliasProtected(_) => 423,
+        _ => 500,
+    }
+}
+
+fn route(cm: &ConfigManager, method: &Method, url: &str, body: &str) -> Result<(u16, Value)> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (method, segments.as_slice()) {
+        (Method::Get, ["aliases"]) => list(cm, query),
+        (Method::Post, ["aliases"]) => add(cm, serde_json::from_str(body)?),
+        (Method::Get, ["aliases", name]) => {
+            let store = cm.load_aliases()?;
+            let alias = store
+                .get(name)
+                .ok_or_else(|| AlxError::AliasNotFound(name.to_string()))?;
+            Ok((200, serde_json::to_value(alias)?))
+        }
+        (Method::Patch | Method::Put, ["aliases", name]) => {
+            edit(cm, name, serde_json::from_str(body)?)
+        }
+        (Method::Delete, ["aliases", name]) => remove(cm, name),
+        (Method::Post, ["sync"]) => {
+            alx::sync::sync_aliases(cm)?;
+            Ok((200, json!({ "synced": true })))
+        }
+        _ => Ok((
+            404,
+            json!({ "error": format!("No route for {} {}", method, path) }),
+        )),
+    }
+}
+
+fn list(cm: &ConfigManager, query: &str) -> Result<(u16, Value)> {
+    let store = cm.load_aliases()?;
+    let group = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("group="));
+
+    let aliases: Vec<&Alias> = match group {
+        Some(group) => store.list_by_group(group),
+        None => store.list().iter().collect(),
+    };
+    Ok((200, serde_json::to_value(aliases)?))
+}
+
+fn add(cm: &ConfigManager, new: NewAlias) -> Result<(u16, Value)> {
+    let settings = cm.load_config()?.settings;
+    let mut store = cm.load_aliases()?;
+    let validation = settings.validation();
+    for warning in AliasValidator::check_in_store(&store, &new.name, &new.command, validation)? {
+        tracing::warn!("{}", warning);
+    }
+    check_syntax(cm, &new.command)?;
+    let mut alias = Alias::new(new.name.clone(), new.command).with_tags(new.tags);
+    alias.description = new.description;
+    alias.group = new
+        .group
+        .map(|g| group_name(&settings, &store, &g))
+        .transpose()?;
+    store.add(alias)?;
+    save_store(cm, &store)?;
+
+    sync_alias_after_change(cm, &store, &new.name, None)?;
+    record_change(cm, &format!("alx: add alias '{}'", new.name))?;
+    if let Some(alias) = store.get(&new.name) {
+        run_alias_hooks(cm, AliasEvent::Add, &[(alias, None)]);
+    }
+
+    Ok((201, serde_json::to_value(store.get(&new.name))?))
+}
+
+fn edit(cm: &ConfigManager, name: &str, changes: AliasChanges) -> Result<(u16, Value)> {
+    let config = cm.load_config()?;
+    let settings = &config.settings;
+    let mut store = cm.load_aliases()?;
+    // An empty description or group clears it
+    let group = match changes.group.as_deref() {
+        Some("") => Some(None),
+        Some(group) => Some(Some(group_name(settings, &store, group)?)),
+        None => None,
+    };
+    let alias = store
+        .get_mut(name)
+        .ok_or_else(|| AlxError::AliasNotFound(name.to_string()))?;
+    config.check_unlocked(alias, false)?;
+    let old = alias.clone();
+
+    if let Some(command) = changes.command {
+        // There is no one to confirm with, so protected aliases keep theirs
+        if alias.protected && command != alias.command {
+            return Err(AlxError::AliasProtected(name.to_string()));
+        }
+        AliasValidator::validate_command(&command)?;
+        // A new command turns a link into a plain alias
+        if command != alias.command {
+            alias.kind = AliasKind::Command;
+        }
+        alias.command = command;
+    }
+    if let Some(description) = changes.description {
+        alias.description = Some(description).filter(|d| !d.is_empty());
+    }
+    if let Some(group) = group {
+        alias.group = group;
+    }
+    if let Some(tags) = changes.tags {
+        alias.tags = tags;
+    }
+    if let Some(enabled) = changes.enabled {
+        alias.enabled = enabled;
+    }
+
+    if *alias == old {
+        return Ok((200, serde_json::to_value(&*alias)?));
+    }
+    alias.updated_at = chrono::Utc::now();
+
+    let command = alias.command.clone();
+    if command != old.command {
+        let profile = settings.validation_profile();
+        for warning in AliasValidator::check_command(&store, name, &command, profile)? {
+            tracing::warn!("{}", warning);
+        }
+        check_syntax(cm, &command)?;
+    }
+    save_store(cm, &store)?;
+
+    sync_alias_after_change(cm, &store, name, Some(&old))?;
+    record_change(cm, &format!("alx: edit alias '{}'", name))?;
+    if let Some(alias) = store.get(name) {
+        run_alias_hooks(cm, AliasEvent::Edit, &[(alias, Some(&old))]);
+    }
+
+    Ok((200, serde_json::to_value(store.get(name))?))
+}
+
+fn remove(cm: &ConfigManager, name: &str) -> Result<(u16, Value)> {
+    let mut store = cm.load_aliases()?;
+    if let Some(alias) = store.get(name) {
+        cm.load_config()?.check_unlocked(alias, false)?;
+        if alias.protected {
+            return Err(AlxError::AliasProtected(name.to_string()));
+        }
+    }
+    let linked = store.links_to(name);
+    if !linked.is_empty() {
+        let message = format!(
+            "{}: linked from {}; remove or relink them first",
+            name,
+            linked.join(", ")
+        );
+        return Ok((409, json!({ "error": message })));
+    }
+    let alias = store.remove(name)?;
+    save_store(cm, &store)?;
+
+    sync_alias_after_change(cm, &store, name, Some(&alias))?;
+    record_change(cm, &format!("alx: remove alias '{}'", name))?;
+    run_alias_hooks(cm, AliasEvent::Remove, &[(&alias, None)]);
+
+    Ok((200, serde_json::to_value(&alias)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alx::config::Config;
+
+    fn request(cm: &ConfigManager, method: Method, url: &str, body: &str) -> (u16, Value) {
+        route(cm, &method, url, body).unwrap_or_else(|e| (status_for(&e), json!(e.to_string())))
+    }
+
+    #[test]
+    fn test_routes() {
+        let mut config = Config::default();
+        config.settings.default_shell = Some("bash".to_string());
+        let cm = ConfigManager::in_memory(config).unwrap();
+
+        let body = r#"{"name": "gs", "command": "git status", "group": "git"}"#;
+        let (status, alias) = request(&cm, Method::Post, "/aliases", body);
+        assert_eq!(status, 201);
+        assert_eq!(alias["command"], "git status");
+        assert_eq!(request(&cm, Method::Post, "/aliases", body).0, 409);
+
+        let (status, alias) = request(
+            &cm,
+            Method::Patch,
+            "/aliases/gs",
+            r#"{"command": "git status -sb", "group": ""}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(alias["command"], "git status -sb");
+        assert!(alias["group"].is_null());
+
+        let (_, aliases) = request(&cm, Method::Get, "/aliases?group=git", "");
+        assert_eq!(aliases, json!([]));
+        let (_, aliases) = request(&cm, Method::Get, "/aliases", "");
+        assert_eq!(aliases.as_array().unwrap().len(), 1);
+
+        let synced = cm.read(&cm.shell_aliases_file_for(alx::shell::ShellType::Bash));
+        assert!(synced.unwrap().unwrap().contains("git status -sb"));
+
+        assert_eq!(request(&cm, Method::Delete, "/aliases/gs", "").0, 200);
+        assert_eq!(request(&cm, Method::Get, "/aliases/gs", "").0, 404);
+        assert_eq!(request(&cm, Method::Post, "/aliases", "{").0, 400);
+        assert_eq!(request(&cm, Method::Get, "/nope", "").0, 404);
+    }
+
+    #[test]
+    fn test_reject() {
+        let addr: SocketAddr = "127.0.0.1:7423".parse().unwrap();
+        let token = new_token();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, new_token());
+
+        let headers = |extra: &[(&str, &str)]| {
+            let auth = format!("Bearer {}", token);
+            let mut headers = vec![
+                ("Host".to_string(), "127.0.0.1:7423".to_string()),
+                ("Authorization".to_string(), auth),
+            ];
+            for (field, value) in extra {
+                headers.push((field.to_string(), value.to_string()));
+            }
+            headers
+        };
+        let status = |method: Method, headers: &[(String, String)]| {
+            reject(&method, headers, addr, &token).map(|(status, _)| status)
+        };
+
+        assert_eq!(status(Method::Get, &headers(&[])), None);
+        let json = [("Content-Type", "application/json; charset=utf-8")];
+        assert_eq!(status(Method::Post, &headers(&json)), None);
+        let form = [("Content-Type", "text/plain")];
+        assert_eq!(status(Method::Post, &headers(&form)), Some(415));
+        let page = [("Origin", "https://example.com")];
+        assert_eq!(status(Method::Get, &headers(&page)), Some(403));
+
+        let mut rebound = headers(&[]);
+        rebound[0].1 = "evil.example:7423".to_string();
+        assert_eq!(status(Method::Get, &rebound), Some(403));
+        let mut local = headers(&[]);
+        local[0].1 = "localhost:7423".to_string();
+        assert_eq!(status(Method::Get, &local), None);
+        let mut anonymous = headers(&[]);
+        anonymous.pop();
+        assert_eq!(status(Method::Get, &anonymous), Some(401));
+    }
+}
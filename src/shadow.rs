@@ -0,0 +1,190 @@
+use crate::editor;
+use crate::shell::ShellType;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "case", "esac", "for", "select", "while", "until", "do",
+    "done", "in", "function", "time", "coproc", "begin", "end", "switch", "not", "and", "or",
+];
+
+const POSIX_BUILTINS: &[&str] = &[
+    ".", ":", "[", "alias", "bg", "break", "cd", "command", "continue", "echo", "eval", "exec",
+    "exit", "export", "false", "fg", "getopts", "hash", "jobs", "kill", "printf", "pwd", "read",
+    "readonly", "return", "set", "shift", "source", "test", "times", "trap", "true", "type",
+    "ulimit", "umask", "unalias", "unset", "wait",
+];
+
+const BASH_BUILTINS: &[&str] = &[
+    "bind",
+    "builtin",
+    "caller",
+    "compgen",
+    "complete",
+    "compopt",
+    "declare",
+    "dirs",
+    "disown",
+    "enable",
+    "fc",
+    "help",
+    "history",
+    "let",
+    "local",
+    "logout",
+    "mapfile",
+    "popd",
+    "pushd",
+    "readarray",
+    "shopt",
+    "suspend",
+    "typeset",
+];
+
+const ZSH_BUILTINS: &[&str] = &[
+    "autoload",
+    "bindkey",
+    "builtin",
+    "compdef",
+    "declare",
+    "dirs",
+    "disown",
+    "emulate",
+    "fc",
+    "functions",
+    "history",
+    "let",
+    "local",
+    "logout",
+    "noglob",
+    "popd",
+    "print",
+    "pushd",
+    "r",
+    "rehash",
+    "setopt",
+    "typeset",
+    "unfunction",
+    "unsetopt",
+    "whence",
+    "where",
+    "which",
+    "zle",
+    "zmodload",
+    "zstyle",
+];
+
+const FISH_BUILTINS: &[&str] = &[
+    "abbr",
+    "argparse",
+    "bind",
+    "block",
+    "builtin",
+    "commandline",
+    "complete",
+    "contains",
+    "count",
+    "disown",
+    "emit",
+    "functions",
+    "history",
+    "math",
+    "random",
+    "realpath",
+    "set_color",
+    "status",
+    "string",
+];
+
+// What a name ran before an alx alias took it over
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shadowed {
+    // An alias defined outside alx, e.g. in .bashrc
+    Alias { command: String, file: PathBuf },
+    Keyword,
+    Builtin,
+    Program(PathBuf),
+}
+
+impl std::fmt::Display for Shadowed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Shadowed::Alias { command, file } => {
+                write!(f, "alias '{}' ({})", command, file.display())
+            }
+            Shadowed::Keyword => write!(f, "shell keyword"),
+            Shadowed::Builtin => write!(f, "shell builtin"),
+            Shadowed::Program(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+pub fn is_builtin(name: &str, shell_type: ShellType) -> bool {
+    let extra = match shell_type {
+        ShellType::Bash => BASH_BUILTINS,
+        ShellType::Zsh => ZSH_BUILTINS,
+        ShellType::Fish => FISH_BUILTINS,
+    };
+    POSIX_BUILTINS.contains(&name) || extra.contains(&name)
+}
+
+// Resolve `name` the way the shell would without alx, in the shell's order:
+// aliases from the user's own files, keywords, builtins, then $PATH
+pub fn shadowed(
+    name: &str,
+    shell_type: ShellType,
+    other_aliases: &HashMap<String, (String, PathBuf)>,
+) -> Option<Shadowed> {
+    if let Some((command, file)) = other_aliases.get(name) {
+        return Some(Shadowed::Alias {
+            command: command.clone(),
+            file: file.clone(),
+        });
+    }
+    if KEYWORDS.contains(&name) {
+        return Some(Shadowed::Keyword);
+    }
+    if is_builtin(name, shell_type) {
+        return Some(Shadowed::Builtin);
+    }
+    editor::find_program(name).map(Shadowed::Program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shadowed() {
+        let mut other = HashMap::new();
+        other.insert(
+            "ll".to_string(),
+            ("ls -l".to_string(), PathBuf::from("/home/me/.bashrc")),
+        );
+
+        assert!(matches!(
+            shadowed("ll", ShellType::Bash, &other),
+            Some(Shadowed::Alias { .. })
+        ));
+        assert_eq!(
+            shadowed("while", ShellType::Zsh, &other),
+            Some(Shadowed::Keyword)
+        );
+        assert_eq!(
+            shadowed("shopt", ShellType::Bash, &other),
+            Some(Shadowed::Builtin)
+        );
+        assert!(!matches!(
+            shadowed("shopt", ShellType::Fish, &other),
+            Some(Shadowed::Builtin)
+        ));
+        assert!(matches!(
+            shadowed("sh", ShellType::Bash, &other),
+            Some(Shadowed::Program(_))
+        ));
+        assert_eq!(
+            shadowed("alx-no-such-command", ShellType::Bash, &other),
+            None
+        );
+    }
+}
@@ -23,40 +23,72 @@ impl ShellHandler for BashHandler {
     }
 
     fn generate_aliases_file(&self, aliases: &[&Alias]) -> String {
-        let mut content = String::new();
-        content.push_str("# Generated by alx - DO NOT EDIT MANUALLY\n");
-        content.push_str("# This file is automatically generated and will be overwritten\n\n");
+        super::render_grouped(aliases, |alias| self.generate_alias_line(alias))
+    }
 
-        if aliases.is_empty() {
-            content.push_str("# No aliases configured\n");
-            return content;
+    fn cleanup(&self, names: &[String]) -> String {
+        let mut content = String::from("# Aliases removed since they were last synced\n");
+        for name in names {
+            content.push_str(&format!("unalias {} 2>/dev/null\n", name));
         }
+        content
+    }
 
-        // Group by category
-        let mut grouped: std::collections::HashMap<String, Vec<&Alias>> =
-            std::collections::HashMap::new();
+    fn descriptions(&self, _aliases: &[&Alias]) -> String {
+        // bash completion lists bare names only
+        String::new()
+    }
 
-        for alias in aliases {
-            let group = alias.group.as_deref().unwrap_or("general");
-            grouped.entry(group.to_string()).or_default().push(alias);
-        }
+    fn usage_hook(&self, log: &std::path::Path) -> String {
+        // The typed command line is only available from history, so check the
+        // newest entry before each prompt, once per entry
+        format!(
+            r#"# Record alias usage for 'alx top'
+__alx_track() {{
+  local num word _
+  read -r num word _ <<< "$(HISTTIMEFORMAT= builtin history 1)"
+  [[ $num == "$__alx_last" ]] && return
+  __alx_last=$num
+  if [[ -n $word && -n ${{BASH_ALIASES[$word]+set}} ]]; then
+    printf '%(%s)T %s\n' -1 "$word" >> {log}
+  fi
+}}
+[[ $PROMPT_COMMAND == *__alx_track* ]] || PROMPT_COMMAND="__alx_track${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}"
+"#,
+            log = crate::shell::quote(&log.to_string_lossy())
+        )
+    }
 
-        let mut groups: Vec<_> = grouped.keys().collect();
-        groups.sort();
+    fn reload_function(&self) -> String {
+        r#"# Load this file again after alx changes it
+__alx_file=${BASH_SOURCE[0]}
+alx-reload() { . "$__alx_file"; }
+"#
+        .to_string()
+    }
 
-        for group in groups {
-            let group_aliases = grouped.get(group).unwrap();
-            content.push_str(&format!("# {} aliases\n", group));
+    fn fingerprint_line(&self, fingerprint: &str) -> String {
+        format!("__alx_fingerprint={}\n", fingerprint)
+    }
 
-            for alias in group_aliases {
-                if let Some(desc) = &alias.description {
-                    content.push_str(&format!("# {}\n", desc));
-                }
-                content.push_str(&format!("{}\n", self.generate_alias_line(alias)));
-            }
-            content.push('\n');
-        }
+    fn stale_check(
+        &self,
+        fingerprint_file: &std::path::Path,
+        aliases_file: &std::path::Path,
+    ) -> String {
+        let mut content = crate::shell::posix_stale_function(fingerprint_file, aliases_file);
+        content.push_str(
+            "[[ $PROMPT_COMMAND == *__alx_stale* ]] || PROMPT_COMMAND=\"__alx_stale${PROMPT_COMMAND:+;$PROMPT_COMMAND}\"\n",
+        );
+        content
+    }
 
+    fn scope_hook(&self, scopes: &[crate::shell::GroupScope]) -> String {
+        let mut content =
+            crate::shell::posix_scope_function(scopes, |alias| self.generate_alias_line(alias));
+        content.push_str(
+            "[[ $PROMPT_COMMAND == *__alx_scope* ]] || PROMPT_COMMAND=\"__alx_scope${PROMPT_COMMAND:+;$PROMPT_COMMAND}\"\n__alx_scope\n",
+        );
         content
     }
 
@@ -67,6 +99,41 @@ impl ShellHandler for BashHandler {
         Ok(home.join(".bashrc"))
     }
 
+    fn login_config_file_path(&self) -> Result<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            crate::error::AlxError::ConfigError("Could not find home directory".to_string())
+        })?;
+        Ok(home.join(".bash_profile"))
+    }
+
+    fn autoload_file_path(&self) -> Result<Option<PathBuf>> {
+        Ok(None)
+    }
+
+    fn history_file_path(&self) -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os("HISTFILE").filter(|p| !p.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+        let home = dirs::home_dir().ok_or_else(|| {
+            crate::error::AlxError::ConfigError("Could not find home directory".to_string())
+        })?;
+        Ok(home.join(".bash_history"))
+    }
+
+    // With HISTTIMEFORMAT set, each command follows a "#<unix time>" line
+    fn parse_history(&self, content: &str) -> Vec<String> {
+        content
+            .lines()
+            .filter(|line| {
+                let timestamp = line
+                    .strip_prefix('#')
+                    .is_some_and(|t| !t.is_empty() && t.bytes().all(|b| b.is_ascii_digit()));
+                !timestamp && !line.trim().is_empty()
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
     fn parse_aliases_from_file(&self, path: &std::path::Path) -> Result<Vec<(String, String)>> {
         use std::fs;
 
@@ -104,7 +171,7 @@ impl ShellHandler for BashHandler {
 }
 
 impl BashHandler {
-    fn parse_alias_line(line: &str) -> Option<(String, String)> {
+    pub fn parse_alias_line(line: &str) -> Option<(String, String)> {
         let trimmed = line.trim();
 
         // Skip comments and empty lines
@@ -53,7 +53,7 @@ impl ShellDetector {
         )))
     }
 
-    fn parse_shell_name(name: &str) -> Result<ShellType> {
+    pub fn parse_shell_name(name: &str) -> Result<ShellType> {
         if !Self::is_supported(name) {
             return Err(AlxError::UnsupportedShell(name.to_string()));
         }
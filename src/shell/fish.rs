@@ -23,40 +23,117 @@ impl ShellHandler for FishHandler {
     }
 
     fn generate_aliases_file(&self, aliases: &[&Alias]) -> String {
-        let mut content = String::new();
-        content.push_str("# Generated by alx - DO NOT EDIT MANUALLY\n");
-        content.push_str("# This file is automatically generated and will be overwritten\n\n");
+        super::render_grouped(aliases, |alias| self.generate_alias_line(alias))
+    }
 
-        if aliases.is_empty() {
-            content.push_str("# No aliases configured\n");
-            return content;
+    fn cleanup(&self, names: &[String]) -> String {
+        let mut content = String::from("# Aliases removed since they were last synced\n");
+        for name in names {
+            content.push_str(&format!("functions -e {}\n", name));
         }
+        content
+    }
 
-        // Group by category
-        let mut grouped: std::collections::HashMap<String, Vec<&Alias>> =
-            std::collections::HashMap::new();
-
+    fn descriptions(&self, aliases: &[&Alias]) -> String {
+        let mut content = String::new();
         for alias in aliases {
-            let group = alias.group.as_deref().unwrap_or("general");
-            grouped.entry(group.to_string()).or_default().push(alias);
+            if let Some(desc) = &alias.description {
+                content.push_str(&format!(
+                    "functions -d '{}' {}\n",
+                    Self::escape_command(desc),
+                    alias.name
+                ));
+            }
+        }
+        if !content.is_empty() {
+            content.insert_str(0, &format!("{}\n", crate::shell::DESCRIPTIONS_HEADING));
         }
+        content
+    }
 
-        let mut groups: Vec<_> = grouped.keys().collect();
-        groups.sort();
+    fn usage_hook(&self, log: &std::path::Path) -> String {
+        // fish aliases are functions
+        format!(
+            r#"# Record alias usage for 'alx top'
+function __alx_track --on-event fish_preexec
+    set -l word (string split -f1 ' ' -- $argv[1])
+    if test -n "$word"; and functions -q -- $word
+        echo (date +%s) $word >> '{log}'
+    end
+end
+"#,
+            log = Self::escape_command(&log.to_string_lossy())
+        )
+    }
 
-        for group in groups {
-            let group_aliases = grouped.get(group).unwrap();
-            content.push_str(&format!("# {} aliases\n", group));
+    fn reload_function(&self) -> String {
+        r#"# Load this file again after alx changes it
+set -g __alx_file (status --current-filename)
+function alx-reload
+    source $__alx_file
+end
+"#
+        .to_string()
+    }
 
-            for alias in group_aliases {
-                if let Some(desc) = &alias.description {
-                    content.push_str(&format!("# {}\n", desc));
-                }
-                content.push_str(&format!("{}\n", self.generate_alias_line(alias)));
+    fn fingerprint_line(&self, fingerprint: &str) -> String {
+        format!("set -g __alx_fingerprint {}\n", fingerprint)
+    }
+
+    fn stale_check(
+        &self,
+        fingerprint_file: &std::path::Path,
+        aliases_file: &std::path::Path,
+    ) -> String {
+        format!(
+            r#"# Say when the aliases drift from the store (alx shell-init)
+function __alx_stale --on-event fish_prompt
+    test -r '{fingerprint}'; and test -r '{aliases}'; or return
+    read -l store < '{fingerprint}'
+    read -l line < '{aliases}'
+    set -l file (string split ' ' -- "$line")[-1]
+    set -l state "$store:$file:$__alx_fingerprint"
+    test -z "$store"; or test "$state" = "$__alx_stale_seen"; and return
+    set -g __alx_stale_seen $state
+    if test "$store" != "$file"
+        echo "alx: aliases changed; run 'alx sync'" >&2
+    else if test "$file" != "$__alx_fingerprint"
+        echo "alx: aliases were synced; run alx-reload to pick them up" >&2
+    end
+end
+"#,
+            fingerprint = Self::escape_command(&fingerprint_file.to_string_lossy()),
+            aliases = Self::escape_command(&aliases_file.to_string_lossy()),
+        )
+    }
+
+    fn scope_hook(&self, scopes: &[crate::shell::GroupScope]) -> String {
+        let mut content = String::from(
+            "# Groups scoped to directories (alx scope)\nfunction __alx_scope --on-variable PWD\n",
+        );
+        for scope in scopes {
+            let patterns: Vec<String> = scope
+                .dirs
+                .iter()
+                .map(|dir| format!("'{}/*'", Self::escape_command(&dir.to_string_lossy())))
+                .collect();
+            content.push_str(&format!(
+                "    switch \"$PWD/\"\n        case {}\n",
+                patterns.join(" ")
+            ));
+            for alias in &scope.aliases {
+                content.push_str(&format!(
+                    "            {}\n",
+                    self.generate_alias_line(alias)
+                ));
             }
-            content.push('\n');
+            content.push_str("        case '*'\n");
+            for alias in &scope.aliases {
+                content.push_str(&format!("            functions -e {}\n", alias.name));
+            }
+            content.push_str("    end\n");
         }
-
+        content.push_str("end\n__alx_scope\n");
         content
     }
 
@@ -67,6 +144,50 @@ impl ShellHandler for FishHandler {
         Ok(home.join(".config/fish/config.fish"))
     }
 
+    fn login_config_file_path(&self) -> Result<PathBuf> {
+        self.config_file_path()
+    }
+
+    // fish sources every file in conf.d before config.fish
+    fn autoload_file_path(&self) -> Result<Option<PathBuf>> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            crate::error::AlxError::ConfigError("Could not find home directory".to_string())
+        })?;
+        Ok(Some(home.join(".config/fish/conf.d/alx.fish")))
+    }
+
+    fn history_file_path(&self) -> Result<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            crate::error::AlxError::ConfigError("Could not find home directory".to_string())
+        })?;
+        Ok(home.join(".local/share/fish/fish_history"))
+    }
+
+    // Each entry is "- cmd: <command>" followed by indented "when:" and
+    // "paths:" lines, with newlines and backslashes escaped
+    fn parse_history(&self, content: &str) -> Vec<String> {
+        content
+            .lines()
+            .filter_map(|line| line.strip_prefix("- cmd: "))
+            .map(|command| {
+                let mut unescaped = String::new();
+                let mut chars = command.chars();
+                while let Some(c) = chars.next() {
+                    match (c, chars.clone().next()) {
+                        ('\\', Some('n')) => unescaped.push('\n'),
+                        ('\\', Some('\\')) => unescaped.push('\\'),
+                        _ => {
+                            unescaped.push(c);
+                            continue;
+                        }
+                    }
+                    chars.next();
+                }
+                unescaped
+            })
+            .collect()
+    }
+
     fn parse_aliases_from_file(&self, path: &std::path::Path) -> Result<Vec<(String, String)>> {
         use std::fs;
 
@@ -169,4 +290,13 @@ mod tests {
         assert!(content.contains("alias ll 'ls -la'"));
         assert!(content.contains("# List all files"));
     }
+
+    #[test]
+    fn test_parse_history() {
+        let history = "- cmd: git status\n  when: 1700000000\n- cmd: printf a\\\\nb\\nc\n  when: 1700000001\n  paths:\n    - a\n";
+        assert_eq!(
+            FishHandler::new().parse_history(history),
+            vec!["git status", "printf a\\nb\nc"]
+        );
+    }
 }
@@ -0,0 +1,72 @@
+use crate::shell::ShellHandler;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+// The shell's history file and the command lines in it, or None when there
+// is no history to read
+pub fn load(handler: &dyn ShellHandler) -> Option<(PathBuf, Vec<String>)> {
+    let path = handler.history_file_path().ok()?;
+    // zsh writes some characters in its own metafied encoding
+    let content = fs::read(&path).ok()?;
+    let commands = handler.parse_history(&String::from_utf8_lossy(&content));
+    Some((path, commands))
+}
+
+// How often each (name, command) alias shows up in `history`: as the first
+// word of a command line, or as the start of one that types the command out
+pub fn count_uses(history: &[String], aliases: &[(&str, &str)]) -> Vec<usize> {
+    let mut counts = vec![0; aliases.len()];
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, (name, _)) in aliases.iter().enumerate() {
+        by_name.entry(name).or_default().push(i);
+    }
+
+    for line in history {
+        let line = line.trim_start();
+        let first = line.split_whitespace().next().unwrap_or_default();
+        for &i in by_name.get(first).into_iter().flatten() {
+            counts[i] += 1;
+        }
+        for (i, (name, command)) in aliases.iter().enumerate() {
+            if *name != first && starts_with_words(line, command.trim()) {
+                counts[i] += 1;
+            }
+        }
+    }
+    counts
+}
+
+// Whether `line` starts with `prefix` followed by the end of a word
+fn starts_with_words(line: &str, prefix: &str) -> bool {
+    !prefix.is_empty()
+        && line
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_uses() {
+        let history: Vec<String> = [
+            "gs",
+            "git status",
+            "git status --short",
+            "git stash",
+            "  gs -s",
+            "ls -la /tmp",
+            "lsblk",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let counts = count_uses(
+            &history,
+            &[("gs", "git status"), ("ll", "ls -la"), ("l", "ls")],
+        );
+        assert_eq!(counts, vec![4, 1, 1]);
+    }
+}
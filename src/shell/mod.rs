@@ -1,12 +1,20 @@
 pub mod bash;
 pub mod detector;
 pub mod fish;
+pub mod history;
+pub mod omz;
+pub mod rc;
+pub mod registry;
+pub mod sources;
+pub mod syntax;
 pub mod zsh;
 
 use crate::alias::Alias;
 use crate::error::Result;
+use std::collections::BTreeMap;
+use std::fmt::Write;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ShellType {
     Bash,
     Zsh,
@@ -23,9 +31,180 @@ impl ShellType {
     }
 }
 
+// Quote a word for POSIX shells, leaving it bare when that is already safe
+pub fn quote(word: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_-./:@=,+%".contains(c);
+    if !word.is_empty() && word.chars().all(safe) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+// The generated file shared by all shells: a header, then the aliases under
+// one "# <group> aliases" heading per group, in group order
+pub(crate) fn render_grouped(aliases: &[&Alias], line: impl Fn(&Alias) -> String) -> String {
+    let mut content = String::new();
+    content.push_str("# Generated by alx - DO NOT EDIT MANUALLY\n");
+    content.push_str("# This file is automatically generated and will be overwritten\n\n");
+
+    if aliases.is_empty() {
+        content.push_str("# No aliases configured\n");
+        return content;
+    }
+
+    let mut grouped: BTreeMap<&str, Vec<&Alias>> = BTreeMap::new();
+    let mut size = 0;
+    for alias in aliases {
+        let group = alias.group.as_deref().unwrap_or("general");
+        grouped.entry(group).or_default().push(alias);
+        // Room for the alias line with its quoting, and the description
+        size += alias.name.len() + alias.command.len() + 16;
+        size += alias.description.as_ref().map_or(0, |d| d.len() + 3);
+    }
+    content.reserve(size + grouped.len() * 32);
+
+    for (group, group_aliases) in grouped {
+        let _ = writeln!(content, "# {} aliases", group);
+        for alias in group_aliases {
+            if let Some(desc) = &alias.description {
+                let _ = writeln!(content, "# {}", desc);
+            }
+            content.push_str(&line(alias));
+            content.push('\n');
+        }
+        content.push('\n');
+    }
+
+    content
+}
+
+// The heading of the block that hands alias descriptions to completion menus,
+// written at the end of the generated file
+pub(crate) const DESCRIPTIONS_HEADING: &str = "# Descriptions for completion menus";
+
+// The body of `__alx_stale` for bash and zsh. The fingerprints are read with
+// the `read` builtin, so the check never forks.
+pub(crate) fn posix_stale_function(
+    fingerprint_file: &std::path::Path,
+    aliases_file: &std::path::Path,
+) -> String {
+    format!(
+        r#"# Say when the aliases drift from the store (alx shell-init)
+__alx_stale() {{
+  local store= file=
+  {{ read -r store < {fingerprint}; read -r file < {aliases}; }} 2>/dev/null
+  file=${{file##*=}}
+  local state="$store:$file:$__alx_fingerprint"
+  [[ -z $store || $state == "$__alx_stale_seen" ]] && return
+  __alx_stale_seen=$state
+  if [[ $store != "$file" ]]; then
+    echo "alx: aliases changed; run 'alx sync'" >&2
+  elif [[ $file != "$__alx_fingerprint" ]]; then
+    echo "alx: aliases were synced; run alx-reload to pick them up" >&2
+  fi
+}}
+"#,
+        fingerprint = quote(&fingerprint_file.to_string_lossy()),
+        aliases = quote(&aliases_file.to_string_lossy()),
+    )
+}
+
+// A group whose aliases only exist inside `dirs` and their subdirectories
+pub struct GroupScope<'a> {
+    pub dirs: Vec<std::path::PathBuf>,
+    pub aliases: Vec<&'a Alias>,
+}
+
+// The body of `__alx_scope` for bash and zsh: one case per group that
+// defines its aliases inside the group's directories and removes them
+// everywhere else
+pub(crate) fn posix_scope_function(
+    scopes: &[GroupScope],
+    line: impl Fn(&Alias) -> String,
+) -> String {
+    let mut content = String::from("# Groups scoped to directories (alx scope)\n__alx_scope() {\n");
+    for scope in scopes {
+        let patterns: Vec<String> = scope
+            .dirs
+            .iter()
+            .map(|dir| format!("{}*", quote(&format!("{}/", dir.display()))))
+            .collect();
+        let _ = writeln!(content, "  case \"$PWD/\" in\n    {})", patterns.join("|"));
+        for alias in &scope.aliases {
+            let _ = writeln!(content, "      {}", line(alias));
+        }
+        content.push_str("      ;;\n    *)\n");
+        for alias in &scope.aliases {
+            let _ = writeln!(content, "      unalias {} 2>/dev/null", alias.name);
+        }
+        content.push_str("      ;;\n  esac\n");
+    }
+    content.push_str("}\n");
+    content
+}
+
+/// Generates and parses alias definitions for one shell.
+///
+/// ```
+/// use alx::alias::Alias;
+/// use alx::shell::ShellHandler;
+/// use alx::shell::zsh::ZshHandler;
+///
+/// let alias = Alias::new("gs".to_string(), "git status".to_string());
+/// assert_eq!(ZshHandler::new().generate_alias_line(&alias), "alias gs='git status'");
+/// ```
 pub trait ShellHandler {
     fn generate_alias_line(&self, alias: &Alias) -> String;
     fn generate_aliases_file(&self, aliases: &[&Alias]) -> String;
+    // Shell code that appends "<unix time> <alias>" to `log` whenever an
+    // alias starts a command line
+    fn usage_hook(&self, log: &std::path::Path) -> String;
+    // Shell code that switches scoped groups on and off as the working
+    // directory changes
+    fn scope_hook(&self, scopes: &[GroupScope]) -> String;
+    // Shell code that drops aliases alx no longer defines from shells that
+    // loaded them earlier
+    fn cleanup(&self, names: &[String]) -> String;
+    // Shell code that shows the aliases' descriptions in completion menus,
+    // empty when none has a description or the shell can't show them
+    fn descriptions(&self, aliases: &[&Alias]) -> String;
+    // Shell code defining alx-reload, which sources the generated file again
+    // so a running shell picks up changes
+    fn reload_function(&self) -> String;
+    // The first line of the generated file, setting __alx_fingerprint to the
+    // fingerprint of the store it was generated from
+    fn fingerprint_line(&self, fingerprint: &str) -> String;
+    // Shell code for `alx shell-init` that prints a notice before the prompt
+    // once the store, the generated file and the loaded aliases drift apart
+    fn stale_check(
+        &self,
+        fingerprint_file: &std::path::Path,
+        aliases_file: &std::path::Path,
+    ) -> String;
     fn config_file_path(&self) -> Result<std::path::PathBuf>;
+    // The file login shells read instead, such as the ones macOS terminals
+    // open; the same as config_file_path for shells that read one file
+    fn login_config_file_path(&self) -> Result<std::path::PathBuf>;
+    // A file the shell loads on its own at startup, where the generated
+    // aliases can go instead of being sourced from the rc file
+    fn autoload_file_path(&self) -> Result<Option<std::path::PathBuf>>;
     fn parse_aliases_from_file(&self, path: &std::path::Path) -> Result<Vec<(String, String)>>;
+    // Where the shell saves its command history
+    fn history_file_path(&self) -> Result<std::path::PathBuf>;
+    // The command lines in a history file, oldest first
+    fn parse_history(&self, content: &str) -> Vec<String>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("gs"), "gs");
+        assert_eq!(quote("git status"), "'git status'");
+        assert_eq!(quote("echo 'hi'"), r"'echo '\''hi'\'''");
+        assert_eq!(quote(""), "''");
+    }
 }
@@ -0,0 +1,99 @@
+use crate::error::{AlxError, Result};
+use std::env;
+use std::path::PathBuf;
+
+// Root of the oh-my-zsh installation ($ZSH, falling back to ~/.oh-my-zsh)
+pub fn omz_dir() -> Result<PathBuf> {
+    if let Ok(dir) = env::var("ZSH")
+        && !dir.is_empty()
+    {
+        return Ok(PathBuf::from(dir));
+    }
+
+    let home = dirs::home_dir()
+        .ok_or_else(|| AlxError::ConfigError("Could not find home directory".to_string()))?;
+    Ok(home.join(".oh-my-zsh"))
+}
+
+// Locate a plugin's definition file, preferring $ZSH_CUSTOM overrides the
+// same way oh-my-zsh does
+pub fn plugin_file(plugin: &str) -> Result<PathBuf> {
+    let omz = omz_dir()?;
+    let custom = env::var("ZSH_CUSTOM")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| omz.join("custom"));
+
+    let file_name = format!("{}.plugin.zsh", plugin);
+    [custom.join("plugins"), omz.join("plugins")]
+        .into_iter()
+        .map(|dir| dir.join(plugin).join(&file_name))
+        .find(|path| path.exists())
+        .ok_or_else(|| {
+            AlxError::ConfigError(format!(
+                "oh-my-zsh plugin '{}' not found in {:?}",
+                plugin, omz
+            ))
+        })
+}
+
+// Read the plugin list from a `plugins=(...)` assignment, which may span
+// several lines
+pub fn enabled_plugins(zshrc: &str) -> Vec<String> {
+    let mut plugins = Vec::new();
+    let mut in_list = false;
+
+    for line in zshrc.lines() {
+        let mut rest = line.trim();
+
+        if !in_list {
+            match rest.strip_prefix("plugins=(") {
+                Some(list) => {
+                    in_list = true;
+                    rest = list;
+                }
+                None => continue,
+            }
+        }
+
+        let (list, closed) = match rest.split_once(')') {
+            Some((list, _)) => (list, true),
+            None => (rest, false),
+        };
+
+        for word in list.split_whitespace() {
+            if word.starts_with('#') {
+                break;
+            }
+            plugins.push(word.to_string());
+        }
+
+        if closed {
+            in_list = false;
+        }
+    }
+
+    plugins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enabled_plugins_single_line() {
+        let zshrc =
+            "export ZSH=\"$HOME/.oh-my-zsh\"\nplugins=(git docker)\nsource $ZSH/oh-my-zsh.sh\n";
+        assert_eq!(enabled_plugins(zshrc), vec!["git", "docker"]);
+    }
+
+    #[test]
+    fn test_enabled_plugins_multi_line() {
+        let zshrc = "plugins=(\n  git\n  kubectl # k8s\n  z\n)\n# plugins=(ignored)\n";
+        assert_eq!(enabled_plugins(zshrc), vec!["git", "kubectl", "z"]);
+    }
+
+    #[test]
+    fn test_enabled_plugins_missing() {
+        assert!(enabled_plugins("alias ll='ls -la'\n").is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use super::ShellType;
+
+// The comment `alx init` writes above the source line in the rc file
+pub const MARKER: &str = "# alx - alias manager";
+
+// The line that loads the generated aliases file at `path`. bash also reads
+// .bashrc for scripts run over ssh, so there the line only runs in interactive
+// shells, and in login shells too with `login_shells`.
+pub fn source_line(shell_type: ShellType, path: &str, login_shells: bool) -> String {
+    match shell_type {
+        ShellType::Fish => format!("source \"{}\"", path),
+        ShellType::Bash if login_shells => format!(
+            "{{ [[ $- == *i* ]] || shopt -q login_shell; }} && [ -f \"{0}\" ] && source \"{0}\"",
+            path
+        ),
+        ShellType::Bash => format!("[[ $- == *i* ]] && [ -f \"{0}\" ] && source \"{0}\"", path),
+        ShellType::Zsh => format!("[ -f \"{0}\" ] && source \"{0}\"", path),
+    }
+}
+
+// The block appended to an rc file that doesn't load alx yet
+pub fn block(source_line: &str) -> String {
+    format!("\n{}\n{}\n", MARKER, source_line)
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Repair {
+    pub content: String,
+    pub changes: Vec<String>,
+}
+
+// Make `content` load alx through exactly one managed block with
+// `source_line`: the first block's source line is updated, later blocks and
+// stray copies of the line are dropped, and a block is added when none exists
+pub fn repair(content: &str, source_line: &str) -> Repair {
+    let mut repair = Repair::default();
+    let has_block = content.lines().any(|l| l.trim() == MARKER);
+    let mut kept: Vec<&str> = Vec::new();
+    let mut seen_block = false;
+
+    let mut lines = content.lines().peekable();
+    while let Some(line) = lines.next() {
+        if line.trim() == MARKER {
+            let old = lines.next_if(|next| is_source_line(next));
+            if seen_block {
+                // Take the blank line written before the block with it
+                if kept.last().is_some_and(|l| l.trim().is_empty()) {
+                    kept.pop();
+                }
+                repair
+                    .changes
+                    .push("Removed a duplicate alx block".to_string());
+                continue;
+            }
+            seen_block = true;
+            kept.push(line);
+            kept.push(source_line);
+            match old {
+                Some(old) if old.trim() == source_line => {}
+                Some(old) => repair
+                    .changes
+                    .push(format!("Updated the source line (was: {})", old.trim())),
+                None => repair
+                    .changes
+                    .push("Added the missing source line".to_string()),
+            }
+        } else if has_block && line.trim() == source_line {
+            repair
+                .changes
+                .push("Removed a duplicate source line".to_string());
+        } else {
+            kept.push(line);
+        }
+    }
+
+    repair.content = kept.join("\n");
+    if !repair.content.is_empty() {
+        repair.content.push('\n');
+    }
+    if !seen_block && !content.lines().any(|l| l.trim() == source_line) {
+        repair.content.push_str(&block(source_line));
+        repair.changes.push("Added the alx block".to_string());
+    }
+    repair
+}
+
+fn is_source_line(line: &str) -> bool {
+    let line = line.trim();
+    line.contains("source ") || line.starts_with(". ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repair() {
+        let line = source_line(ShellType::Bash, "$HOME/.config/alx/shell/aliases.sh", false);
+        let old = source_line(ShellType::Zsh, "$HOME/.alx/shell/aliases.sh", false);
+
+        let healthy = format!("export A=1\n{}", block(&line));
+        let fixed = repair(&healthy, &line);
+        assert!(fixed.changes.is_empty());
+        assert_eq!(fixed.content, healthy);
+
+        let broken = format!(
+            "export A=1\n{}{}\n{}\nalias ll='ls -la'\n",
+            block(&old),
+            block(&line),
+            line
+        );
+        let fixed = repair(&broken, &line);
+        assert_eq!(fixed.changes.len(), 3);
+        assert_eq!(
+            fixed.content,
+            format!("export A=1\n{}\nalias ll='ls -la'\n", block(&line))
+        );
+
+        let fixed = repair("export A=1\n", &line);
+        assert_eq!(fixed.content, healthy);
+        assert_eq!(fixed.changes, vec!["Added the alx block"]);
+
+        // A source line added by hand is left alone
+        let manual = format!("{}\n", line);
+        assert!(repair(&manual, &line).changes.is_empty());
+    }
+
+    #[test]
+    fn test_source_line() {
+        let path = "$HOME/.config/alx/shell/aliases.sh";
+        assert_eq!(
+            source_line(ShellType::Zsh, path, false),
+            format!("[ -f \"{0}\" ] && source \"{0}\"", path)
+        );
+        assert!(source_line(ShellType::Bash, path, false).starts_with("[[ $- == *i* ]] && "));
+        assert!(source_line(ShellType::Bash, path, true).contains("|| shopt -q login_shell;"));
+    }
+}
@@ -0,0 +1,46 @@
+use super::bash::BashHandler;
+use super::fish::FishHandler;
+use super::zsh::ZshHandler;
+use super::{ShellHandler, ShellType};
+use std::collections::HashMap;
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+// Builds the handler for one shell
+pub type HandlerFactory = fn() -> Box<dyn ShellHandler>;
+
+fn registry() -> &'static RwLock<HashMap<ShellType, HandlerFactory>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<ShellType, HandlerFactory>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut factories: HashMap<ShellType, HandlerFactory> = HashMap::new();
+        factories.insert(ShellType::Bash, || Box::new(BashHandler::new()));
+        factories.insert(ShellType::Zsh, || Box::new(ZshHandler::new()));
+        factories.insert(ShellType::Fish, || Box::new(FishHandler::new()));
+        RwLock::new(factories)
+    })
+}
+
+/// Use `factory` to build the handler for `shell_type` from now on, in place
+/// of the built-in one.
+///
+/// ```
+/// use alx::alias::Alias;
+/// use alx::shell::ShellType;
+/// use alx::shell::bash::BashHandler;
+/// use alx::shell::registry;
+///
+/// registry::register(ShellType::Bash, || Box::new(BashHandler::new()));
+/// let alias = Alias::new("gs".to_string(), "git status".to_string());
+/// let handler = registry::handler(ShellType::Bash);
+/// assert_eq!(handler.generate_alias_line(&alias), "alias gs='git status'");
+/// ```
+pub fn register(shell_type: ShellType, factory: HandlerFactory) {
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(shell_type, factory);
+}
+
+pub fn handler(shell_type: ShellType) -> Box<dyn ShellHandler> {
+    let factory = registry().read().unwrap_or_else(PoisonError::into_inner)[&shell_type];
+    factory()
+}
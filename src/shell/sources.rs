@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Collect `path` and every file it sources, depth first, following nested
+// `source`/`.` lines up to `max_depth` levels. Files that don't exist or were
+// already visited are skipped.
+pub fn sourced_files(path: &Path, max_depth: usize, exclude: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    for excluded in exclude {
+        visited.insert(canonical(excluded));
+    }
+
+    collect(path, 0, max_depth, &mut visited, &mut files);
+    files
+}
+
+fn collect(
+    path: &Path,
+    depth: usize,
+    max_depth: usize,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) {
+    if !path.is_file() || !visited.insert(canonical(path)) {
+        return;
+    }
+    files.push(path.to_path_buf());
+
+    if depth >= max_depth {
+        return;
+    }
+
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    let base = path.parent().unwrap_or(Path::new("."));
+    for target in source_targets(&content, base) {
+        collect(&target, depth + 1, max_depth, visited, files);
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Find files loaded with `source file` or `. file`, including the guarded
+// forms rc files commonly use (`[ -f x ] && . x`, `if ...; then . x; fi`)
+fn source_targets(content: &str, base: &Path) -> Vec<PathBuf> {
+    let mut targets = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+
+        for segment in line.split(['&', '|', ';']) {
+            let segment = segment.trim();
+            let segment = segment.strip_prefix("then ").unwrap_or(segment).trim();
+
+            let Some(rest) = segment
+                .strip_prefix("source ")
+                .or_else(|| segment.strip_prefix(". "))
+            else {
+                continue;
+            };
+
+            let Some(word) = rest.split_whitespace().next() else {
+                continue;
+            };
+            if let Some(path) = resolve(word, base) {
+                targets.push(path);
+            }
+        }
+    }
+
+    targets
+}
+
+// Expand home and zsh config dir references; anything else dynamic can't be
+// followed statically
+fn resolve(word: &str, base: &Path) -> Option<PathBuf> {
+    let word = word.trim_matches(|c| c == '"' || c == '\'');
+    let home = dirs::home_dir()?;
+    let zdotdir = std::env::var_os("ZDOTDIR").filter(|d| !d.is_empty());
+
+    let path = if let Some(rest) = word.strip_prefix("~/") {
+        home.join(rest)
+    } else if let Some(rest) = word
+        .strip_prefix("$HOME/")
+        .or_else(|| word.strip_prefix("${HOME}/"))
+    {
+        home.join(rest)
+    } else if let Some(rest) = word.strip_prefix("${ZDOTDIR:-$HOME}/") {
+        zdotdir.map_or(home, PathBuf::from).join(rest)
+    } else if let (Some(rest), Some(dir)) = (
+        word.strip_prefix("$ZDOTDIR/")
+            .or_else(|| word.strip_prefix("${ZDOTDIR}/")),
+        zdotdir,
+    ) {
+        PathBuf::from(dir).join(rest)
+    } else {
+        PathBuf::from(word)
+    };
+
+    let text = path.to_string_lossy();
+    if text.contains('$') || text.contains('*') || text.contains('`') {
+        return None;
+    }
+
+    Some(if path.is_absolute() {
+        path
+    } else {
+        base.join(path)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_targets() {
+        let home = dirs::home_dir().unwrap();
+        let zdotdir = std::env::var_os("ZDOTDIR")
+            .filter(|d| !d.is_empty())
+            .map_or(home.clone(), PathBuf::from);
+        let base = Path::new("/etc");
+        let content = r#"
+source ~/.bash_aliases
+[ -f "$HOME/.aliases" ] && . "$HOME/.aliases"
+if [ -f ~/.work ]; then . ~/.work; fi
+# source ~/.commented
+source "$ZSH/oh-my-zsh.sh"
+source ${ZDOTDIR:-$HOME}/.zsh_aliases
+. local.sh
+"#;
+
+        assert_eq!(
+            source_targets(content, base),
+            vec![
+                home.join(".bash_aliases"),
+                home.join(".aliases"),
+                home.join(".work"),
+                zdotdir.join(".zsh_aliases"),
+                PathBuf::from("/etc/local.sh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sourced_files_depth_and_cycles() {
+        let temp = tempfile::tempdir().unwrap();
+        let rc = temp.path().join("rc");
+        let first = temp.path().join("first.sh");
+        let second = temp.path().join("second.sh");
+        let generated = temp.path().join("generated.sh");
+
+        fs::write(&rc, "source first.sh\nsource generated.sh\n").unwrap();
+        fs::write(&first, "alias a='a'\n. second.sh\n").unwrap();
+        fs::write(&second, "alias b='b'\nsource rc\n").unwrap();
+        fs::write(&generated, "alias c='c'\n").unwrap();
+
+        let files = sourced_files(&rc, 3, std::slice::from_ref(&generated));
+        assert_eq!(files, vec![rc.clone(), first.clone(), second]);
+
+        let files = sourced_files(&rc, 1, &[]);
+        assert_eq!(files, vec![rc, first, generated]);
+    }
+}
@@ -0,0 +1,48 @@
+use super::ShellType;
+use crate::error::{AlxError, Result};
+use std::io::ErrorKind;
+use std::process::Command;
+
+// Parse `command` with the shell itself without running it. Returns Ok(false)
+// when the shell isn't installed, so the check can be skipped.
+pub fn check(shell_type: ShellType, command: &str) -> Result<bool> {
+    let no_execute = match shell_type {
+        ShellType::Bash | ShellType::Zsh => "-n",
+        ShellType::Fish => "--no-execute",
+    };
+    let program = shell_type.as_str();
+
+    let output = match Command::new(program)
+        .args([no_execute, "-c", command])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AlxError::InvalidCommand(format!(
+            "{} reports a syntax error: {}",
+            program,
+            stderr.trim_end()
+        )));
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        if !check(ShellType::Bash, "true").unwrap() {
+            return;
+        }
+        assert!(check(ShellType::Bash, "git log --format='%h %s' | head").unwrap());
+        assert!(check(ShellType::Bash, "echo 'unbalanced").is_err());
+        assert!(check(ShellType::Bash, "if true; then echo").is_err());
+    }
+}
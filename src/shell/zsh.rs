@@ -10,6 +10,16 @@ impl ZshHandler {
         Self
     }
 
+    // Where zsh reads its startup files: $ZDOTDIR when set, else $HOME
+    pub fn dot_dir() -> Result<PathBuf> {
+        if let Some(dir) = std::env::var_os("ZDOTDIR").filter(|d| !d.is_empty()) {
+            return Ok(PathBuf::from(dir));
+        }
+        dirs::home_dir().ok_or_else(|| {
+            crate::error::AlxError::ConfigError("Could not find home directory".to_string())
+        })
+    }
+
     fn escape_command(command: &str) -> String {
         // Zsh uses the same escaping as Bash for aliases
         command.replace('\'', r"'\''")
@@ -23,48 +33,124 @@ impl ShellHandler for ZshHandler {
     }
 
     fn generate_aliases_file(&self, aliases: &[&Alias]) -> String {
-        let mut content = String::new();
-        content.push_str("# Generated by alx - DO NOT EDIT MANUALLY\n");
-        content.push_str("# This file is automatically generated and will be overwritten\n\n");
+        super::render_grouped(aliases, |alias| self.generate_alias_line(alias))
+    }
 
-        if aliases.is_empty() {
-            content.push_str("# No aliases configured\n");
-            return content;
+    fn cleanup(&self, names: &[String]) -> String {
+        let mut content = String::from("# Aliases removed since they were last synced\n");
+        for name in names {
+            content.push_str(&format!("unalias {} 2>/dev/null\n", name));
         }
+        content
+    }
 
-        // Group by category
-        let mut grouped: std::collections::HashMap<String, Vec<&Alias>> =
-            std::collections::HashMap::new();
-
-        for alias in aliases {
-            let group = alias.group.as_deref().unwrap_or("general");
-            grouped.entry(group.to_string()).or_default().push(alias);
+    fn descriptions(&self, aliases: &[&Alias]) -> String {
+        // Offered next to the aliases themselves, as "name:description"
+        let values: Vec<String> = aliases
+            .iter()
+            .filter_map(|alias| {
+                let desc = alias.description.as_ref()?;
+                let value = format!("{}:{}", alias.name.replace(':', r"\:"), desc);
+                Some(crate::shell::quote(&value))
+            })
+            .collect();
+        if values.is_empty() {
+            return String::new();
         }
+        format!(
+            "{}\nzstyle ':completion:*:aliases' fake {}\n",
+            crate::shell::DESCRIPTIONS_HEADING,
+            values.join(" ")
+        )
+    }
 
-        let mut groups: Vec<_> = grouped.keys().collect();
-        groups.sort();
+    fn usage_hook(&self, log: &std::path::Path) -> String {
+        format!(
+            r#"# Record alias usage for 'alx top'
+zmodload zsh/datetime
+__alx_track() {{
+  local word=${{${{(z)1}}[1]}}
+  if [[ -n $word && -n ${{aliases[$word]}} ]]; then
+    print -r -- "$EPOCHSECONDS $word" >> {log}
+  fi
+}}
+autoload -Uz add-zsh-hook
+add-zsh-hook preexec __alx_track
+"#,
+            log = crate::shell::quote(&log.to_string_lossy())
+        )
+    }
 
-        for group in groups {
-            let group_aliases = grouped.get(group).unwrap();
-            content.push_str(&format!("# {} aliases\n", group));
+    fn reload_function(&self) -> String {
+        r#"# Load this file again after alx changes it
+__alx_file=${(%):-%x}
+alx-reload() { . "$__alx_file" }
+"#
+        .to_string()
+    }
 
-            for alias in group_aliases {
-                if let Some(desc) = &alias.description {
-                    content.push_str(&format!("# {}\n", desc));
-                }
-                content.push_str(&format!("{}\n", self.generate_alias_line(alias)));
-            }
-            content.push('\n');
-        }
+    fn fingerprint_line(&self, fingerprint: &str) -> String {
+        format!("__alx_fingerprint={}\n", fingerprint)
+    }
 
+    fn stale_check(
+        &self,
+        fingerprint_file: &std::path::Path,
+        aliases_file: &std::path::Path,
+    ) -> String {
+        let mut content = crate::shell::posix_stale_function(fingerprint_file, aliases_file);
+        content.push_str("autoload -Uz add-zsh-hook\nadd-zsh-hook precmd __alx_stale\n");
+        content
+    }
+
+    fn scope_hook(&self, scopes: &[crate::shell::GroupScope]) -> String {
+        let mut content =
+            crate::shell::posix_scope_function(scopes, |alias| self.generate_alias_line(alias));
+        content
+            .push_str("autoload -Uz add-zsh-hook\nadd-zsh-hook chpwd __alx_scope\n__alx_scope\n");
         content
     }
 
     fn config_file_path(&self) -> Result<PathBuf> {
-        let home = dirs::home_dir().ok_or_else(|| {
-            crate::error::AlxError::ConfigError("Could not find home directory".to_string())
-        })?;
-        Ok(home.join(".zshrc"))
+        Ok(Self::dot_dir()?.join(".zshrc"))
+    }
+
+    fn login_config_file_path(&self) -> Result<PathBuf> {
+        Ok(Self::dot_dir()?.join(".zprofile"))
+    }
+
+    fn autoload_file_path(&self) -> Result<Option<PathBuf>> {
+        Ok(None)
+    }
+
+    fn history_file_path(&self) -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os("HISTFILE").filter(|p| !p.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+        Ok(Self::dot_dir()?.join(".zsh_history"))
+    }
+
+    // Lines are plain, or ": <start>:<duration>;<command>" with
+    // EXTENDED_HISTORY. A trailing backslash continues the command.
+    fn parse_history(&self, content: &str) -> Vec<String> {
+        let mut commands = Vec::new();
+        let mut continued: Option<String> = None;
+        for line in content.lines() {
+            let line = match continued.take() {
+                Some(start) => format!("{}\n{}", start, line),
+                None => line
+                    .strip_prefix(": ")
+                    .and_then(|rest| rest.split_once(';'))
+                    .map_or(line, |(_, command)| command)
+                    .to_string(),
+            };
+            match line.strip_suffix('\\') {
+                Some(start) => continued = Some(start.to_string()),
+                None if !line.trim().is_empty() => commands.push(line),
+                None => {}
+            }
+        }
+        commands
     }
 
     fn parse_aliases_from_file(&self, path: &std::path::Path) -> Result<Vec<(String, String)>> {
@@ -169,4 +255,27 @@ mod tests {
         assert!(content.contains("alias ll='ls -la'"));
         assert!(content.contains("# List all files"));
     }
+
+    #[test]
+    fn test_parse_history() {
+        let history = ": 1700000000:0;git status\nls -la\n: 1700000001:3;echo a \\\nb\n\n";
+        assert_eq!(
+            ZshHandler::new().parse_history(history),
+            vec!["git status", "ls -la", "echo a \nb"]
+        );
+    }
+
+    #[test]
+    fn test_descriptions() {
+        let handler = ZshHandler::new();
+        let ll = Alias::new("ll".to_string(), "ls -la".to_string())
+            .with_description("Don't hide anything".to_string());
+        let gs = Alias::new("gs".to_string(), "git status".to_string());
+
+        assert_eq!(handler.descriptions(&[&gs]), "");
+        assert_eq!(
+            handler.descriptions(&[&ll, &gs]),
+            "# Descriptions for completion menus\nzstyle ':completion:*:aliases' fake 'll:Don'\\''t hide anything'\n"
+        );
+    }
 }
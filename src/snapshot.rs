@@ -0,0 +1,86 @@
+use crate::alias::validator::AliasValidator;
+use crate::error::{AlxError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Named copies of the aliases file that are never rotated away
+pub fn create(file: &Path, dir: &Path, name: &str, force: bool) -> Result<PathBuf> {
+    let path = snapshot_path(dir, name)?;
+    if path.exists() && !force {
+        return Err(AlxError::ConfigError(format!(
+            "Snapshot '{}' already exists (use --force to replace it)",
+            name
+        )));
+    }
+
+    fs::create_dir_all(dir)?;
+    if file.exists() {
+        fs::copy(file, &path)?;
+    } else {
+        fs::write(&path, "aliases = []\n")?;
+    }
+    Ok(path)
+}
+
+pub fn find(dir: &Path, name: &str) -> Result<PathBuf> {
+    let path = snapshot_path(dir, name)?;
+    if !path.exists() {
+        return Err(AlxError::ConfigError(format!(
+            "Snapshot not found: {} (run 'alx snapshot list' to see available snapshots)",
+            name
+        )));
+    }
+    Ok(path)
+}
+
+// Snapshot names, sorted
+pub fn list(dir: &Path) -> Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+            names.push(name.to_string());
+        }
+    }
+
+    names.sort();
+    Ok(names)
+}
+
+// Snapshot names follow the alias naming rules, which keeps them safe as file names
+fn snapshot_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    AliasValidator::validate_name(name)
+        .map_err(|_| AlxError::ConfigError(format!("Invalid snapshot name: {}", name)))?;
+    Ok(dir.join(format!("{}.toml", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_find_and_list() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("aliases.toml");
+        let dir = temp.path().join("snapshots");
+        fs::write(&file, "aliases = []\n").unwrap();
+
+        create(&file, &dir, "before-import", false).unwrap();
+        create(&file, &dir, "clean", false).unwrap();
+        assert!(create(&file, &dir, "clean", false).is_err());
+        assert!(create(&file, &dir, "clean", true).is_ok());
+        assert!(create(&file, &dir, "../escape", false).is_err());
+
+        assert_eq!(list(&dir).unwrap(), vec!["before-import", "clean"]);
+        assert!(find(&dir, "clean").is_ok());
+        assert!(find(&dir, "missing").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::alias::store::AliasStore;
+use std::collections::{HashMap, HashSet};
+
+// Commands are cut at this many words, so arguments that change from one
+// run to the next (paths, messages) don't split the count
+const MAX_WORDS: usize = 4;
+// Shorter commands aren't worth an alias
+const MIN_LENGTH: usize = 8;
+
+// A command from the shell history that an alias would shorten
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub name: String,
+    pub command: String,
+    pub count: usize,
+}
+
+impl Candidate {
+    // Keystrokes the alias would have saved over the whole history
+    pub fn saving(&self) -> usize {
+        self.count * self.command.len().saturating_sub(self.name.len())
+    }
+}
+
+// Commands typed at least `min_count` times in `history` that no alias in
+// `store` runs yet, most keystrokes saved first. Each gets the first name
+// from `names_for` that `available` accepts.
+pub fn candidates(
+    history: &[String],
+    store: &AliasStore,
+    min_count: usize,
+    available: impl Fn(&str) -> bool,
+) -> Vec<Candidate> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for line in history {
+        let words: Vec<&str> = line
+            .split_whitespace()
+            .take_while(|w| is_plain(w))
+            .take(MAX_WORDS)
+            .collect();
+        // Already typed through an alias
+        if words.first().is_none_or(|w| store.exists(w)) {
+            continue;
+        }
+        for end in 1..=words.len() {
+            *counts.entry(words[..end].join(" ")).or_default() += 1;
+        }
+    }
+
+    let covered: HashSet<String> = store
+        .list()
+        .iter()
+        .map(|a| a.command.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    let mut commands: Vec<(String, usize)> = counts
+        .iter()
+        .filter(|(command, count)| {
+            **count >= min_count
+                && command.len() >= MIN_LENGTH
+                && !covered.contains(*command)
+                // Always typed with more after it, so the longer one is the
+                // better alias
+                && !counts
+                    .iter()
+                    .any(|(other, n)| n == *count && is_extension(other, command))
+        })
+        .map(|(command, count)| (command.clone(), *count))
+        .collect();
+    commands.sort_by(|a, b| {
+        (b.1 * b.0.len())
+            .cmp(&(a.1 * a.0.len()))
+            .then(a.0.cmp(&b.0))
+    });
+
+    let mut taken = HashSet::new();
+    let mut candidates: Vec<Candidate> = commands
+        .into_iter()
+        .filter_map(|(command, count)| {
+            let name = names_for(&command)
+                .into_iter()
+                .find(|n| !taken.contains(n) && available(n))?;
+            taken.insert(name.clone());
+            Some(Candidate {
+                name,
+                command,
+                count,
+            })
+        })
+        .collect();
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.saving()));
+    candidates
+}
+
+// Names to try for an alias of `command`, best first: the initials of its
+// words (git commit -m -> gcm), then variants of those
+pub fn names_for(command: &str) -> Vec<String> {
+    let initials: String = command
+        .split_whitespace()
+        .filter_map(|w| {
+            w.trim_start_matches('-')
+                .chars()
+                .find(|c| c.is_ascii_alphanumeric())
+        })
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if initials.is_empty() || !initials.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Vec::new();
+    }
+
+    let mut names = Vec::new();
+    if initials.len() > 1 {
+        names.push(initials.clone());
+    }
+    names.extend(variants(&initials, command, None));
+    names
+}
+
+// Other names for an alias of `command` when `name` is taken: more letters of
+// the command's last word (gs -> gst), the group as a prefix (git-gs), then
+// numbers (gs2)
+pub fn variants(name: &str, command: &str, group: Option<&str>) -> Vec<String> {
+    let mut names = Vec::new();
+    let last = command
+        .split_whitespace()
+        .last()
+        .unwrap_or_default()
+        .trim_start_matches('-');
+    let letters: Vec<char> = last
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let mut longer = name.to_string();
+    // The name usually ends with the word's first letter already
+    let skip = usize::from(letters.first() == name.chars().last().as_ref());
+    for c in letters.iter().skip(skip).take(2) {
+        longer.push(*c);
+        names.push(longer.clone());
+    }
+    if let Some(group) = group {
+        names.push(format!("{}-{}", group, name));
+    }
+    names.extend((2..10).map(|n| format!("{}{}", name, n)));
+    names.retain(|n| n != name);
+    names
+}
+
+// A word an alias can hold on its own: no quotes, expansions or operators
+fn is_plain(word: &str) -> bool {
+    !word.contains([
+        '\'', '"', '`', '$', '|', '&', ';', '<', '>', '(', ')', '\\', '*',
+    ])
+}
+
+// Whether `longer` is `shorter` followed by more words
+fn is_extension(longer: &str, shorter: &str) -> bool {
+    longer
+        .strip_prefix(shorter)
+        .is_some_and(|rest| rest.starts_with(' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+
+    #[test]
+    fn test_candidates() {
+        let history: Vec<String> = [
+            "git commit -m 'one'",
+            "git commit -m 'two'",
+            "git commit -m 'three'",
+            "docker compose up -d",
+            "docker compose up -d",
+            "docker compose up -d",
+            "git status",
+            "git status",
+            "git status",
+            "gs",
+            "ls",
+            "ls",
+            "ls",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let store =
+            AliasStore::from_aliases(vec![Alias::new("gs".to_string(), "git status".to_string())]);
+
+        let found = candidates(&history, &store, 3, |name| name != "dcud");
+        let found: Vec<(&str, &str, usize)> = found
+            .iter()
+            .map(|c| (c.name.as_str(), c.command.as_str(), c.count))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("dcud2", "docker compose up -d", 3),
+                ("gcm", "git commit -m", 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(names_for("git commit -m")[..3], ["gcm", "gcm2", "gcm3"]);
+        assert_eq!(names_for("kubectl get pods")[..3], ["kgp", "kgpo", "kgpod"]);
+        assert_eq!(names_for("terraform")[..2], ["te", "ter"]);
+        assert!(names_for("7z x").is_empty());
+        assert_eq!(
+            variants("gs", "git stash", Some("git"))[..4],
+            ["gst", "gsta", "git-gs", "gs2"]
+        );
+    }
+}
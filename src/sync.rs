@@ -0,0 +1,833 @@
+use crate::alias::Alias;
+use crate::alias::filter::Filter;
+use crate::alias::store::AliasStore;
+use crate::config::manager::{self, ConfigManager};
+use crate::config::paths;
+use crate::error::{AlxError, Result};
+use crate::hooks;
+use crate::report;
+use crate::secrets::{self, Secret};
+use crate::shell::detector::ShellDetector;
+use crate::shell::registry;
+use crate::shell::{DESCRIPTIONS_HEADING, GroupScope, ShellHandler, ShellType};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Render the enabled aliases in `store` as a script for `shell_type`.
+///
+/// ```
+/// use alx::alias::Alias;
+/// use alx::alias::store::AliasStore;
+/// use alx::shell::ShellType;
+///
+/// let store = AliasStore::from_aliases(vec![Alias::new("ll".to_string(), "ls -la".to_string())]);
+/// let script = alx::sync::render_shell_aliases(&store, ShellType::Fish).unwrap();
+/// assert!(script.contains("alias ll 'ls -la'"));
+/// ```
+pub fn render_shell_aliases(store: &AliasStore, shell_type: ShellType) -> Result<String> {
+    let handler = registry::handler(shell_type);
+    let store = store.resolve_links()?;
+    let aliases: Vec<&Alias> = store.list().iter().filter(|a| a.enabled).collect();
+    let mut content = handler.generate_aliases_file(&aliases);
+    content.push_str(&handler.descriptions(&aliases));
+    Ok(content)
+}
+
+/// The enabled aliases of `store` that `keep` selects, as a script for
+/// `shell_type` to evaluate. Links and secrets are resolved as in the synced
+/// files, so a link keeps working when its target is left out.
+pub fn render_env(
+    store: &AliasStore,
+    shell_type: ShellType,
+    keep: impl Fn(&Alias) -> bool,
+) -> Result<String> {
+    let store = store.resolve_links()?;
+    let subset =
+        AliasStore::from_aliases(store.list().iter().filter(|a| keep(a)).cloned().collect());
+    let resolved = secrets::resolve_store(&subset, Secret::lookup)?;
+    let mut content = render(&resolved, shell_type, &RenderOptions::default())?;
+    // Non-interactive bash ignores aliases unless told otherwise
+    if shell_type == ShellType::Bash {
+        let split = content.find("\n\n").map_or(0, |i| i + 2);
+        content.insert_str(split, "shopt -s expand_aliases\n\n");
+    }
+    Ok(content)
+}
+
+// What goes into the synced files besides the aliases themselves
+#[derive(Default)]
+struct RenderOptions {
+    // Where the usage hook records aliases, when usage tracking is on
+    usage_log: Option<PathBuf>,
+    // Groups only defined inside these directories
+    scopes: BTreeMap<String, Vec<PathBuf>>,
+    // Aliases to remove from running shells, when emit_cleanup is on
+    cleanup: Vec<String>,
+    // Which aliases go into the files at all, per sync_exclude_groups and
+    // sync_exclude_tags
+    include: Filter,
+    // Define alx-reload, in files that get sourced rather than eval'd
+    reload: bool,
+}
+
+impl RenderOptions {
+    fn load(config_manager: &ConfigManager, store: &AliasStore) -> Result<Self> {
+        let config = config_manager.load_config()?;
+        let home = dirs::home_dir().unwrap_or_default();
+        let include = config.settings.sync_filter();
+        let cleanup = if config.settings.emit_cleanup {
+            let store = included(store, &include);
+            let state = SyncState::load(config_manager).next(&store, true);
+            state.removed.into_iter().collect()
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            cleanup,
+            include,
+            reload: true,
+            usage_log: config
+                .settings
+                .track_usage
+                .then(|| config_manager.usage_log()),
+            scopes: config
+                .scopes
+                .into_iter()
+                .map(|(group, dirs)| {
+                    let dirs = dirs.iter().map(|d| paths::expand_path(d, &home)).collect();
+                    (group, dirs)
+                })
+                .collect(),
+        })
+    }
+
+    fn is_scoped(&self, alias: &Alias) -> bool {
+        alias
+            .group
+            .as_deref()
+            .is_some_and(|g| self.scopes.contains_key(g))
+    }
+}
+
+// Which aliases the last sync wrote, so the next one can tell which are gone.
+// Kept next to the generated files as it describes this machine's shells.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct SyncState {
+    #[serde(default)]
+    synced: BTreeSet<String>,
+    // Synced at some point, and not enabled since
+    #[serde(default)]
+    removed: BTreeSet<String>,
+}
+
+impl SyncState {
+    fn path(config_manager: &ConfigManager) -> PathBuf {
+        config_manager.shell_dir().join("state.toml")
+    }
+
+    // A missing or unreadable state just means nothing is known to be removed
+    fn load(config_manager: &ConfigManager) -> Self {
+        config_manager
+            .read(&Self::path(config_manager))
+            .ok()
+            .flatten()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    // The state once `store` is synced. Removed names are only collected
+    // while `track_removed` is on, so turning emit_cleanup on later doesn't
+    // unalias names removed long ago.
+    fn next(&self, store: &AliasStore, track_removed: bool) -> Self {
+        let synced: BTreeSet<String> = store
+            .list()
+            .iter()
+            .filter(|a| a.enabled)
+            .map(|a| a.name.clone())
+            .collect();
+        let removed = if track_removed {
+            self.removed
+                .iter()
+                .chain(&self.synced)
+                .filter(|name| !synced.contains(*name))
+                .cloned()
+                .collect()
+        } else {
+            BTreeSet::new()
+        };
+        Self { synced, removed }
+    }
+
+    fn save_after_sync(config_manager: &ConfigManager, store: &AliasStore) -> Result<()> {
+        let track_removed = config_manager.load_config()?.settings.emit_cleanup;
+        let state = Self::load(config_manager).next(store, track_removed);
+        let content = toml::to_string(&state).map_err(|e| AlxError::ConfigError(e.to_string()))?;
+        config_manager.write(&Self::path(config_manager), &content)
+    }
+}
+
+// The aliases of `store` that `include` lets into the generated files. Links
+// must be resolved first so a link keeps working when its target is left out.
+fn included<'a>(store: &'a AliasStore, include: &Filter) -> Cow<'a, AliasStore> {
+    if store.list().iter().all(|a| include.matches(a)) {
+        return Cow::Borrowed(store);
+    }
+    let aliases = store.list().iter().filter(|a| include.matches(a));
+    Cow::Owned(AliasStore::from_aliases(aliases.cloned().collect()))
+}
+
+// The file as synced: the cleanup, usage hook and alx-reload after the header
+// when they are on, directory-scoped groups after the others, then the descriptions
+fn render(store: &AliasStore, shell_type: ShellType, options: &RenderOptions) -> Result<String> {
+    let handler = registry::handler(shell_type);
+    let (scoped, aliases): (Vec<&Alias>, Vec<&Alias>) = store
+        .list()
+        .iter()
+        .filter(|a| a.enabled)
+        .partition(|a| options.is_scoped(a));
+    let mut content = handler.generate_aliases_file(&aliases);
+
+    let split = content.find("\n\n").map_or(0, |i| i + 2);
+    if options.reload {
+        content.insert_str(split, &format!("{}\n", handler.reload_function()));
+    }
+    if let Some(log) = &options.usage_log {
+        let hook = handler.usage_hook(log);
+        content.insert_str(split, &format!("{}\n", hook));
+    }
+    if !options.cleanup.is_empty() {
+        let cleanup = handler.cleanup(&options.cleanup);
+        content.insert_str(split, &format!("{}\n", cleanup));
+    }
+
+    let scopes: Vec<GroupScope> = options
+        .scopes
+        .iter()
+        .map(|(group, dirs)| GroupScope {
+            dirs: dirs.clone(),
+            aliases: scoped
+                .iter()
+                .filter(|a| a.group.as_deref() == Some(group.as_str()))
+                .copied()
+                .collect(),
+        })
+        .filter(|scope| !scope.aliases.is_empty())
+        .collect();
+    if !scopes.is_empty() {
+        content.push_str(&handler.scope_hook(&scopes));
+    }
+    content.push_str(&handler.descriptions(&aliases));
+
+    Ok(content)
+}
+
+// The store as written to the shell files: links replaced by their targets'
+// commands, then secrets looked up
+fn resolve(store: &AliasStore) -> Result<Cow<'_, AliasStore>> {
+    match store.resolve_links()? {
+        Cow::Borrowed(store) => secrets::resolve_store(store, Secret::lookup),
+        Cow::Owned(store) => Ok(Cow::Owned(
+            secrets::resolve_store(&store, Secret::lookup)?.into_owned(),
+        )),
+    }
+}
+
+// The shell chosen during init or with `alx shell set`, falling back to
+// detection so scripts and cron jobs don't depend on $SHELL
+pub fn current_shell(config_manager: &ConfigManager) -> Result<ShellType> {
+    match config_manager.load_config()?.settings.default_shell {
+        Some(name) => ShellDetector::parse_shell_name(&name),
+        None => ShellDetector::detect(),
+    }
+}
+
+/// Regenerate the shell aliases file for the current shell and every other
+/// shell with its own output path, running the pre_sync and post_sync hooks
+/// around them.
+///
+/// ```no_run
+/// use alx::config::manager::ConfigManager;
+///
+/// let config_manager = ConfigManager::new()?;
+/// alx::sync::sync_aliases(&config_manager)?;
+/// # Ok::<(), alx::error::AlxError>(())
+/// ```
+pub fn sync_aliases(config_manager: &ConfigManager) -> Result<()> {
+    let store = config_manager.load_aliases()?;
+    let options = RenderOptions::load(config_manager, &store)?;
+    let resolved = resolve(&store)?;
+    let resolved = included(&resolved, &options.include);
+    let fingerprint = store_fingerprint(config_manager)?;
+    write_shell_aliases(config_manager, &fingerprint, |shell_type, _| {
+        render(&resolved, shell_type, &options)
+    })?;
+    SyncState::save_after_sync(config_manager, &included(&store, &options.include))
+}
+
+/// Update the shell aliases files after a change to the single alias `name`,
+/// which was `old` before the change and is looked up in `store` after it.
+/// Only the lines of that alias are rewritten; the whole file is regenerated
+/// when the existing file can't be patched.
+pub fn sync_alias(
+    config_manager: &ConfigManager,
+    store: &AliasStore,
+    name: &str,
+    old: Option<&Alias>,
+) -> Result<()> {
+    let options = RenderOptions::load(config_manager, store)?;
+    let resolved = resolve(store)?;
+    let resolved = included(&resolved, &options.include);
+    // Scoped groups live in the shell hook, which is always regenerated, and
+    // lines with resolved secrets or links can't be matched against the store.
+    // Aliases moving in or out of an excluded group or tag take a full render
+    // too.
+    let scoped = old.into_iter().chain(store.get(name)).any(|a| {
+        options.is_scoped(a)
+            || !options.include.matches(a)
+            || secrets::has_placeholders(&a.command)
+            || a.is_link()
+            || !store.links_to(&a.name).is_empty()
+    });
+    let store = included(store, &options.include);
+    let fingerprint = store_fingerprint(config_manager)?;
+    write_shell_aliases(config_manager, &fingerprint, |shell_type, path| {
+        let handler = registry::handler(shell_type);
+        let empty = render(&AliasStore::new(), shell_type, &options)?;
+        let patched = if scoped {
+            None
+        } else {
+            config_manager.read(path)?.and_then(|current| {
+                let current = without_fingerprint(&current);
+                patch_alias(current, &empty, handler.as_ref(), &store, name, old)
+            })
+        };
+        match patched {
+            Some(content) => Ok(content),
+            None => render(&resolved, shell_type, &options),
+        }
+    })?;
+    SyncState::save_after_sync(config_manager, &store)
+}
+
+/// The generated files to keep up to date: the current shell's, then those of
+/// other shells that have their own output path.
+pub fn sync_targets(config_manager: &ConfigManager) -> Result<Vec<(ShellType, PathBuf)>> {
+    let current = current_shell(config_manager)?;
+    let mut targets = vec![(current, config_manager.shell_aliases_file_for(current))];
+    for (shell_type, path) in config_manager.shell_outputs() {
+        if !targets.iter().any(|(s, p)| s == shell_type || p == path) {
+            targets.push((*shell_type, path.clone()));
+        }
+    }
+    Ok(targets)
+}
+
+// The fingerprint of the store file as it is now, which may have been edited
+// by hand since alx last saved it
+fn store_fingerprint(config_manager: &ConfigManager) -> Result<String> {
+    let content = config_manager
+        .read(config_manager.aliases_file())?
+        .unwrap_or_default();
+    Ok(manager::fingerprint(&content))
+}
+
+// A generated file without its fingerprint line
+fn without_fingerprint(content: &str) -> &str {
+    match content.split_once('\n') {
+        Some((first, rest)) if first.contains("__alx_fingerprint") => rest,
+        _ => content,
+    }
+}
+
+// Render and write every target in parallel between the hooks, which run
+// once for the whole batch. Each file starts with `fingerprint`, which is
+// also written where the prompt check looks for the store's.
+fn write_shell_aliases(
+    config_manager: &ConfigManager,
+    fingerprint: &str,
+    render: impl Fn(ShellType, &Path) -> Result<String> + Sync,
+) -> Result<()> {
+    let hooks = config_manager.load_config()?.hooks;
+    let targets = sync_targets(config_manager)?;
+
+    let shell_aliases_file = &targets[0].1;
+    if let Some(command) = &hooks.pre_sync {
+        hooks::run("pre_sync", command, shell_aliases_file)?;
+    }
+
+    let progress = report::sync_progress(targets.len());
+    let written = targets.par_iter().try_for_each(|(shell_type, path)| {
+        let shell = shell_type.as_str();
+        let line = registry::handler(*shell_type).fingerprint_line(fingerprint);
+        let result = render(*shell_type, path)
+            .and_then(|content| config_manager.write(path, &format!("{}{}", line, content)));
+        match &result {
+            Ok(()) => tracing::info!(shell, path = %path.display(), "synced shell aliases"),
+            Err(e) => tracing::error!(shell, path = %path.display(), "sync failed: {}", e),
+        }
+        progress.inc(1);
+        result
+    });
+    progress.finish_and_clear();
+    written?;
+    config_manager.write(&config_manager.store_fingerprint_file(), fingerprint)?;
+
+    if let Some(command) = &hooks.post_sync {
+        hooks::run("post_sync", command, shell_aliases_file)?;
+    }
+
+    Ok(())
+}
+
+// One group of the generated file: the aliases under its "# <group> aliases"
+// heading, each as its optional description comment and alias line
+struct Block {
+    group: String,
+    entries: Vec<Vec<String>>,
+}
+
+// Patch `current`, as generated for the old store, so that it matches what
+// would be rendered for `store`. `empty` is the rendering of an empty store.
+// Returns None when the file doesn't have the expected layout.
+fn patch_alias(
+    current: &str,
+    empty: &str,
+    handler: &dyn ShellHandler,
+    store: &AliasStore,
+    name: &str,
+    old: Option<&Alias>,
+) -> Option<String> {
+    // The header is the empty file without its "No aliases" line
+    let (header, no_aliases) = empty
+        .trim_end_matches('\n')
+        .rsplit_once('\n')
+        .map(|(header, last)| (format!("{}\n", header), format!("{}\n", last)))?;
+    // The descriptions are rendered again for the new store below
+    let body = current.strip_prefix(&header)?;
+    let body = match body.find(&format!("\n{}\n", DESCRIPTIONS_HEADING)) {
+        Some(end) => &body[..end + 1],
+        None => body,
+    };
+    let mut blocks = parse_blocks(body, &no_aliases)?;
+
+    let group_of = |alias: &Alias| alias.group.clone().unwrap_or_else(|| "general".to_string());
+    let entry = |alias: &Alias| {
+        let mut lines: Vec<String> = alias
+            .description
+            .iter()
+            .map(|d| format!("# {}", d))
+            .collect();
+        lines.push(handler.generate_alias_line(alias));
+        lines
+    };
+
+    let mut touched = Vec::new();
+    if let Some(old) = old.filter(|a| a.enabled) {
+        let group = group_of(old);
+        let index = blocks.binary_search_by(|b| b.group.cmp(&group)).ok()?;
+        let old_entry = entry(old);
+        let position = blocks[index].entries.iter().position(|e| *e == old_entry)?;
+        blocks[index].entries.remove(position);
+        touched.push(group);
+    }
+
+    if let Some(new) = store.get(name).filter(|a| a.enabled) {
+        let new_entry = entry(new);
+        if new_entry.iter().any(|line| line.contains('\n')) {
+            return None;
+        }
+
+        let group = group_of(new);
+        let index = match blocks.binary_search_by(|b| b.group.cmp(&group)) {
+            Ok(index) => index,
+            Err(index) => {
+                blocks.insert(
+                    index,
+                    Block {
+                        group: group.clone(),
+                        entries: Vec::new(),
+                    },
+                );
+                index
+            }
+        };
+        // Aliases keep their store order within a group
+        let position = store
+            .list()
+            .iter()
+            .filter(|a| a.enabled && group_of(a) == group)
+            .position(|a| a.name == name)?;
+        if position > blocks[index].entries.len() {
+            return None;
+        }
+        blocks[index].entries.insert(position, new_entry);
+        touched.push(group);
+    }
+
+    // Bail out if the file had drifted from the store
+    for group in &touched {
+        let expected = store
+            .list()
+            .iter()
+            .filter(|a| a.enabled && group_of(a) == *group)
+            .count();
+        let actual = blocks
+            .iter()
+            .find(|b| b.group == *group)
+            .map_or(0, |b| b.entries.len());
+        if actual != expected {
+            return None;
+        }
+    }
+    blocks.retain(|b| !b.entries.is_empty());
+
+    let mut content = header;
+    if blocks.is_empty() {
+        content.push_str(&no_aliases);
+    }
+    for block in blocks {
+        content.push_str(&format!("# {} aliases\n", block.group));
+        for line in block.entries.concat() {
+            content.push_str(&line);
+            content.push('\n');
+        }
+        content.push('\n');
+    }
+    let aliases: Vec<&Alias> = store.list().iter().filter(|a| a.enabled).collect();
+    content.push_str(&handler.descriptions(&aliases));
+    Some(content)
+}
+
+fn parse_blocks(body: &str, no_aliases: &str) -> Option<Vec<Block>> {
+    if body == no_aliases {
+        return Some(Vec::new());
+    }
+
+    let mut blocks = Vec::new();
+    let mut lines = body.strip_suffix('\n')?.split('\n');
+    while let Some(heading) = lines.next() {
+        let group = heading.strip_prefix("# ")?.strip_suffix(" aliases")?;
+        let mut entries = Vec::new();
+        loop {
+            let line = lines.next()?;
+            if line.is_empty() {
+                break;
+            }
+            if line.starts_with("# ") {
+                let alias_line = lines.next().filter(|l| !l.is_empty())?;
+                entries.push(vec![line.to_string(), alias_line.to_string()]);
+            } else {
+                entries.push(vec![line.to_string()]);
+            }
+        }
+        blocks.push(Block {
+            group: group.to_string(),
+            entries,
+        });
+    }
+    Some(blocks)
+}
+
+// Whether any generated shell file no longer matches the store
+pub fn shell_aliases_stale(config_manager: &ConfigManager) -> Result<bool> {
+    let store = config_manager.load_aliases()?;
+    // Can't tell without the secrets, e.g. when the password manager is locked
+    let Ok(resolved) = resolve(&store) else {
+        return Ok(true);
+    };
+    let options = RenderOptions::load(config_manager, &store)?;
+    let resolved = included(&resolved, &options.include);
+    let fingerprint = store_fingerprint(config_manager)?;
+    for (shell_type, path) in sync_targets(config_manager)? {
+        let line = registry::handler(shell_type).fingerprint_line(&fingerprint);
+        let expected = format!("{}{}", line, render(&resolved, shell_type, &options)?);
+        if config_manager.read(&path)?.unwrap_or_default() != expected {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// The generated file for the current shell
+pub fn current_shell_aliases_file(config_manager: &ConfigManager) -> PathBuf {
+    match current_shell(config_manager) {
+        Ok(shell_type) => config_manager.shell_aliases_file_for(shell_type),
+        Err(_) => config_manager.shell_aliases_file(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(name: &str, command: &str, group: Option<&str>) -> Alias {
+        let alias = Alias::new(name.to_string(), command.to_string());
+        match group {
+            Some(group) => alias.with_group(group.to_string()),
+            None => alias,
+        }
+    }
+
+    // Apply one change and check the patched file against a full render
+    fn check(before: &AliasStore, after: &AliasStore, name: &str) {
+        for shell_type in [ShellType::Bash, ShellType::Zsh, ShellType::Fish] {
+            let handler = registry::handler(shell_type);
+            let tracked = RenderOptions {
+                usage_log: Some(PathBuf::from("/tmp/usage.log")),
+                ..Default::default()
+            };
+            for options in [RenderOptions::default(), tracked] {
+                let empty = render(&AliasStore::new(), shell_type, &options).unwrap();
+                let current = render(before, shell_type, &options).unwrap();
+                let patched = patch_alias(
+                    &current,
+                    &empty,
+                    handler.as_ref(),
+                    after,
+                    name,
+                    before.get(name),
+                )
+                .unwrap();
+                assert_eq!(patched, render(after, shell_type, &options).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_patch_alias() {
+        let empty = AliasStore::from_aliases(Vec::new());
+        let one = AliasStore::from_aliases(vec![alias("gs", "git status", Some("git"))]);
+        check(&empty, &one, "gs");
+        check(&one, &empty, "gs");
+
+        let before = AliasStore::from_aliases(vec![
+            alias("gs", "git status", Some("git")),
+            alias("ll", "ls -la", None).with_description("Long listing".to_string()),
+            alias("gd", "git diff", Some("git")),
+        ]);
+
+        let mut added = before.clone();
+        added.add(alias("k", "kubectl", Some("k8s"))).unwrap();
+        check(&before, &added, "k");
+
+        let mut edited = before.clone();
+        edited.get_mut("gs").unwrap().command = "git status -sb".to_string();
+        edited.get_mut("gs").unwrap().description = Some("Short status".to_string());
+        check(&before, &edited, "gs");
+
+        let mut moved = before.clone();
+        moved.get_mut("gd").unwrap().group = None;
+        check(&before, &moved, "gd");
+
+        let mut disabled = before.clone();
+        disabled.get_mut("ll").unwrap().enabled = false;
+        check(&before, &disabled, "ll");
+
+        let mut removed = before.clone();
+        removed.remove("gs").unwrap();
+        check(&before, &removed, "gs");
+    }
+
+    #[test]
+    fn test_sync_state() {
+        let mut store = AliasStore::from_aliases(vec![
+            alias("gs", "git status", None),
+            alias("ll", "ls -la", None),
+        ]);
+        let state = SyncState::default().next(&store, true);
+        assert!(state.removed.is_empty());
+
+        store.remove("gs").unwrap();
+        store.get_mut("ll").unwrap().enabled = false;
+        let state = state.next(&store, true);
+        assert_eq!(state.removed, BTreeSet::from(["gs".into(), "ll".into()]));
+        // Still removed on later syncs, until the alias comes back
+        let state = state.next(&store, true);
+        assert_eq!(state.removed.len(), 2);
+        store.get_mut("ll").unwrap().enabled = true;
+        let state = state.next(&store, true);
+        assert_eq!(state.removed, BTreeSet::from(["gs".into()]));
+        assert!(state.next(&store, false).removed.is_empty());
+
+        let options = RenderOptions {
+            cleanup: vec!["gs".to_string()],
+            ..Default::default()
+        };
+        let bash = render(&store, ShellType::Bash, &options).unwrap();
+        assert!(bash.contains("\nunalias gs 2>/dev/null\n"));
+        let fish = render(&store, ShellType::Fish, &options).unwrap();
+        assert!(fish.contains("\nfunctions -e gs\n"));
+    }
+
+    #[test]
+    fn test_render_env() {
+        let store = AliasStore::from_aliases(vec![
+            alias("gs", "git status", Some("git")),
+            Alias::link("ci-status".to_string(), "gs".to_string()).with_group("ci".to_string()),
+        ]);
+        let script = render_env(&store, ShellType::Bash, |a| {
+            a.group.as_deref() == Some("ci")
+        })
+        .unwrap();
+        assert!(script.contains("shopt -s expand_aliases\n"));
+        assert!(script.contains("alias ci-status='git status'\n"));
+        assert!(!script.contains("alias gs="));
+        // Nothing to source again in an eval'd script
+        assert!(!script.contains("alx-reload"));
+    }
+
+    #[test]
+    fn test_render_reload() {
+        let store = AliasStore::from_aliases(vec![alias("ll", "ls -la", None)]);
+        let options = RenderOptions {
+            reload: true,
+            ..Default::default()
+        };
+        for shell in [ShellType::Bash, ShellType::Zsh, ShellType::Fish] {
+            let content = render(&store, shell, &options).unwrap();
+            let reload = content.find("alx-reload").unwrap();
+            assert!(reload < content.find("alias ll").unwrap());
+        }
+    }
+
+    #[test]
+    fn test_included() {
+        let store = AliasStore::from_aliases(vec![
+            alias("ll", "ls -la", None),
+            alias("vpn", "vpn connect", Some("work")),
+            Alias::link("v".to_string(), "vpn".to_string()),
+        ]);
+        assert!(matches!(included(&store, &Filter::new()), Cow::Borrowed(_)));
+
+        let resolved = resolve(&store).unwrap();
+        let synced = included(&resolved, &Filter::new().without_group("work"));
+        let names: Vec<&str> = synced.list().iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["ll", "v"]);
+        let bash = render(&synced, ShellType::Bash, &RenderOptions::default()).unwrap();
+        assert!(bash.contains("alias v='vpn connect'\n"));
+        assert!(!bash.contains("# work aliases"));
+    }
+
+    #[test]
+    fn test_render_scopes() {
+        let store = AliasStore::from_aliases(vec![
+            alias("ll", "ls -la", None),
+            alias("k", "kubectl", Some("k8s")),
+        ]);
+        let options = RenderOptions {
+            scopes: BTreeMap::from([
+                ("k8s".to_string(), vec![PathBuf::from("/work/infra")]),
+                ("empty".to_string(), vec![PathBuf::from("/tmp")]),
+            ]),
+            ..Default::default()
+        };
+
+        for shell_type in [ShellType::Bash, ShellType::Zsh, ShellType::Fish] {
+            let content = render(&store, shell_type, &options).unwrap();
+            assert!(!content.contains("# k8s aliases"));
+            assert!(content.contains("/work/infra/"));
+            assert!(!content.contains("/tmp/"));
+        }
+        let bash = render(&store, ShellType::Bash, &options).unwrap();
+        assert!(bash.contains("    /work/infra/*)\n      alias k='kubectl'\n"));
+        assert!(bash.contains("unalias k 2>/dev/null"));
+    }
+
+    #[test]
+    fn test_sync_targets() {
+        let mut config = crate::config::Config::default();
+        config.settings.default_shell = Some("zsh".to_string());
+        config.settings.bash_output_path = Some("shell/aliases.sh".to_string());
+        config.settings.fish_output_path = Some("fish/aliases.fish".to_string());
+        let config_manager = ConfigManager::in_memory(config).unwrap();
+        let dir = config_manager.config_dir().clone();
+
+        // bash shares the zsh file, so only fish is added
+        assert_eq!(
+            sync_targets(&config_manager).unwrap(),
+            vec![
+                (ShellType::Zsh, dir.join("shell/aliases.sh")),
+                (ShellType::Fish, dir.join("fish/aliases.fish")),
+            ]
+        );
+
+        let store = AliasStore::from_aliases(vec![alias("gs", "git status", None)]);
+        config_manager.save_aliases(&store).unwrap();
+        assert!(shell_aliases_stale(&config_manager).unwrap());
+        sync_aliases(&config_manager).unwrap();
+        assert!(!shell_aliases_stale(&config_manager).unwrap());
+
+        let fish = config_manager
+            .read(&dir.join("fish/aliases.fish"))
+            .unwrap()
+            .unwrap();
+        assert!(fish.contains("alias gs 'git status'"));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let mut config = crate::config::Config::default();
+        config.settings.default_shell = Some("fish".to_string());
+        let config_manager = ConfigManager::in_memory(config).unwrap();
+        let file = config_manager.shell_aliases_file_for(ShellType::Fish);
+        let fingerprint = || {
+            config_manager
+                .read(&config_manager.store_fingerprint_file())
+                .unwrap()
+                .unwrap()
+        };
+
+        let mut store = AliasStore::from_aliases(vec![alias("gs", "git status", None)]);
+        config_manager.save_aliases(&store).unwrap();
+        let saved = fingerprint();
+        sync_aliases(&config_manager).unwrap();
+        let content = config_manager.read(&file).unwrap().unwrap();
+        assert!(content.starts_with(&format!("set -g __alx_fingerprint {}\n", saved)));
+
+        // A single-alias sync patches the file below a new fingerprint line
+        store.add(alias("ll", "ls -la", None)).unwrap();
+        config_manager.save_aliases(&store).unwrap();
+        assert_ne!(fingerprint(), saved);
+        assert!(shell_aliases_stale(&config_manager).unwrap());
+        sync_alias(&config_manager, &store, "ll", None).unwrap();
+        assert!(!shell_aliases_stale(&config_manager).unwrap());
+        let content = config_manager.read(&file).unwrap().unwrap();
+        assert!(content.starts_with(&format!("set -g __alx_fingerprint {}\n", fingerprint())));
+    }
+
+    #[test]
+    fn test_patch_alias_falls_back() {
+        let store = AliasStore::from_aliases(vec![alias("ll", "ls -la", None)]);
+        let handler = registry::handler(ShellType::Bash);
+        let empty = render_shell_aliases(&AliasStore::new(), ShellType::Bash).unwrap();
+        let old = alias("gone", "true", None);
+        let patch = |current: &str, name: &str, old: Option<&Alias>| {
+            patch_alias(current, &empty, handler.as_ref(), &store, name, old)
+        };
+
+        assert!(patch("", "ll", None).is_none());
+        // The old alias isn't in the file, so it has drifted from the store
+        let current = render_shell_aliases(&store, ShellType::Bash).unwrap();
+        assert!(patch(&current, "gone", Some(&old)).is_none());
+        assert!(patch(&current, "ll", None).is_none());
+        // Turning usage tracking on changes the header
+        let options = RenderOptions {
+            usage_log: Some(PathBuf::from("/tmp/usage.log")),
+            ..Default::default()
+        };
+        let tracked = render(&store, ShellType::Bash, &options).unwrap();
+        assert!(patch(&tracked, "ll", Some(&store.aliases[0])).is_none());
+        // Scoped groups follow the groups, so the file can't be parsed
+        let options = RenderOptions {
+            scopes: BTreeMap::from([("k8s".to_string(), vec![PathBuf::from("/work/infra")])]),
+            ..Default::default()
+        };
+        let mut scoped = store.clone();
+        scoped.add(alias("k", "kubectl", Some("k8s"))).unwrap();
+        let current = render(&scoped, ShellType::Bash, &options).unwrap();
+        assert!(patch(&current, "ll", Some(&store.aliases[0])).is_none());
+    }
+}
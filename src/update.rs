@@ -0,0 +1,185 @@
+use crate::error::{AlxError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const RELEASES_URL: &str = "https://api.github.com/repos/hiro08gh/alx/releases/latest";
+const USER_AGENT: &str = concat!("alx/", env!("CARGO_PKG_VERSION"));
+// The passive check asks GitHub at most this often
+const CHECK_INTERVAL_HOURS: i64 = 24;
+
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    // The binary built for this platform, named alx-<os>-<arch>[.exe]
+    pub fn asset(&self) -> Option<&Asset> {
+        let name = asset_name();
+        self.assets.iter().find(|a| a.name == name)
+    }
+}
+
+pub fn asset_name() -> String {
+    format!(
+        "alx-{}-{}{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+pub fn latest(timeout: Option<std::time::Duration>) -> Result<Release> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(timeout)
+        .build()
+        .into();
+    agent
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", USER_AGENT)
+        .call()
+        .and_then(|mut r| r.body_mut().read_json())
+        .map_err(|e| AlxError::NetworkError(format!("Failed to look up the latest release: {}", e)))
+}
+
+// Whether `latest` is a higher major.minor.patch than `current`. Versions
+// that don't parse are never newer.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim_start_matches('v');
+    // Pre-release and build suffixes are ignored
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().unwrap_or(Some(0))?,
+    ))
+}
+
+// Download `asset` next to the running binary, then move it over the binary
+// in one rename so an interrupted update leaves the old one in place
+pub fn install(asset: &Asset) -> Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let exe = fs::canonicalize(&exe).unwrap_or(exe);
+    let dir = exe
+        .parent()
+        .ok_or_else(|| AlxError::ConfigError("Can't locate the alx binary".to_string()))?;
+    let download = dir.join(format!(".{}.download", asset.name));
+
+    let result =
+        download_to(&asset.browser_download_url, &download).and_then(|()| replace(&download, &exe));
+    if result.is_err() {
+        let _ = fs::remove_file(&download);
+    }
+    result.map(|()| exe)
+}
+
+fn download_to(url: &str, path: &Path) -> Result<()> {
+    let mut response = ureq::get(url)
+        .header("User-Agent", USER_AGENT)
+        .call()
+        .map_err(|e| AlxError::NetworkError(format!("Failed to download {}: {}", url, e)))?;
+    let mut file = fs::File::create(path)?;
+    io::copy(&mut response.body_mut().as_reader(), &mut file)?;
+    file.sync_all()?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn replace(new: &Path, exe: &Path) -> Result<()> {
+    fs::rename(new, exe)?;
+    Ok(())
+}
+
+// Windows can't overwrite a running executable, but it can rename it
+#[cfg(windows)]
+fn replace(new: &Path, exe: &Path) -> Result<()> {
+    let old = exe.with_extension("old.exe");
+    let _ = fs::remove_file(&old);
+    fs::rename(exe, &old)?;
+    if let Err(e) = fs::rename(new, exe) {
+        let _ = fs::rename(&old, exe);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+// When the passive check last asked GitHub, and what it found
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCheck {
+    pub checked_at: DateTime<Utc>,
+    pub latest: Option<String>,
+}
+
+impl UpdateCheck {
+    // Kept in the cache directory, away from a config directory that may be
+    // under git
+    fn path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("alx").join("update-check.toml"))
+    }
+
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(Self::path()?).ok()?;
+        toml::from_str(&content).ok()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = toml::to_string(self).map_err(|e| AlxError::ConfigError(e.to_string()))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        now - self.checked_at >= Duration::hours(CHECK_INTERVAL_HOURS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.5.0", "0.4.0"));
+        assert!(is_newer("0.10.0", "0.9.3"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("v0.4.0", "0.4.0"));
+        assert!(!is_newer("0.4.1-rc.1", "0.4.1"));
+        assert!(!is_newer("nightly", "0.4.0"));
+    }
+}
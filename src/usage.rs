@@ -0,0 +1,216 @@
+use crate::error::{AlxError, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+// Daily counts older than this are dropped when rolling up; last-used times
+// are kept
+const RETENTION_DAYS: i64 = 400;
+
+// One alias invocation recorded by the shell hook
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub time: DateTime<Utc>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Usage {
+    // Uses since the cutoff passed to `summarize`
+    pub count: usize,
+    // Most recent use, including before the cutoff
+    pub last_used: DateTime<Utc>,
+}
+
+// Read the log written by the shell hook, one "<unix time> <alias>" line per
+// use. Lines that don't parse, e.g. after a partial write, are skipped.
+pub fn read(path: &Path) -> Result<Vec<Event>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(content.lines().filter_map(parse_line).collect())
+}
+
+fn parse_line(line: &str) -> Option<Event> {
+    let (time, name) = line.trim().split_once(' ')?;
+    let time = DateTime::from_timestamp(time.parse().ok()?, 0)?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| Event {
+        time,
+        name: name.to_string(),
+    })
+}
+
+// Usage rolled up from the log: uses per alias per UTC day, so the file stays
+// small however long tracking has been on
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(default)]
+    pub aliases: BTreeMap<String, AliasStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasStats {
+    pub last_used: DateTime<Utc>,
+    #[serde(default)]
+    pub days: BTreeMap<NaiveDate, usize>,
+}
+
+impl Stats {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| AlxError::ConfigError(format!("{:?} is invalid: {}", path, e.message())))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string(self).map_err(|e| AlxError::ConfigError(e.to_string()))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, events: &[Event]) {
+        for event in events {
+            let stats = self
+                .aliases
+                .entry(event.name.clone())
+                .or_insert(AliasStats {
+                    last_used: event.time,
+                    days: BTreeMap::new(),
+                });
+            *stats.days.entry(event.time.date_naive()).or_default() += 1;
+            stats.last_used = stats.last_used.max(event.time);
+        }
+    }
+
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        let oldest = (now - Duration::days(RETENTION_DAYS)).date_naive();
+        for stats in self.aliases.values_mut() {
+            stats.days.retain(|day, _| *day >= oldest);
+        }
+    }
+
+    pub fn last_used(&self) -> HashMap<String, DateTime<Utc>> {
+        self.aliases
+            .iter()
+            .map(|(name, stats)| (name.clone(), stats.last_used))
+            .collect()
+    }
+
+    // Uses per alias on the days from `since` on. Counts are per day, so the
+    // whole day `since` falls on is included.
+    pub fn summarize(&self, since: DateTime<Utc>) -> HashMap<String, Usage> {
+        let since = since.date_naive();
+        self.aliases
+            .iter()
+            .map(|(name, stats)| {
+                let usage = Usage {
+                    count: stats.days.range(since..).map(|(_, n)| n).sum(),
+                    last_used: stats.last_used,
+                };
+                (name.clone(), usage)
+            })
+            .collect()
+    }
+}
+
+// Move the events in the hook's log into the stats file and return the
+// stats. The log is renamed before it's read, so uses logged meanwhile start
+// a new log instead of being lost.
+pub fn roll_up(log: &Path, stats_path: &Path, now: DateTime<Utc>) -> Result<Stats> {
+    let mut stats = Stats::load(stats_path)?;
+    // Left behind by a roll-up that failed before saving; the log waits
+    // until it's done
+    let rolling = log.with_extension("log.rolling");
+    if !rolling.exists() {
+        if !log.exists() {
+            return Ok(stats);
+        }
+        fs::rename(log, &rolling)?;
+    }
+
+    stats.record(&read(&rolling)?);
+    stats.prune(now);
+    stats.save(stats_path)?;
+    fs::remove_file(&rolling)?;
+    Ok(stats)
+}
+
+// Parse an age such as 12h, 30d or 8w
+pub fn parse_age(value: &str) -> Result<Duration> {
+    let invalid = || {
+        AlxError::ConfigError(format!(
+            "Invalid age: {} (expected a number followed by h, d or w, e.g. 30d)",
+            value
+        ))
+    };
+
+    let split = value.len().saturating_sub(1);
+    let (number, unit) = (value.get(..split).ok_or_else(invalid)?, &value[split..]);
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+    match unit {
+        "h" => Duration::try_hours(number),
+        "d" => Duration::try_days(number),
+        "w" => Duration::try_weeks(number),
+        _ => None,
+    }
+    .filter(|_| number >= 0)
+    .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_roll_up() {
+        const DAY: i64 = 86400;
+        let temp = TempDir::new().unwrap();
+        let log = temp.path().join("usage.log");
+        let stats_path = temp.path().join("usage.toml");
+        let now = DateTime::from_timestamp(500 * DAY, 0).unwrap();
+        assert!(read(&log).unwrap().is_empty());
+
+        let lines = format!(
+            "{} gs\n{} gs\n{} ll\ngarbage\n400\n{} gs\n",
+            10 * DAY,
+            498 * DAY,
+            498 * DAY + 60,
+            499 * DAY
+        );
+        fs::write(&log, lines).unwrap();
+        assert_eq!(read(&log).unwrap().len(), 4);
+
+        let stats = roll_up(&log, &stats_path, now).unwrap();
+        assert!(!log.exists());
+        // Day 10 is past the retention period
+        assert_eq!(stats.aliases["gs"].days.len(), 2);
+        assert_eq!(stats, Stats::load(&stats_path).unwrap());
+
+        fs::write(&log, format!("{} gs\n", 499 * DAY + 60)).unwrap();
+        let stats = roll_up(&log, &stats_path, now).unwrap();
+        let usage = stats.summarize(DateTime::from_timestamp(499 * DAY + 3600, 0).unwrap());
+        assert_eq!(usage["gs"].count, 2);
+        assert_eq!(usage["gs"].last_used.timestamp(), 499 * DAY + 60);
+        assert_eq!(usage["ll"].count, 0);
+        assert!(!usage.contains_key("k"));
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_age("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_age("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("-1d").is_err());
+        assert!(parse_age("").is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::{AlxError, Result};
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{DebouncedEvent, new_debouncer};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+// Call `on_change` with the changed files each time one of `files` changes.
+// Runs until the watcher fails or `on_change` returns an error.
+pub fn watch(files: &[PathBuf], mut on_change: impl FnMut(&[PathBuf]) -> Result<()>) -> Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(DEBOUNCE, tx).map_err(watch_error)?;
+
+    // Editors often save by renaming a new file over the old one, so watch the
+    // parent directories rather than the files themselves
+    let dirs: BTreeSet<&Path> = files.iter().filter_map(|f| f.parent()).collect();
+    for dir in dirs {
+        debouncer
+            .watcher()
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+    }
+
+    // Reads produce events too, including those done by `on_change` itself,
+    // so only react when a file's size or modification time moved
+    let mut stamps: HashMap<PathBuf, Option<Stamp>> =
+        files.iter().map(|f| (f.clone(), stamp(f))).collect();
+
+    for result in rx {
+        let mut changed = changed_files(&result.map_err(watch_error)?, files);
+        changed.retain(|file| {
+            let new = stamp(file);
+            stamps.insert(file.clone(), new) != Some(new)
+        });
+        if !changed.is_empty() {
+            on_change(&changed)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn changed_files(events: &[DebouncedEvent], files: &[PathBuf]) -> Vec<PathBuf> {
+    files
+        .iter()
+        .filter(|file| events.iter().any(|e| e.path == **file))
+        .cloned()
+        .collect()
+}
+
+type Stamp = (SystemTime, u64);
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn watch_error(e: notify_debouncer_mini::notify::Error) -> AlxError {
+    AlxError::IoError(std::io::Error::other(format!("File watcher failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify_debouncer_mini::DebouncedEventKind;
+
+    #[test]
+    fn test_changed_files() {
+        let files = vec![
+            PathBuf::from("/c/aliases.toml"),
+            PathBuf::from("/c/config.toml"),
+        ];
+        let event = |path: &str| DebouncedEvent {
+            path: PathBuf::from(path),
+            kind: DebouncedEventKind::Any,
+        };
+
+        let events = vec![event("/c/aliases.toml"), event("/c/.aliases.toml.swp")];
+        assert_eq!(changed_files(&events, &files), vec![files[0].clone()]);
+        assert!(changed_files(&[event("/c/shell/aliases.sh")], &files).is_empty());
+    }
+}